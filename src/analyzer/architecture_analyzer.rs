@@ -0,0 +1,120 @@
+use hakana_reflection_info::code_location::{FilePath, HPos};
+use hakana_reflection_info::function_context::FunctionContext;
+use hakana_reflection_info::issue::{Issue, IssueKind};
+use hakana_reflection_info::t_union::TUnion;
+use hakana_reflection_info::StrId;
+
+use crate::function_analysis_data::FunctionAnalysisData;
+use crate::scope_analyzer::ScopeAnalyzer;
+use crate::statements_analyzer::StatementsAnalyzer;
+
+/// Checks a reference to a symbol, or to a class member if the second part isn't empty,
+/// against the architecture rules in the config
+pub(crate) fn check_reference(
+    statements_analyzer: &StatementsAnalyzer,
+    symbol: (StrId, StrId),
+    pos: HPos,
+    analysis_data: &mut FunctionAnalysisData,
+    function_context: &FunctionContext,
+) {
+    let config = statements_analyzer.get_config();
+
+    if config.architecture_rules.is_empty() {
+        return;
+    }
+
+    let interner = statements_analyzer.get_interner();
+
+    let referencing_name =
+        if let Some(calling_functionlike_id) = &function_context.calling_functionlike_id {
+            calling_functionlike_id.to_string(interner)
+        } else if let Some(calling_class) = &function_context.calling_class {
+            interner.lookup(calling_class).to_string()
+        } else {
+            return;
+        };
+
+    let symbol_name = if symbol.1 == StrId::EMPTY {
+        interner.lookup(&symbol.0).to_string()
+    } else {
+        format!(
+            "{}::{}",
+            interner.lookup(&symbol.0),
+            interner.lookup(&symbol.1)
+        )
+    };
+
+    // members are only disallowed by name, since their class is checked wherever it's named
+    let declaring_file = if symbol.1 == StrId::EMPTY {
+        get_declaring_file(statements_analyzer, &symbol.0)
+            .map(|declaring_file| interner.lookup(&declaring_file.0))
+    } else {
+        None
+    };
+
+    let file_path = statements_analyzer.get_file_path_actual();
+
+    for rule in &config.architecture_rules {
+        if !rule.applies_to_file(file_path) || !rule.disallows_symbol(&symbol_name, declaring_file)
+        {
+            continue;
+        }
+
+        analysis_data.maybe_add_issue(
+            Issue::new(
+                IssueKind::ArchitectureViolation,
+                format!(
+                    "{} cannot reference {}, as this breaks the architecture rule \"{}\"",
+                    referencing_name, symbol_name, rule.name
+                ),
+                pos,
+                &function_context.calling_functionlike_id,
+            ),
+            config,
+            file_path,
+        );
+    }
+}
+
+/// Checks the classes referenced by a type hint
+pub(crate) fn check_type_hint(
+    statements_analyzer: &StatementsAnalyzer,
+    type_hint: &TUnion,
+    pos: HPos,
+    analysis_data: &mut FunctionAnalysisData,
+    function_context: &FunctionContext,
+) {
+    for name in type_hint.get_referenced_type_names() {
+        check_reference(
+            statements_analyzer,
+            (name, StrId::EMPTY),
+            pos,
+            analysis_data,
+            function_context,
+        );
+    }
+}
+
+fn get_declaring_file(
+    statements_analyzer: &StatementsAnalyzer,
+    symbol: &StrId,
+) -> Option<FilePath> {
+    let codebase = statements_analyzer.get_codebase();
+
+    if let Some(functionlike_info) = codebase.functionlike_infos.get(&(*symbol, StrId::EMPTY)) {
+        return Some(functionlike_info.def_location.file_path);
+    }
+
+    if let Some(classlike_info) = codebase.classlike_infos.get(symbol) {
+        return Some(classlike_info.name_location.file_path);
+    }
+
+    if let Some(type_definition_info) = codebase.type_definitions.get(symbol) {
+        return Some(type_definition_info.location.file_path);
+    }
+
+    codebase
+        .constant_infos
+        .get(symbol)
+        .map(|constant_info| constant_info.pos.file_path)
+}
@@ -0,0 +1,222 @@
+use hakana_reflection_info::analysis_result::Replacement;
+use hakana_reflection_info::codebase_info::CodebaseInfo;
+use hakana_reflection_info::function_context::FunctionContext;
+use hakana_reflection_info::functionlike_identifier::FunctionLikeIdentifier;
+use hakana_reflection_info::functionlike_info::FunctionLikeInfo;
+use hakana_reflection_info::issue::{Issue, IssueKind};
+use hakana_reflection_info::method_identifier::MethodIdentifier;
+use hakana_reflection_info::t_union::TUnion;
+use hakana_reflection_info::StrId;
+use hakana_type::type_comparator::type_comparison_result::TypeComparisonResult;
+use hakana_type::type_comparator::union_type_comparator;
+use oxidized::pos::Pos;
+
+use crate::function_analysis_data::FunctionAnalysisData;
+use crate::scope_analyzer::ScopeAnalyzer;
+use crate::statements_analyzer::StatementsAnalyzer;
+
+/// Checks a call against the `banned_functions` config. Methods can be banned either
+/// on the class they're called on or on the class that declares them.
+pub(crate) fn check_call(
+    statements_analyzer: &StatementsAnalyzer,
+    functionlike_id: &FunctionLikeIdentifier,
+    name_pos: &Pos,
+    analysis_data: &mut FunctionAnalysisData,
+    function_context: &FunctionContext,
+) {
+    let config = statements_analyzer.get_config();
+
+    if config.banned_functions.is_empty() {
+        return;
+    }
+
+    let codebase = statements_analyzer.get_codebase();
+    let interner = statements_analyzer.get_interner();
+
+    let mut candidate_ids = vec![*functionlike_id];
+
+    if let FunctionLikeIdentifier::Method(classlike_name, method_name) = functionlike_id {
+        let declaring_method_id =
+            codebase.get_declaring_method_id(&MethodIdentifier(*classlike_name, *method_name));
+
+        if declaring_method_id.0 != *classlike_name {
+            candidate_ids.push(FunctionLikeIdentifier::Method(
+                declaring_method_id.0,
+                declaring_method_id.1,
+            ));
+        }
+    }
+
+    let (banned_id, banned_function) = if let Some(banned) =
+        candidate_ids.into_iter().find_map(|candidate_id| {
+            config
+                .banned_functions
+                .get(&candidate_id.to_string(interner))
+                .map(|banned_function| (candidate_id, banned_function))
+        }) {
+        banned
+    } else {
+        return;
+    };
+
+    let issue = Issue::new(
+        IssueKind::BannedFunction,
+        format!(
+            "{} {} is banned: {}",
+            match banned_id {
+                FunctionLikeIdentifier::Function(_) => "Function",
+                FunctionLikeIdentifier::Method(_, _) => "Method",
+            },
+            banned_id.to_string(interner),
+            banned_function.message
+        ),
+        statements_analyzer.get_hpos(name_pos),
+        &function_context.calling_functionlike_id,
+    );
+
+    if config.issues_to_fix.contains(&issue.kind) && !config.add_fixmes {
+        // Only replace code that's not already covered by a FIXME
+        if analysis_data.get_matching_hakana_fixme(&issue).is_none() {
+            let replacement_name = banned_function
+                .replacement
+                .as_ref()
+                .and_then(|replacement| {
+                    get_replacement_name(statements_analyzer, &banned_id, replacement)
+                });
+
+            if let Some(replacement_name) = replacement_name {
+                analysis_data.add_replacement(
+                    (name_pos.start_offset() as u32, name_pos.end_offset() as u32),
+                    Replacement::Substitute(replacement_name),
+                );
+
+                return;
+            }
+        }
+    }
+
+    analysis_data.maybe_add_issue(
+        issue,
+        statements_analyzer.get_config(),
+        statements_analyzer.get_file_path_actual(),
+    );
+}
+
+/// Gets the name to substitute at the call site, as long as the replacement can be
+/// called in exactly the same way as the banned function or method
+fn get_replacement_name(
+    statements_analyzer: &StatementsAnalyzer,
+    banned_id: &FunctionLikeIdentifier,
+    replacement: &str,
+) -> Option<String> {
+    let codebase = statements_analyzer.get_codebase();
+    let interner = statements_analyzer.get_interner();
+
+    let (banned_storage, replacement_storage, replacement_name) = match banned_id {
+        FunctionLikeIdentifier::Function(function_name) => {
+            let replacement_id = interner.get(replacement)?;
+
+            (
+                codebase
+                    .functionlike_infos
+                    .get(&(*function_name, StrId::EMPTY))?,
+                codebase
+                    .functionlike_infos
+                    .get(&(replacement_id, StrId::EMPTY))?,
+                format!("\\{}", replacement),
+            )
+        }
+        FunctionLikeIdentifier::Method(classlike_name, method_name) => {
+            // we only swap the method name, so the replacement has to be on the same class
+            let (replacement_classlike_name, replacement_method_name) =
+                replacement.split_once("::")?;
+
+            if replacement_classlike_name != interner.lookup(classlike_name) {
+                return None;
+            }
+
+            let replacement_method_id = codebase.get_declaring_method_id(&MethodIdentifier(
+                *classlike_name,
+                interner.get(replacement_method_name)?,
+            ));
+
+            (
+                codebase.get_method(&MethodIdentifier(*classlike_name, *method_name))?,
+                codebase.get_method(&replacement_method_id)?,
+                replacement_method_name.to_string(),
+            )
+        }
+    };
+
+    if signatures_are_compatible(codebase, banned_storage, replacement_storage) {
+        Some(replacement_name)
+    } else {
+        None
+    }
+}
+
+fn signatures_are_compatible(
+    codebase: &CodebaseInfo,
+    banned_storage: &FunctionLikeInfo,
+    replacement_storage: &FunctionLikeInfo,
+) -> bool {
+    if banned_storage.params.len() != replacement_storage.params.len()
+        || banned_storage.is_async != replacement_storage.is_async
+        || banned_storage.method_info.as_ref().map(|m| m.is_static)
+            != replacement_storage
+                .method_info
+                .as_ref()
+                .map(|m| m.is_static)
+    {
+        return false;
+    }
+
+    for (banned_param, replacement_param) in banned_storage
+        .params
+        .iter()
+        .zip(replacement_storage.params.iter())
+    {
+        if banned_param.is_inout != replacement_param.is_inout
+            || banned_param.is_variadic != replacement_param.is_variadic
+            || (banned_param.is_optional && !replacement_param.is_optional)
+        {
+            return false;
+        }
+
+        // the replacement has to accept everything the banned function accepts
+        if !is_type_compatible(
+            codebase,
+            &banned_param.signature_type,
+            &replacement_param.signature_type,
+        ) {
+            return false;
+        }
+    }
+
+    // and it can't return anything the banned function wouldn't
+    is_type_compatible(
+        codebase,
+        &replacement_storage.return_type,
+        &banned_storage.return_type,
+    )
+}
+
+fn is_type_compatible(
+    codebase: &CodebaseInfo,
+    input_type: &Option<TUnion>,
+    container_type: &Option<TUnion>,
+) -> bool {
+    match (input_type, container_type) {
+        (_, None) => true,
+        (None, Some(_)) => false,
+        (Some(input_type), Some(container_type)) => union_type_comparator::is_contained_by(
+            codebase,
+            input_type,
+            container_type,
+            false,
+            false,
+            false,
+            &mut TypeComparisonResult::new(),
+        ),
+    }
+}
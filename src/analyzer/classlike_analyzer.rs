@@ -1,11 +1,16 @@
+use crate::architecture_analyzer;
+use crate::deprecation_analyzer;
 use crate::expression_analyzer;
 use crate::file_analyzer::FileAnalyzer;
 use crate::function_analysis_data::FunctionAnalysisData;
 use crate::functionlike_analyzer::FunctionLikeAnalyzer;
+use crate::module_analyzer;
+use crate::property_initialization_analyzer;
 use crate::scope_analyzer::ScopeAnalyzer;
 use crate::scope_context::ScopeContext;
 use crate::statements_analyzer::StatementsAnalyzer;
 use crate::stmt_analyzer::AnalysisError;
+use crate::template_variance_analyzer;
 use hakana_reflection_info::analysis_result::AnalysisResult;
 use hakana_reflection_info::codebase_info::symbols::SymbolKind;
 use hakana_reflection_info::data_flow::graph::{DataFlowGraph, GraphKind};
@@ -85,6 +90,82 @@ impl<'a> ClassLikeAnalyzer<'a> {
             None,
         );
 
+        for parent_hint in stmt.extends.iter().chain(stmt.implements.iter()) {
+            if let aast::Hint_::Happly(parent_name, _) = &*parent_hint.1 {
+                if let Some(parent_name) = resolved_names.get(&parent_name.0.start_offset()) {
+                    deprecation_analyzer::check_classlike(
+                        statements_analyzer,
+                        parent_name,
+                        statements_analyzer.get_hpos(&parent_hint.0),
+                        &mut analysis_data,
+                        &class_context.function_context,
+                    );
+                }
+            }
+        }
+
+        // promoted properties have their type hints checked as constructor params
+        for property_storage in classlike_storage.properties.values() {
+            if let Some(type_pos) = property_storage.type_pos {
+                if !property_storage.is_promoted
+                    && type_pos.file_path == classlike_storage.def_location.file_path
+                {
+                    deprecation_analyzer::check_type_hint(
+                        statements_analyzer,
+                        &property_storage.type_,
+                        type_pos,
+                        &mut analysis_data,
+                        &class_context.function_context,
+                    );
+
+                    module_analyzer::check_type_hint(
+                        statements_analyzer,
+                        &property_storage.type_,
+                        type_pos,
+                        &mut analysis_data,
+                        &class_context.function_context,
+                    );
+
+                    architecture_analyzer::check_type_hint(
+                        statements_analyzer,
+                        &property_storage.type_,
+                        type_pos,
+                        &mut analysis_data,
+                        &class_context.function_context,
+                    );
+                }
+            }
+        }
+
+        property_initialization_analyzer::check_class(
+            statements_analyzer,
+            stmt,
+            classlike_storage,
+            &mut analysis_data,
+            &class_context.function_context,
+        );
+
+        template_variance_analyzer::check_class(
+            statements_analyzer,
+            stmt,
+            classlike_storage,
+            &mut analysis_data,
+            &class_context.function_context,
+        );
+
+        // only the issues found above, since the analysis below is just for collecting references
+        let mut issues_to_emit = std::mem::take(&mut analysis_data.issues_to_emit);
+
+        if !issues_to_emit.is_empty() {
+            issues_to_emit.sort_by_key(|issue| issue.pos.start_offset);
+
+            analysis_result
+                .emitted_issues
+                .entry(*statements_analyzer.get_file_path())
+                .or_default()
+                .extend(issues_to_emit);
+        }
+
         for constant in &stmt.consts {
             match &constant.kind {
                 aast::ClassConstKind::CCAbstract(Some(expr))
@@ -1,5 +1,7 @@
 use std::{error::Error, fs::File, io::BufReader, path::Path};
 
+use hakana_reflection_info::issue::IssueSeverity;
+use indexmap::IndexMap;
 use rustc_hash::FxHashMap;
 use serde::Deserialize;
 
@@ -16,12 +18,97 @@ pub struct JsonConfig {
     pub allowed_issues: Vec<String>,
     #[serde(default)]
     pub test_files: Vec<String>,
+    /// Module name => files in that module, for files without a `module` declaration.
+    /// Modules are matched in the order they're declared, so the first matching module wins
+    #[serde(default)]
+    pub modules: IndexMap<String, Vec<String>>,
+    #[serde(default)]
+    pub architecture_rules: Vec<JsonArchitectureRule>,
+    /// Fully-qualified function or method name (`Foo::bar`) => why it can't be called
+    #[serde(default)]
+    pub banned_functions: FxHashMap<String, JsonBannedFunction>,
+    /// Issue name => `error`, `warning` or `info`. Issues not listed here are errors
+    #[serde(default)]
+    pub issue_severities: FxHashMap<String, IssueSeverity>,
+    /// Severities for issues in specific files, where the first matching override wins
+    #[serde(default)]
+    pub issue_severity_overrides: Vec<JsonIssueSeverityOverride>,
+    /// Shared libraries that provide extra hooks, see `plugin_hook`
+    #[serde(default)]
+    pub plugins: Vec<String>,
+    /// Whether functions declared with an empty `[]` context list are checked for purity,
+    /// like functions with a `<<Hakana\Pure>>` attribute
+    #[serde(default)]
+    pub check_pure_contexts: bool,
+    /// Whether fixing `MissingReturnType` also replaces `mixed` and `dynamic` return types
+    /// with the narrower type that every return statement has
+    #[serde(default)]
+    pub tighten_return_types: bool,
+}
+
+/// Restricts the symbols that code in some files can reference
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct JsonArchitectureRule {
+    /// Shown in the issue message when the rule is broken
+    pub name: String,
+    /// Files the rule applies to, or all files if empty
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Files the rule doesn't apply to
+    #[serde(default)]
+    pub except_files: Vec<String>,
+    /// Symbols declared in these files can't be referenced
+    #[serde(default)]
+    pub disallowed_paths: Vec<String>,
+    /// Fully-qualified symbols (`Foo\bar`, `Foo`, `Foo::bar`) that can't be referenced
+    #[serde(default)]
+    pub disallowed_symbols: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct JsonBannedFunction {
+    pub message: String,
+    /// A function or method that can be called instead, and that calls can be fixed to use
+    /// if its signature is compatible
+    #[serde(default)]
+    pub replacement: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct JsonIssueSeverityOverride {
+    pub files: Vec<String>,
+    /// Issue name => severity for issues in those files
+    pub issue_severities: FxHashMap<String, IssueSeverity>,
 }
 
 #[derive(Deserialize, Debug, Default)]
 pub struct JsonSecurityConfig {
     pub ignore_files: Vec<String>,
     pub ignore_sink_files: FxHashMap<String, Vec<String>>,
+    /// Names of source kinds on top of the built-in ones, e.g. `"PaymentData"`
+    #[serde(default)]
+    pub custom_sources: Vec<String>,
+    /// Names of sink kinds on top of the built-in ones, e.g. `"Analytics"`
+    #[serde(default)]
+    pub custom_sinks: Vec<String>,
+    /// Source kind => sink kinds that data from that source must never reach
+    #[serde(default)]
+    pub source_sink_pairs: FxHashMap<String, Vec<String>>,
+    /// Whether `source_sink_pairs` replaces the built-in pairs instead of extending them
+    #[serde(default)]
+    pub override_source_sink_pairs: bool,
+    /// Fully-qualified function or method name (`Foo::bar`) => source kinds it returns
+    #[serde(default)]
+    pub sources: FxHashMap<String, Vec<String>>,
+    /// Fully-qualified function or method name => param name => sink kinds
+    #[serde(default)]
+    pub sinks: FxHashMap<String, FxHashMap<String, Vec<String>>>,
+    /// Fully-qualified function or method name => sink kinds it removes
+    #[serde(default)]
+    pub sanitizers: FxHashMap<String, Vec<String>>,
 }
 
 pub(crate) fn read_from_file(path: &Path) -> Result<JsonConfig, Box<dyn Error>> {
@@ -1,18 +1,22 @@
-use std::{error::Error, path::Path};
+use std::{error::Error, path::Path, sync::Arc};
 
 use hakana_reflection_info::{
     data_flow::{graph::GraphKind, tainted_node::TaintedNode},
-    issue::{Issue, IssueKind},
-    taint::{SinkType, SourceType},
+    issue::{Issue, IssueKind, IssueSeverity},
+    taint::{
+        get_default_source_sink_pairs, string_to_sink_types, string_to_source_types, SinkType,
+        SourceSinkPairs, SourceType,
+    },
     Interner,
 };
+use indexmap::IndexMap;
 use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::custom_hook::CustomHook;
 
 pub mod json_config;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Config {
     pub migration_symbols: FxHashMap<String, String>,
     pub find_unused_expressions: bool,
@@ -23,16 +27,64 @@ pub struct Config {
     pub graph_kind: GraphKind,
     pub ignore_files: Vec<String>,
     pub test_files: Vec<String>,
+    pub modules: IndexMap<String, Vec<glob::Pattern>>,
+    pub architecture_rules: Vec<ArchitectureRule>,
+    pub banned_functions: FxHashMap<String, BannedFunction>,
+    pub issue_severities: FxHashMap<IssueKind, IssueSeverity>,
+    pub issue_severity_overrides: Vec<IssueSeverityOverride>,
     pub ignore_issue_files: FxHashMap<IssueKind, Vec<String>>,
     pub ignore_all_issues_in_files: Vec<String>,
     pub security_config: SecurityConfig,
     pub root_dir: String,
-    pub hooks: Vec<Box<dyn CustomHook>>,
+    pub hooks: Vec<Arc<dyn CustomHook>>,
     pub ignore_mixed_issues: bool,
     pub add_fixmes: bool,
     pub remove_fixmes: bool,
     pub all_custom_issues: FxHashSet<String>,
     pub ast_diff: bool,
+    pub collect_expr_types: bool,
+    pub check_pure_contexts: bool,
+    pub tighten_return_types: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct ArchitectureRule {
+    pub name: String,
+    pub files: Vec<glob::Pattern>,
+    pub except_files: Vec<glob::Pattern>,
+    pub disallowed_paths: Vec<glob::Pattern>,
+    pub disallowed_symbols: FxHashSet<String>,
+}
+
+impl ArchitectureRule {
+    pub fn applies_to_file(&self, file: &str) -> bool {
+        (self.files.is_empty() || self.files.iter().any(|rule_file| rule_file.matches(file)))
+            && !self
+                .except_files
+                .iter()
+                .any(|except_file| except_file.matches(file))
+    }
+
+    pub fn disallows_symbol(&self, symbol_name: &str, declaring_file: Option<&str>) -> bool {
+        self.disallowed_symbols.contains(symbol_name)
+            || declaring_file.map_or(false, |declaring_file| {
+                self.disallowed_paths
+                    .iter()
+                    .any(|disallowed_path| disallowed_path.matches(declaring_file))
+            })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BannedFunction {
+    pub message: String,
+    pub replacement: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct IssueSeverityOverride {
+    pub files: Vec<glob::Pattern>,
+    pub issue_severities: FxHashMap<IssueKind, IssueSeverity>,
 }
 
 #[derive(Clone, Debug)]
@@ -40,6 +92,12 @@ pub struct SecurityConfig {
     ignore_files: Vec<String>,
     ignore_sink_files: FxHashMap<String, Vec<String>>,
     pub max_depth: u8,
+    pub source_sink_pairs: SourceSinkPairs,
+    pub functionlike_sources: FxHashMap<String, FxHashSet<SourceType>>,
+    pub functionlike_sinks: FxHashMap<String, FxHashMap<String, FxHashSet<SinkType>>>,
+    pub functionlike_sanitizers: FxHashMap<String, FxHashSet<SinkType>>,
+    /// Whether to record where taints stop flowing, so that missing paths can be explained
+    pub record_cutoffs: bool,
 }
 
 impl Default for SecurityConfig {
@@ -54,7 +112,111 @@ impl SecurityConfig {
             ignore_files: Vec::new(),
             ignore_sink_files: FxHashMap::default(),
             max_depth: 40,
+            source_sink_pairs: get_default_source_sink_pairs(),
+            functionlike_sources: FxHashMap::default(),
+            functionlike_sinks: FxHashMap::default(),
+            functionlike_sanitizers: FxHashMap::default(),
+            record_cutoffs: false,
+        }
+    }
+
+    fn update_from_json(
+        &mut self,
+        json_config: json_config::JsonSecurityConfig,
+    ) -> Result<(), Box<dyn Error>> {
+        let custom_sources = json_config
+            .custom_sources
+            .into_iter()
+            .collect::<FxHashSet<_>>();
+        let custom_sinks = json_config
+            .custom_sinks
+            .into_iter()
+            .collect::<FxHashSet<_>>();
+
+        let get_source_type = |str: String| -> Result<SourceType, Box<dyn Error>> {
+            if custom_sources.contains(&str) {
+                Ok(SourceType::Custom(str))
+            } else {
+                string_to_source_types(str.clone())
+                    .ok_or_else(|| format!("Unknown taint source {}", str).into())
+            }
+        };
+
+        let get_sink_types = |strs: Vec<String>| -> Result<FxHashSet<SinkType>, Box<dyn Error>> {
+            let mut sink_types = FxHashSet::default();
+
+            for str in strs {
+                if custom_sinks.contains(&str) {
+                    sink_types.insert(SinkType::Custom(str));
+                } else {
+                    let named_sink_types = string_to_sink_types(str.clone());
+
+                    if named_sink_types.is_empty() {
+                        return Err(format!("Unknown taint sink {}", str).into());
+                    }
+
+                    sink_types.extend(named_sink_types);
+                }
+            }
+
+            Ok(sink_types)
+        };
+
+        if json_config.override_source_sink_pairs {
+            self.source_sink_pairs = SourceSinkPairs::default();
+        }
+
+        for (source, sinks) in json_config.source_sink_pairs {
+            self.source_sink_pairs
+                .entry(get_source_type(source)?)
+                .or_default()
+                .extend(get_sink_types(sinks)?);
         }
+
+        self.functionlike_sources = json_config
+            .sources
+            .into_iter()
+            .map(|(functionlike_name, sources)| {
+                Ok((
+                    functionlike_name,
+                    sources
+                        .into_iter()
+                        .map(get_source_type)
+                        .collect::<Result<_, _>>()?,
+                ))
+            })
+            .collect::<Result<_, Box<dyn Error>>>()?;
+
+        self.functionlike_sinks = json_config
+            .sinks
+            .into_iter()
+            .map(|(functionlike_name, params)| {
+                Ok((
+                    functionlike_name,
+                    params
+                        .into_iter()
+                        .map(|(param_name, sinks)| {
+                            Ok((
+                                if param_name.starts_with('$') {
+                                    param_name
+                                } else {
+                                    format!("${}", param_name)
+                                },
+                                get_sink_types(sinks)?,
+                            ))
+                        })
+                        .collect::<Result<_, Box<dyn Error>>>()?,
+                ))
+            })
+            .collect::<Result<_, Box<dyn Error>>>()?;
+
+        self.functionlike_sanitizers = json_config
+            .sanitizers
+            .into_iter()
+            .map(|(functionlike_name, sinks)| Ok((functionlike_name, get_sink_types(sinks)?)))
+            .collect::<Result<_, Box<dyn Error>>>()?;
+
+        Ok(())
     }
 }
 
@@ -70,6 +232,11 @@ impl Config {
             graph_kind: GraphKind::FunctionBody,
             ignore_files: Vec::new(),
             test_files: Vec::new(),
+            modules: IndexMap::default(),
+            architecture_rules: vec![],
+            banned_functions: FxHashMap::default(),
+            issue_severities: FxHashMap::default(),
+            issue_severity_overrides: vec![],
             ignore_issue_files: FxHashMap::default(),
             ignore_all_issues_in_files: vec![],
             security_config: SecurityConfig::new(),
@@ -80,6 +247,9 @@ impl Config {
             all_custom_issues,
             allowable_issues: None,
             ast_diff: false,
+            collect_expr_types: false,
+            check_pure_contexts: false,
+            tighten_return_types: false,
         }
     }
 
@@ -90,6 +260,21 @@ impl Config {
     ) -> Result<(), Box<dyn Error>> {
         let json_config = json_config::read_from_file(config_path)?;
 
+        // plugins come first, since the rest of the config can refer to their custom issues
+        #[cfg(not(target_arch = "wasm32"))]
+        for plugin_path in &json_config.plugins {
+            let plugin_hook =
+                crate::plugin_hook::PluginHook::load(&format!("{}/{}", cwd, plugin_path))?;
+
+            self.all_custom_issues.extend(
+                crate::custom_hook::InternalHook::get_custom_issue_names(&plugin_hook)
+                    .into_iter()
+                    .map(|issue_name| issue_name.to_string()),
+            );
+
+            self.hooks.push(Arc::new(plugin_hook));
+        }
+
         self.ignore_files = json_config
             .ignore_files
             .into_iter()
@@ -102,6 +287,93 @@ impl Config {
             .map(|v| format!("{}/{}", cwd, v))
             .collect();
 
+        let prefix_paths = |paths: Vec<String>| -> Vec<String> {
+            paths
+                .into_iter()
+                .map(|v| format!("{}/{}", cwd, v))
+                .collect()
+        };
+
+        // these are matched against every file, so they're only compiled once
+        let compile_patterns = |paths: Vec<String>| -> Result<Vec<glob::Pattern>, Box<dyn Error>> {
+            prefix_paths(paths)
+                .into_iter()
+                .map(|path| {
+                    glob::Pattern::new(&path).map_err(|error| {
+                        Box::<dyn Error>::from(format!("Invalid file pattern {}: {}", path, error))
+                    })
+                })
+                .collect()
+        };
+
+        self.modules = json_config
+            .modules
+            .into_iter()
+            .map(|(module_name, files)| Ok((module_name, compile_patterns(files)?)))
+            .collect::<Result<_, Box<dyn Error>>>()?;
+
+        self.architecture_rules = json_config
+            .architecture_rules
+            .into_iter()
+            .map(|rule| {
+                Ok(ArchitectureRule {
+                    name: rule.name,
+                    files: compile_patterns(rule.files)?,
+                    except_files: compile_patterns(rule.except_files)?,
+                    disallowed_paths: compile_patterns(rule.disallowed_paths)?,
+                    disallowed_symbols: rule
+                        .disallowed_symbols
+                        .into_iter()
+                        .map(|symbol| symbol.trim_start_matches('\\').to_string())
+                        .collect(),
+                })
+            })
+            .collect::<Result<_, Box<dyn Error>>>()?;
+
+        self.banned_functions = json_config
+            .banned_functions
+            .into_iter()
+            .map(|(functionlike_name, banned_function)| {
+                (
+                    functionlike_name.trim_start_matches('\\').to_string(),
+                    BannedFunction {
+                        message: banned_function.message,
+                        replacement: banned_function
+                            .replacement
+                            .map(|replacement| replacement.trim_start_matches('\\').to_string()),
+                    },
+                )
+            })
+            .collect();
+
+        let all_custom_issues = &self.all_custom_issues;
+
+        let get_issue_severities =
+            |issue_severities: FxHashMap<String, IssueSeverity>| -> Result<_, Box<dyn Error>> {
+                issue_severities
+                    .into_iter()
+                    .map(|(issue_name, severity)| {
+                        Ok((
+                            IssueKind::from_str_custom(issue_name.as_str(), all_custom_issues)?,
+                            severity,
+                        ))
+                    })
+                    .collect()
+            };
+
+        self.issue_severities = get_issue_severities(json_config.issue_severities)?;
+
+        self.issue_severity_overrides = json_config
+            .issue_severity_overrides
+            .into_iter()
+            .map(|severity_override| {
+                Ok(IssueSeverityOverride {
+                    files: compile_patterns(severity_override.files)?,
+                    issue_severities: get_issue_severities(severity_override.issue_severities)?,
+                })
+            })
+            .collect::<Result<_, Box<dyn Error>>>()?;
+
         self.ignore_issue_files = json_config
             .ignore_issue_files
             .iter()
@@ -133,18 +405,22 @@ impl Config {
             )
         };
 
-        self.security_config.ignore_files = json_config
-            .security_analysis
-            .ignore_files
+        self.check_pure_contexts = json_config.check_pure_contexts;
+        self.tighten_return_types = json_config.tighten_return_types;
+
+        let mut security_analysis = json_config.security_analysis;
+
+        self.security_config.ignore_files = std::mem::take(&mut security_analysis.ignore_files)
             .into_iter()
             .map(|v| format!("{}/{}", cwd, v))
             .collect();
-        self.security_config.ignore_sink_files = json_config
-            .security_analysis
-            .ignore_sink_files
-            .into_iter()
-            .map(|(k, v)| (k, v.into_iter().map(|v| format!("{}/{}", cwd, v)).collect()))
-            .collect();
+        self.security_config.ignore_sink_files =
+            std::mem::take(&mut security_analysis.ignore_sink_files)
+                .into_iter()
+                .map(|(k, v)| (k, v.into_iter().map(|v| format!("{}/{}", cwd, v)).collect()))
+                .collect();
+
+        self.security_config.update_from_json(security_analysis)?;
 
         Ok(())
     }
@@ -159,6 +435,27 @@ impl Config {
         true
     }
 
+    pub fn get_issue_severity(&self, issue: &Issue, interner: &Interner) -> IssueSeverity {
+        let file = interner.lookup(&issue.pos.file_path.0);
+
+        for severity_override in &self.issue_severity_overrides {
+            if let Some(severity) = severity_override.issue_severities.get(&issue.kind) {
+                if severity_override
+                    .files
+                    .iter()
+                    .any(|override_file| override_file.matches(file))
+                {
+                    return *severity;
+                }
+            }
+        }
+
+        self.issue_severities
+            .get(&issue.kind)
+            .copied()
+            .unwrap_or_default()
+    }
+
     pub fn allow_issues_in_file(&self, file: &str) -> bool {
         for ignore_file_path in &self.ignore_all_issues_in_files {
             if glob::Pattern::new(ignore_file_path).unwrap().matches(file) {
@@ -181,6 +478,17 @@ impl Config {
         true
     }
 
+    pub fn get_module_for_file(&self, file: &str) -> Option<&String> {
+        self.modules
+            .iter()
+            .find(|(_, module_files)| {
+                module_files
+                    .iter()
+                    .any(|module_file| module_file.matches(file))
+            })
+            .map(|(module_name, _)| module_name)
+    }
+
     pub fn allow_taints_in_file(&self, file: &str) -> bool {
         for ignore_file_path in &self.security_config.ignore_files {
             if glob::Pattern::new(ignore_file_path).unwrap().matches(file) {
@@ -1,5 +1,9 @@
-use hakana_reflection_info::analysis_result::AnalysisResult;
+use std::collections::BTreeMap;
+
+use hakana_reflection_info::analysis_result::{AnalysisResult, Replacement};
+use hakana_reflection_info::code_location::FilePath;
 use hakana_reflection_info::function_context::FunctionLikeIdentifier;
+use hakana_reflection_info::issue::Issue;
 use hakana_reflection_info::Interner;
 use hakana_reflection_info::{
     codebase_info::CodebaseInfo, functionlike_info::FunctionLikeInfo, t_union::TUnion,
@@ -8,6 +12,7 @@ use oxidized::{
     aast,
     ast_defs::{self, Pos},
 };
+use rustc_hash::FxHashMap;
 
 use crate::{
     config, function_analysis_data::FunctionAnalysisData, scope_context::ScopeContext,
@@ -53,6 +58,50 @@ pub struct AfterArgAnalysisData<'a> {
     pub statements_analyzer: &'a StatementsAnalyzer<'a>,
 }
 
+pub struct FunctionLikeReturnTypeData<'a> {
+    /// The function, or the method on the class it was called on
+    pub functionlike_id: &'a FunctionLikeIdentifier,
+    /// The function, or the method on the class that declares it
+    pub declaring_functionlike_id: &'a FunctionLikeIdentifier,
+    /// The inferred types of the call's arguments, in order, where known
+    pub arg_types: Vec<Option<&'a TUnion>>,
+    pub codebase: &'a CodebaseInfo,
+    pub interner: &'a Interner,
+}
+
+pub struct AfterCodebaseAnalysisData<'a> {
+    /// The merged result of analysing every file, including all symbol references
+    pub analysis_result: &'a AnalysisResult,
+    pub codebase: &'a CodebaseInfo,
+    pub config: &'a config::Config,
+    pub interner: &'a Interner,
+}
+
+/// Collects the issues and replacements added by `after_codebase_analysis` hooks
+#[derive(Default)]
+pub struct CodebaseAnalysisData {
+    pub issues: Vec<Issue>,
+    pub replacements: FxHashMap<FilePath, BTreeMap<(u32, u32), Replacement>>,
+}
+
+impl CodebaseAnalysisData {
+    pub fn add_issue(&mut self, issue: Issue) {
+        self.issues.push(issue);
+    }
+
+    pub fn add_replacement(
+        &mut self,
+        file_path: FilePath,
+        offsets: (u32, u32),
+        replacement: Replacement,
+    ) {
+        self.replacements
+            .entry(file_path)
+            .or_default()
+            .insert(offsets, replacement);
+    }
+}
+
 pub trait InternalHook {
     fn get_migration_name(&self) -> Option<&str> {
         None
@@ -104,6 +153,16 @@ pub trait InternalHook {
     ) {
     }
 
+    // This hook is run before working out the return type of a function or method call.
+    // Returning a type here replaces the declared return type for that call.
+    #[allow(unused_variables)]
+    fn get_functionlike_return_type(
+        &self,
+        return_type_data: FunctionLikeReturnTypeData,
+    ) -> Option<TUnion> {
+        None
+    }
+
     #[allow(unused_variables)]
     fn after_functionlike_analysis(
         &self,
@@ -119,6 +178,16 @@ pub trait InternalHook {
         false
     }
 
+    // This hook is run once every file has been analyzed, for checks that need
+    // knowledge of the whole codebase
+    #[allow(unused_variables)]
+    fn after_codebase_analysis(
+        &self,
+        codebase_analysis_data: &mut CodebaseAnalysisData,
+        after_codebase_analysis_data: AfterCodebaseAnalysisData,
+    ) {
+    }
+
     fn get_custom_issue_names(&self) -> Vec<&str> {
         vec![]
     }
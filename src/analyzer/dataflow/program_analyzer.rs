@@ -1,7 +1,8 @@
 use hakana_logger::Logger;
 use hakana_logger::Verbosity;
-use hakana_reflection_info::code_location::FilePath;
-use hakana_reflection_info::data_flow::node::DataFlowNodeKind;
+use hakana_reflection_info::code_location::{FilePath, HPos};
+use hakana_reflection_info::codebase_info::CodebaseInfo;
+use hakana_reflection_info::data_flow::node::{DataFlowNode, DataFlowNodeKind};
 use hakana_reflection_info::Interner;
 use hakana_reflection_info::StrId;
 use rustc_hash::FxHashMap;
@@ -10,7 +11,7 @@ use std::sync::Arc;
 use std::time::Instant;
 
 use crate::config::Config;
-use hakana_reflection_info::data_flow::graph::DataFlowGraph;
+use hakana_reflection_info::data_flow::graph::{DataFlowGraph, TaintCutoff};
 use hakana_reflection_info::data_flow::path::ArrayDataKind;
 use hakana_reflection_info::data_flow::path::PathKind;
 use hakana_reflection_info::data_flow::tainted_node::TaintedNode;
@@ -27,19 +28,20 @@ pub fn find_tainted_data(
     let mut new_issues = vec![];
 
     let sources = graph
-        .sources.values().map(|v| Arc::new(TaintedNode::from(v)))
+        .sources
+        .values()
+        .map(|v| {
+            Arc::new(TaintedNode::from(
+                v,
+                &config.security_config.source_sink_pairs,
+            ))
+        })
         .collect::<Vec<_>>();
 
     logger.log_sync("Security analysis: detecting paths");
     logger.log_sync(&format!(" - initial sources count: {}", sources.len()));
     logger.log_sync(&format!(" - initial sinks count:   {}", graph.sinks.len()));
 
-    // for (from_id, to) in &graph.forward_edges {
-    //     for (to_id, path) in to {
-    //         println!("{} --{}--> {}", from_id, path.kind, to_id);
-    //     }
-    // }
-
     find_paths_to_sinks(
         sources,
         graph,
@@ -48,6 +50,7 @@ pub fn find_tainted_data(
         &mut new_issues,
         true,
         interner,
+        None,
     );
 
     new_issues
@@ -65,17 +68,16 @@ pub fn find_connections(
         .sources
         .iter()
         .filter(|(_, v)| matches!(v.kind, DataFlowNodeKind::DataSource { .. }))
-        .map(|(_, v)| Arc::new(TaintedNode::from(v)))
+        .map(|(_, v)| {
+            Arc::new(TaintedNode::from(
+                v,
+                &config.security_config.source_sink_pairs,
+            ))
+        })
         .collect::<Vec<_>>();
 
     logger.log_sync(&format!(" - initial sources count: {}", sources.len()));
 
-    // for (from_id, to) in &graph.forward_edges {
-    //     for (to_id, _) in to {
-    //         println!("{} -> {}", from_id, to_id);
-    //     }
-    // }
-
     find_paths_to_sinks(
         sources,
         graph,
@@ -84,11 +86,70 @@ pub fn find_connections(
         &mut new_issues,
         false,
         interner,
+        None,
     );
 
     new_issues
 }
 
+/// Searches for paths from the nodes on the `from` line to the nodes on the `to` line,
+/// keeping track of everywhere propagation stopped along the way
+pub fn explain_taint_paths(
+    graph: &DataFlowGraph,
+    config: &Config,
+    logger: &Logger,
+    interner: &Interner,
+    from: (FilePath, u32),
+    to: (FilePath, u32),
+) -> TaintExplanation {
+    let mut sources = graph
+        .sources
+        .values()
+        .filter(|node| is_on_line(node, from))
+        .filter(|node| matches!(node.kind, DataFlowNodeKind::TaintSource { .. }))
+        .map(|node| {
+            Arc::new(TaintedNode::from(
+                node,
+                &config.security_config.source_sink_pairs,
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    // we can also explain paths from places that aren't sources, by pretending
+    // they're tainted with everything
+    if sources.is_empty() {
+        sources = graph
+            .vertices
+            .values()
+            .filter(|node| is_on_line(node, from))
+            .map(|node| {
+                let mut source = TaintedNode::from(node, &config.security_config.source_sink_pairs);
+                source.taint_sinks = SinkType::user_controllable_taints();
+                Arc::new(source)
+            })
+            .collect();
+    }
+
+    let mut explanation = TaintExplanation {
+        to,
+        found_paths: vec![],
+        stops: vec![],
+    };
+
+    find_paths_to_sinks(
+        sources,
+        graph,
+        config,
+        logger,
+        &mut vec![],
+        true,
+        interner,
+        Some(&mut explanation),
+    );
+
+    explanation
+}
+
 #[inline]
 fn find_paths_to_sinks(
     mut sources: Vec<Arc<TaintedNode>>,
@@ -98,6 +159,7 @@ fn find_paths_to_sinks(
     new_issues: &mut Vec<Issue>,
     match_sinks: bool,
     interner: &Interner,
+    mut explanation: Option<&mut TaintExplanation>,
 ) {
     let mut seen_sources = FxHashSet::default();
 
@@ -105,7 +167,7 @@ fn find_paths_to_sinks(
         seen_sources.insert(source.get_unique_source_id());
     }
 
-    if !match_sinks || !graph.sinks.is_empty() {
+    if !match_sinks || !graph.sinks.is_empty() || explanation.is_some() {
         for i in 0..config.security_config.max_depth {
             if !sources.is_empty() {
                 let now = if matches!(
@@ -133,9 +195,13 @@ fn find_paths_to_sinks(
                     let source_taints = source.taint_sinks.clone();
                     let source_id = source.id.clone();
 
-                    let generated_sources = get_specialized_sources(graph, source);
+                    let generated_sources = get_specialized_sources(graph, source.clone());
                     actual_source_count += generated_sources.len();
 
+                    if let Some(explanation) = &mut explanation {
+                        explanation.add_source_stops(graph, source, generated_sources.is_empty());
+                    }
+
                     for generated_source in generated_sources {
                         new_sources.extend(get_child_nodes(
                             graph,
@@ -148,6 +214,7 @@ fn find_paths_to_sinks(
                             i == config.security_config.max_depth - 1,
                             match_sinks,
                             interner,
+                            explanation.as_deref_mut(),
                         ))
                     }
 
@@ -267,6 +334,7 @@ fn get_child_nodes(
     is_last: bool,
     match_sinks: bool,
     interner: &Interner,
+    mut explanation: Option<&mut TaintExplanation>,
 ) -> Vec<Arc<TaintedNode>> {
     let mut new_child_nodes = Vec::new();
 
@@ -280,12 +348,14 @@ fn get_child_nodes(
                             target_id,
                             generated_source.get_trace(interner, &config.root_dir)
                         );
-                        new_issues.push(Issue::new(
+                        let mut issue = Issue::new(
                             IssueKind::TaintedData(t.clone()),
                             message,
                             **generated_source.pos.as_ref().unwrap(),
                             &None,
-                        ));
+                        );
+                        issue.taint_trace = Some(generated_source.get_trace_steps());
+                        new_issues.push(issue);
                     }
                 }
             }
@@ -301,46 +371,16 @@ fn get_child_nodes(
                 panic!();
             };
 
-            // skip Exception::__construct, which looks too noisy
-            if to_id == "Exception::__construct#1" {
-                continue;
-            }
-
-            if let PathKind::Aggregate = &path.kind {
-                continue;
-            }
-
-            // if we're going through a scalar type guard and the last non-default path was
-            // an array or property assignment, skip
-            if let PathKind::ScalarTypeGuard = &path.kind {
-                if has_recent_assignment(&generated_source.path_types) {
-                    continue;
-                }
-            }
-
-            if let PathKind::RefineSymbol(symbol_id) = &path.kind {
-                if has_unmatched_property_assignment(symbol_id, &generated_source.path_types) {
-                    continue;
+            if let Some(reason) =
+                get_ignored_path_reason(to_id, &path.kind, &generated_source.path_types)
+            {
+                if let Some(explanation) = &mut explanation {
+                    explanation.stops.push(TaintStop {
+                        node: generated_source.clone(),
+                        reason: TaintStopReason::IgnoredPath(reason),
+                    });
                 }
-            }
 
-            if should_ignore_array_fetch(
-                &path.kind,
-                &ArrayDataKind::ArrayKey,
-                &generated_source.path_types,
-            ) {
-                continue;
-            }
-
-            if should_ignore_array_fetch(
-                &path.kind,
-                &ArrayDataKind::ArrayValue,
-                &generated_source.path_types,
-            ) {
-                continue;
-            }
-
-            if should_ignore_property_fetch(&path.kind, &generated_source.path_types) {
                 continue;
             }
 
@@ -353,12 +393,14 @@ fn get_child_nodes(
                                 target_id,
                                 generated_source.get_trace(interner, &config.root_dir)
                             );
-                            new_issues.push(Issue::new(
+                            let mut issue = Issue::new(
                                 IssueKind::TaintedData(t.clone()),
                                 message,
                                 **generated_source.pos.as_ref().unwrap(),
                                 &None,
-                            ));
+                            );
+                            issue.taint_trace = Some(generated_source.get_trace_steps());
+                            new_issues.push(issue);
                         }
                     }
                 }
@@ -373,7 +415,8 @@ fn get_child_nodes(
                 new_taints.retain(|t| !removed_taints.contains(t));
             }
 
-            let mut new_destination = TaintedNode::from(destination_node);
+            let mut new_destination =
+                TaintedNode::from(destination_node, &config.security_config.source_sink_pairs);
 
             new_destination.previous = Some(generated_source.clone());
             new_destination.taint_sinks = new_taints.clone();
@@ -388,6 +431,31 @@ fn get_child_nodes(
 
             new_destination.path_types = new_path_types;
 
+            if let Some(explanation) = &mut explanation {
+                if new_taints.is_empty() && !source_taints.is_empty() {
+                    explanation.stops.push(TaintStop {
+                        node: Arc::new(new_destination),
+                        reason: TaintStopReason::RemovedTaints(source_taints.clone()),
+                    });
+                    continue;
+                }
+
+                if is_on_line(destination_node, explanation.to) {
+                    if let DataFlowNodeKind::TaintSink { types, .. } = &destination_node.kind {
+                        if !types.iter().any(|t| new_taints.contains(t)) {
+                            explanation.stops.push(TaintStop {
+                                node: Arc::new(new_destination),
+                                reason: TaintStopReason::UnmatchedSink(new_taints),
+                            });
+                            continue;
+                        }
+                    }
+
+                    explanation.found_paths.push(Arc::new(new_destination));
+                    continue;
+                }
+            }
+
             if match_sinks {
                 if let Some(sink) = graph.sinks.get(to_id) {
                     if let DataFlowNodeKind::TaintSink { types, .. } = &sink.kind {
@@ -417,12 +485,15 @@ fn get_child_nodes(
                                             new_destination
                                                 .get_trace(interner, &config.root_dir)
                                         );
-                                        new_issues.push(Issue::new(
+                                        let mut issue = Issue::new(
                                             IssueKind::TaintedData(matching_sink.clone()),
                                             message,
                                             **issue_pos,
                                             &None,
-                                        ));
+                                        );
+                                        issue.taint_trace =
+                                            Some(new_destination.get_trace_steps());
+                                        new_issues.push(issue);
                                     }
                                 }
                             }
@@ -446,6 +517,11 @@ fn get_child_nodes(
 
             if !is_last {
                 new_child_nodes.push(Arc::new(new_destination));
+            } else if let Some(explanation) = &mut explanation {
+                explanation.stops.push(TaintStop {
+                    node: Arc::new(new_destination),
+                    reason: TaintStopReason::MaxDepth,
+                });
             }
         }
     }
@@ -453,6 +529,197 @@ fn get_child_nodes(
     new_child_nodes
 }
 
+/// Why the search for sinks stopped following data
+pub enum TaintStopReason {
+    /// The path was longer than the configured max depth
+    MaxDepth,
+    /// Nothing in the graph flows out of the node
+    NoOutgoingFlows,
+    /// Analysis didn't add the flows out of the node to the graph
+    Cutoff(TaintCutoff),
+    /// The flow out of the node isn't followed, given the paths taken to get there
+    IgnoredPath(&'static str),
+    /// All remaining taints were removed, e.g. by a `Sanitize` attribute or a
+    /// `HAKANA_SECURITY_IGNORE` comment
+    RemovedTaints(FxHashSet<SinkType>),
+    /// The data reached the destination, but none of its remaining taints match
+    /// the destination's sink types
+    UnmatchedSink(FxHashSet<SinkType>),
+}
+
+pub struct TaintStop {
+    pub node: Arc<TaintedNode>,
+    pub reason: TaintStopReason,
+}
+
+impl TaintStop {
+    pub fn format(&self, codebase: &CodebaseInfo, interner: &Interner, root_dir: &str) -> String {
+        let reason = match &self.reason {
+            TaintStopReason::MaxDepth => "max depth reached".to_string(),
+            TaintStopReason::NoOutgoingFlows => "no outgoing flows".to_string(),
+            TaintStopReason::Cutoff(cutoff) => format!(
+                "{} at {}:{}:{}",
+                cutoff.reason,
+                cutoff.pos.file_path.get_relative_path(interner, root_dir),
+                cutoff.pos.start_line,
+                cutoff.pos.start_column
+            ),
+            TaintStopReason::IgnoredPath(reason) => reason.to_string(),
+            TaintStopReason::RemovedTaints(taints) => {
+                if let Some(functionlike_name) =
+                    get_sanitizing_functionlike(&self.node, codebase, interner)
+                {
+                    format!(
+                        "{} removed by Sanitize on {}",
+                        format_taints(taints),
+                        functionlike_name
+                    )
+                } else {
+                    format!("{} removed", format_taints(taints))
+                }
+            }
+            TaintStopReason::UnmatchedSink(taints) => {
+                format!("sink doesn't accept {}", format_taints(taints))
+            }
+        };
+
+        format!("{}: {}", self.node.get_trace(interner, root_dir), reason)
+    }
+}
+
+pub struct TaintExplanation {
+    to: (FilePath, u32),
+    pub found_paths: Vec<Arc<TaintedNode>>,
+    pub stops: Vec<TaintStop>,
+}
+
+impl TaintExplanation {
+    fn add_source_stops(
+        &mut self,
+        graph: &DataFlowGraph,
+        source: Arc<TaintedNode>,
+        has_no_outgoing_flows: bool,
+    ) {
+        let cutoffs = graph
+            .cutoffs
+            .get(&source.id)
+            .into_iter()
+            .chain(
+                source
+                    .unspecialized_id
+                    .as_ref()
+                    .and_then(|unspecialized_id| graph.cutoffs.get(unspecialized_id)),
+            )
+            .flatten()
+            .collect::<Vec<_>>();
+
+        if has_no_outgoing_flows && cutoffs.is_empty() {
+            self.stops.push(TaintStop {
+                node: source,
+                reason: TaintStopReason::NoOutgoingFlows,
+            });
+            return;
+        }
+
+        for cutoff in cutoffs {
+            self.stops.push(TaintStop {
+                node: source.clone(),
+                reason: TaintStopReason::Cutoff(cutoff.clone()),
+            });
+        }
+    }
+}
+
+fn is_on_line(node: &DataFlowNode, (file_path, line): (FilePath, u32)) -> bool {
+    let pos: Option<&HPos> = match &node.kind {
+        DataFlowNodeKind::Vertex { pos, .. }
+        | DataFlowNodeKind::TaintSource { pos, .. }
+        | DataFlowNodeKind::TaintSink { pos, .. } => pos.as_ref(),
+        DataFlowNodeKind::VariableUseSource { pos, .. }
+        | DataFlowNodeKind::VariableUseSink { pos }
+        | DataFlowNodeKind::DataSource { pos, .. } => Some(pos),
+        DataFlowNodeKind::ForLoopInit { .. } => None,
+    };
+
+    pos.map_or(false, |pos| {
+        pos.file_path == file_path && pos.start_line <= line && line <= pos.end_line
+    })
+}
+
+/// Function return nodes are identified by the function's name, so we can find the
+/// function whose `Sanitize` attribute removed a path's taints
+fn get_sanitizing_functionlike(
+    node: &TaintedNode,
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+) -> Option<String> {
+    let functionlike_name = node.unspecialized_id.as_ref().unwrap_or(&node.id);
+
+    let functionlike_key =
+        if let Some((class_name, method_name)) = functionlike_name.split_once("::") {
+            (interner.get(class_name)?, interner.get(method_name)?)
+        } else {
+            (interner.get(functionlike_name)?, StrId::EMPTY)
+        };
+
+    codebase
+        .functionlike_infos
+        .get(&functionlike_key)?
+        .removed_taints
+        .as_ref()
+        .map(|_| format!("{}()", functionlike_name))
+}
+
+fn format_taints(taints: &FxHashSet<SinkType>) -> String {
+    let mut taints = taints.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+    taints.sort();
+    taints.join(", ")
+}
+
+/// Gets the reason a path shouldn't be followed, given the paths taken to get to it
+fn get_ignored_path_reason(
+    to_id: &String,
+    path_kind: &PathKind,
+    previous_path_types: &[PathKind],
+) -> Option<&'static str> {
+    // skip Exception::__construct, which looks too noisy
+    if to_id == "Exception::__construct#1" {
+        return Some("Exception::__construct is ignored");
+    }
+
+    if let PathKind::Aggregate = path_kind {
+        return Some("aggregate paths are not followed");
+    }
+
+    // if we're going through a scalar type guard and the last non-default path was
+    // an array or property assignment, skip
+    if let PathKind::ScalarTypeGuard = path_kind {
+        if has_recent_assignment(previous_path_types) {
+            return Some("scalar type guard after an array or property assignment");
+        }
+    }
+
+    if let PathKind::RefineSymbol(symbol_id) = path_kind {
+        if has_unmatched_property_assignment(symbol_id, previous_path_types) {
+            return Some("symbol refinement without a matching property assignment");
+        }
+    }
+
+    if should_ignore_array_fetch(path_kind, &ArrayDataKind::ArrayKey, previous_path_types) {
+        return Some("array key fetch without a matching array key assignment");
+    }
+
+    if should_ignore_array_fetch(path_kind, &ArrayDataKind::ArrayValue, previous_path_types) {
+        return Some("array value fetch without a matching array value assignment");
+    }
+
+    if should_ignore_property_fetch(path_kind, previous_path_types) {
+        return Some("property fetch without a matching property assignment");
+    }
+
+    None
+}
+
 fn has_recent_assignment(generated_path_types: &[PathKind]) -> bool {
     let filtered_paths = generated_path_types
         .iter()
@@ -8,10 +8,9 @@ use crate::scope_context::loop_scope::LoopScope;
 use crate::scope_context::ScopeContext;
 use crate::statements_analyzer::StatementsAnalyzer;
 use crate::stmt_analyzer::AnalysisError;
-use crate::{expression_analyzer, stmt_analyzer};
+use crate::{expression_analyzer, stmt_analyzer, type_hint_position_collector};
 use hakana_reflection_info::analysis_result::AnalysisResult;
 use hakana_reflection_info::function_context::FunctionContext;
-use hakana_reflection_info::issue::{Issue, IssueKind};
 use oxidized::aast;
 
 pub(crate) fn analyze(
@@ -94,32 +93,23 @@ pub(crate) fn analyze(
         aast::Def::FileAttributes(_) => {
             // not sure
         }
-        aast::Def::Module(boxed) => {
-            analysis_data.maybe_add_issue(
-                Issue::new(
-                    IssueKind::UnrecognizedStatement,
-                    "Unrecognized statement".to_string(),
-                    statements_analyzer.get_hpos(&boxed.span),
-                    &None,
-                ),
-                statements_analyzer.get_config(),
-                statements_analyzer.get_file_path_actual(),
-            );
-        }
-        aast::Def::SetModule(boxed) => {
-            analysis_data.maybe_add_issue(
-                Issue::new(
-                    IssueKind::UnrecognizedStatement,
-                    "Unrecognized statement".to_string(),
-                    statements_analyzer.get_hpos(boxed.pos()),
-                    &None,
-                ),
-                statements_analyzer.get_config(),
-                statements_analyzer.get_file_path_actual(),
-            );
+        aast::Def::Module(_) | aast::Def::SetModule(_) => {
+            // module membership is recorded by the scanner
         }
     }
 
+    if statements_analyzer.get_config().collect_expr_types {
+        type_hint_position_collector::collect(
+            def,
+            statements_analyzer.get_file_analyzer().resolved_names,
+            statements_analyzer.get_codebase(),
+            analysis_result
+                .symbol_positions
+                .entry(*statements_analyzer.get_file_path())
+                .or_default(),
+        );
+    }
+
     for hook in &statements_analyzer.get_config().hooks {
         hook.after_def_analysis(
             analysis_data,
@@ -0,0 +1,184 @@
+use hakana_reflection_info::code_location::HPos;
+use hakana_reflection_info::function_context::FunctionContext;
+use hakana_reflection_info::functionlike_identifier::FunctionLikeIdentifier;
+use hakana_reflection_info::functionlike_info::FunctionLikeInfo;
+use hakana_reflection_info::issue::{Issue, IssueKind};
+use hakana_reflection_info::t_union::TUnion;
+use hakana_reflection_info::StrId;
+
+use crate::function_analysis_data::FunctionAnalysisData;
+use crate::scope_analyzer::ScopeAnalyzer;
+use crate::statements_analyzer::StatementsAnalyzer;
+
+pub(crate) fn check_functionlike(
+    statements_analyzer: &StatementsAnalyzer,
+    functionlike_id: &FunctionLikeIdentifier,
+    functionlike_storage: &FunctionLikeInfo,
+    pos: HPos,
+    analysis_data: &mut FunctionAnalysisData,
+    function_context: &FunctionContext,
+) {
+    let message = if let Some(message) = &functionlike_storage.deprecated {
+        message
+    } else {
+        return;
+    };
+
+    let interner = statements_analyzer.get_interner();
+
+    let (issue_kind, description) = match functionlike_id {
+        FunctionLikeIdentifier::Function(_) => {
+            // recursive calls don't need to be reported
+            if function_context.calling_functionlike_id.as_ref() == Some(functionlike_id) {
+                return;
+            }
+
+            (
+                IssueKind::DeprecatedFunction,
+                format!("Function {}", functionlike_id.to_string(interner)),
+            )
+        }
+        FunctionLikeIdentifier::Method(classlike_name, _) => {
+            if function_context.calling_class.as_ref() == Some(classlike_name) {
+                return;
+            }
+
+            (
+                IssueKind::DeprecatedMethod,
+                format!("Method {}", functionlike_id.to_string(interner)),
+            )
+        }
+    };
+
+    add_deprecation_issue(
+        statements_analyzer,
+        issue_kind,
+        description,
+        message,
+        pos,
+        analysis_data,
+        function_context,
+    );
+}
+
+pub(crate) fn check_classlike(
+    statements_analyzer: &StatementsAnalyzer,
+    classlike_name: &StrId,
+    pos: HPos,
+    analysis_data: &mut FunctionAnalysisData,
+    function_context: &FunctionContext,
+) {
+    if function_context.calling_class.as_ref() == Some(classlike_name) {
+        return;
+    }
+
+    let message = if let Some(message) = statements_analyzer
+        .get_codebase()
+        .classlike_infos
+        .get(classlike_name)
+        .and_then(|classlike_storage| classlike_storage.deprecated.as_ref())
+    {
+        message
+    } else {
+        return;
+    };
+
+    add_deprecation_issue(
+        statements_analyzer,
+        IssueKind::DeprecatedClass,
+        format!(
+            "Class {}",
+            statements_analyzer.get_interner().lookup(classlike_name)
+        ),
+        message,
+        pos,
+        analysis_data,
+        function_context,
+    );
+}
+
+/// Expects the class that declares the property, since that's where its storage lives
+pub(crate) fn check_property(
+    statements_analyzer: &StatementsAnalyzer,
+    declaring_classlike_name: &StrId,
+    property_name: &StrId,
+    pos: HPos,
+    analysis_data: &mut FunctionAnalysisData,
+    function_context: &FunctionContext,
+) {
+    if function_context.calling_class.as_ref() == Some(declaring_classlike_name) {
+        return;
+    }
+
+    let message = if let Some(message) = statements_analyzer
+        .get_codebase()
+        .classlike_infos
+        .get(declaring_classlike_name)
+        .and_then(|classlike_storage| classlike_storage.properties.get(property_name))
+        .and_then(|property_storage| property_storage.deprecated.as_ref())
+    {
+        message
+    } else {
+        return;
+    };
+
+    let interner = statements_analyzer.get_interner();
+
+    add_deprecation_issue(
+        statements_analyzer,
+        IssueKind::DeprecatedProperty,
+        format!(
+            "Property {}::${}",
+            interner.lookup(declaring_classlike_name),
+            interner.lookup(property_name)
+        ),
+        message,
+        pos,
+        analysis_data,
+        function_context,
+    );
+}
+
+/// Checks the classes referenced by a type hint
+pub(crate) fn check_type_hint(
+    statements_analyzer: &StatementsAnalyzer,
+    type_hint: &TUnion,
+    pos: HPos,
+    analysis_data: &mut FunctionAnalysisData,
+    function_context: &FunctionContext,
+) {
+    for name in type_hint.get_referenced_type_names() {
+        check_classlike(
+            statements_analyzer,
+            &name,
+            pos,
+            analysis_data,
+            function_context,
+        );
+    }
+}
+
+fn add_deprecation_issue(
+    statements_analyzer: &StatementsAnalyzer,
+    issue_kind: IssueKind,
+    description: String,
+    message: &str,
+    pos: HPos,
+    analysis_data: &mut FunctionAnalysisData,
+    function_context: &FunctionContext,
+) {
+    analysis_data.maybe_add_issue(
+        Issue::new(
+            issue_kind,
+            if message.is_empty() {
+                format!("{} is deprecated", description)
+            } else {
+                format!("{} is deprecated: {}", description, message)
+            },
+            pos,
+            &function_context.calling_functionlike_id,
+        ),
+        statements_analyzer.get_config(),
+        statements_analyzer.get_file_path_actual(),
+    );
+}
@@ -6,7 +6,7 @@ use crate::function_analysis_data::FunctionAnalysisData;
 use crate::scope_analyzer::ScopeAnalyzer;
 use crate::scope_context::ScopeContext;
 use crate::statements_analyzer::StatementsAnalyzer;
-use hakana_reflection_info::data_flow::graph::{GraphKind, WholeProgramKind};
+use hakana_reflection_info::data_flow::graph::{GraphKind, TaintCutoffReason, WholeProgramKind};
 use hakana_reflection_info::data_flow::node::{DataFlowNode, DataFlowNodeKind};
 use hakana_reflection_info::data_flow::path::PathKind;
 use hakana_reflection_info::function_context::FunctionLikeIdentifier;
@@ -585,22 +585,47 @@ fn add_dataflow(
     let data_flow_graph = &mut analysis_data.data_flow_graph;
 
     if let GraphKind::WholeProgram(WholeProgramKind::Taint) = &data_flow_graph.kind {
-        if !input_type.has_taintable_value() || !param_type.has_taintable_value() {
-            return;
-        }
-
-        if !context.allow_taints || ignore_taints {
-            return;
-        }
-
-        for at in &param_type.types {
-            if let Some(shape_name) = at.get_shape_name() {
-                if let Some(t) = codebase.type_definitions.get(shape_name) {
-                    if t.shape_field_taints.is_some() {
-                        return;
-                    }
+        if !input_type.has_taintable_value()
+            || !param_type.has_taintable_value()
+            || ignore_taints
+            || !context.allow_taints
+            || param_type.types.iter().any(|at| {
+                at.get_shape_name()
+                    .and_then(|shape_name| codebase.type_definitions.get(shape_name))
+                    .map_or(false, |t| t.shape_field_taints.is_some())
+            })
+        {
+            if statements_analyzer
+                .get_config()
+                .security_config
+                .record_cutoffs
+            {
+                let cutoff_reason = if !input_type.has_taintable_value() {
+                    TaintCutoffReason::NonTaintableType(
+                        input_type.get_id(Some(statements_analyzer.get_interner())),
+                    )
+                } else if !param_type.has_taintable_value() {
+                    TaintCutoffReason::NonTaintableType(
+                        param_type.get_id(Some(statements_analyzer.get_interner())),
+                    )
+                } else if ignore_taints {
+                    TaintCutoffReason::IgnorePath
+                } else if !context.allow_taints {
+                    TaintCutoffReason::IgnorePathIfTrue
+                } else {
+                    TaintCutoffReason::ShapeSource
+                };
+
+                for parent_node in &input_type.parent_nodes {
+                    data_flow_graph.add_cutoff(
+                        &parent_node.id,
+                        cutoff_reason.clone(),
+                        statements_analyzer.get_hpos(input_expr.pos()),
+                    );
                 }
             }
+
+            return;
         }
     }
 
@@ -1,6 +1,7 @@
 use std::rc::Rc;
 
 use hakana_reflection_info::analysis_result::Replacement;
+use hakana_reflection_info::functionlike_identifier::FunctionLikeIdentifier;
 use hakana_reflection_info::issue::{Issue, IssueKind};
 use hakana_reflection_info::method_identifier::MethodIdentifier;
 use hakana_reflection_info::{
@@ -25,11 +26,13 @@ use rustc_hash::{FxHashMap, FxHashSet};
 use crate::expr::fetch::array_fetch_analyzer::add_array_fetch_dataflow;
 use crate::stmt_analyzer::AnalysisError;
 use crate::{
+    architecture_analyzer, banned_function_analyzer, deprecation_analyzer,
     expr::{
         call_analyzer::check_method_args, expression_identifier,
         fetch::array_fetch_analyzer::handle_array_access_on_dict,
     },
     function_analysis_data::FunctionAnalysisData,
+    module_analyzer,
     scope_analyzer::ScopeAnalyzer,
     scope_context::ScopeContext,
     statements_analyzer::StatementsAnalyzer,
@@ -89,6 +92,21 @@ pub(crate) fn analyze(
             false,
         );
 
+    if let Some(method_name_pos) = method_name_pos {
+        analysis_data.set_symbol_position(
+            method_name_pos,
+            (declaring_method_id.0, declaring_method_id.1),
+        );
+
+        architecture_analyzer::check_reference(
+            statements_analyzer,
+            (declaring_method_id.0, declaring_method_id.1),
+            statements_analyzer.get_hpos(method_name_pos),
+            analysis_data,
+            &context.function_context,
+        );
+    }
+
     if let Some(overridden_classlikes) = classlike_storage
         .overridden_method_ids
         .get(&declaring_method_id.1)
@@ -130,6 +148,38 @@ pub(crate) fn analyze(
 
     let functionlike_storage = codebase.get_method(&declaring_method_id).unwrap();
 
+    let functionlike_id =
+        FunctionLikeIdentifier::Method(declaring_method_id.0, declaring_method_id.1);
+    let method_name_hpos = statements_analyzer.get_hpos(method_name_pos.unwrap_or(pos));
+
+    deprecation_analyzer::check_functionlike(
+        statements_analyzer,
+        &functionlike_id,
+        functionlike_storage,
+        method_name_hpos,
+        analysis_data,
+        &context.function_context,
+    );
+
+    module_analyzer::check_functionlike(
+        statements_analyzer,
+        &functionlike_id,
+        functionlike_storage,
+        method_name_hpos,
+        analysis_data,
+        &context.function_context,
+    );
+
+    if let Some(method_name_pos) = method_name_pos {
+        banned_function_analyzer::check_call(
+            statements_analyzer,
+            &FunctionLikeIdentifier::Method(classlike_name, *method_name),
+            method_name_pos,
+            analysis_data,
+            &context.function_context,
+        );
+    }
+
     let functionlike_template_types = functionlike_storage.template_types.clone();
 
     let mut template_result = TemplateResult::new(
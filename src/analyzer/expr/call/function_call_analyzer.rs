@@ -17,7 +17,10 @@ use crate::scope_analyzer::ScopeAnalyzer;
 use crate::scope_context::ScopeContext;
 use crate::statements_analyzer::StatementsAnalyzer;
 use crate::stmt_analyzer::AnalysisError;
-use crate::{expression_analyzer, formula_generator};
+use crate::{
+    architecture_analyzer, banned_function_analyzer, deprecation_analyzer, expression_analyzer,
+    formula_generator, module_analyzer,
+};
 use hakana_reflection_info::assertion::Assertion;
 use hakana_reflection_info::data_flow::graph::GraphKind;
 use hakana_reflection_info::functionlike_identifier::FunctionLikeIdentifier;
@@ -139,6 +142,16 @@ pub(crate) fn analyze(
             name,
             false,
         );
+
+        analysis_data.set_symbol_position(expr.0 .0, (name, StrId::EMPTY));
+
+        architecture_analyzer::check_reference(
+            statements_analyzer,
+            (name, StrId::EMPTY),
+            statements_analyzer.get_hpos(expr.0 .0),
+            analysis_data,
+            &context.function_context,
+        );
     }
 
     let mut template_result = TemplateResult::new(IndexMap::new(), IndexMap::new());
@@ -151,6 +164,32 @@ pub(crate) fn analyze(
 
     let functionlike_id = FunctionLikeIdentifier::Function(function_storage.name);
 
+    deprecation_analyzer::check_functionlike(
+        statements_analyzer,
+        &functionlike_id,
+        function_storage,
+        statements_analyzer.get_hpos(expr.0 .0),
+        analysis_data,
+        &context.function_context,
+    );
+
+    module_analyzer::check_functionlike(
+        statements_analyzer,
+        &functionlike_id,
+        function_storage,
+        statements_analyzer.get_hpos(expr.0 .0),
+        analysis_data,
+        &context.function_context,
+    );
+
+    banned_function_analyzer::check_call(
+        statements_analyzer,
+        &functionlike_id,
+        expr.0 .0,
+        analysis_data,
+        &context.function_context,
+    );
+
     arguments_analyzer::check_arguments_match(
         statements_analyzer,
         expr.1,
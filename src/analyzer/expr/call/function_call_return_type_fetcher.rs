@@ -2,7 +2,7 @@ use bstr::BString;
 use hakana_reflection_info::classlike_info::ClassConstantType;
 use hakana_reflection_info::code_location::HPos;
 use hakana_reflection_info::codebase_info::CodebaseInfo;
-use hakana_reflection_info::data_flow::graph::{DataFlowGraph, GraphKind};
+use hakana_reflection_info::data_flow::graph::{DataFlowGraph, GraphKind, TaintCutoffReason};
 use hakana_reflection_info::data_flow::node::{DataFlowNode, DataFlowNodeKind};
 use hakana_reflection_info::data_flow::path::{ArrayDataKind, PathKind};
 use hakana_reflection_info::function_context::FunctionLikeIdentifier;
@@ -23,6 +23,7 @@ use rustc_hash::{FxHashMap, FxHashSet};
 use std::collections::BTreeMap;
 use std::sync::Arc;
 
+use crate::custom_hook::FunctionLikeReturnTypeData;
 use crate::expr::binop::concat_analyzer::analyze_concat_nodes;
 use crate::expr::fetch::array_fetch_analyzer::handle_array_access_on_dict;
 use crate::expr::variable_fetch_analyzer;
@@ -51,24 +52,28 @@ pub(crate) fn fetch(
     context: &mut ScopeContext,
 ) -> TUnion {
     let codebase = statements_analyzer.get_codebase();
-    let mut stmt_type = None;
-
-    if let FunctionLikeIdentifier::Function(name) = functionlike_id {
-        if let Some(t) = handle_special_functions(
-            statements_analyzer,
-            statements_analyzer.get_interner().lookup(name),
-            expr.2,
-            pos,
-            codebase,
-            analysis_data,
-            context,
-        ) {
-            stmt_type = Some(t);
+    let mut stmt_type = get_hook_return_type(
+        statements_analyzer,
+        functionlike_id,
+        functionlike_id,
+        expr.2,
+        analysis_data,
+    );
+
+    if stmt_type.is_none() {
+        if let FunctionLikeIdentifier::Function(name) = functionlike_id {
+            stmt_type = handle_special_functions(
+                statements_analyzer,
+                statements_analyzer.get_interner().lookup(name),
+                expr.2,
+                pos,
+                codebase,
+                analysis_data,
+                context,
+            );
         }
     }
 
-    // todo support custom return type providers for functions
-
     let stmt_type = if let Some(stmt_type) = stmt_type {
         stmt_type
     } else if let Some(function_return_type) = &function_storage.return_type {
@@ -161,6 +166,34 @@ pub(crate) fn fetch(
     )
 }
 
+/// Asks any configured hooks for the return type of a function or method call
+pub(crate) fn get_hook_return_type(
+    statements_analyzer: &StatementsAnalyzer,
+    functionlike_id: &FunctionLikeIdentifier,
+    declaring_functionlike_id: &FunctionLikeIdentifier,
+    args: &[(ast_defs::ParamKind, aast::Expr<(), ()>)],
+    analysis_data: &FunctionAnalysisData,
+) -> Option<TUnion> {
+    let hooks = &statements_analyzer.get_config().hooks;
+
+    if hooks.is_empty() {
+        return None;
+    }
+
+    hooks.iter().find_map(|hook| {
+        hook.get_functionlike_return_type(FunctionLikeReturnTypeData {
+            functionlike_id,
+            declaring_functionlike_id,
+            arg_types: args
+                .iter()
+                .map(|(_, arg_expr)| analysis_data.get_expr_type(arg_expr.pos()))
+                .collect(),
+            codebase: statements_analyzer.get_codebase(),
+            interner: statements_analyzer.get_interner(),
+        })
+    })
+}
+
 fn handle_special_functions(
     statements_analyzer: &StatementsAnalyzer,
     name: &str,
@@ -711,6 +744,18 @@ fn add_dataflow(
 
     if let GraphKind::WholeProgram(_) = &data_flow_graph.kind {
         if !context.allow_taints {
+            if statements_analyzer
+                .get_config()
+                .security_config
+                .record_cutoffs
+            {
+                data_flow_graph.add_cutoff(
+                    &functionlike_id.to_string(statements_analyzer.get_interner()),
+                    TaintCutoffReason::IgnorePathIfTrue,
+                    statements_analyzer.get_hpos(pos),
+                );
+            }
+
             return stmt_type;
         }
     }
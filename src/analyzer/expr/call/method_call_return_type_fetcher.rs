@@ -5,7 +5,7 @@ use hakana_reflection_info::StrId;
 use oxidized::{aast, ast_defs};
 use rustc_hash::{FxHashMap, FxHashSet};
 
-use hakana_reflection_info::data_flow::graph::GraphKind;
+use hakana_reflection_info::data_flow::graph::{GraphKind, TaintCutoffReason};
 use hakana_reflection_info::data_flow::node::{DataFlowNode, DataFlowNodeKind};
 use hakana_reflection_info::data_flow::path::PathKind;
 use hakana_reflection_info::method_identifier::MethodIdentifier;
@@ -25,7 +25,7 @@ use crate::statements_analyzer::StatementsAnalyzer;
 use hakana_reflection_info::functionlike_info::FunctionLikeInfo;
 use hakana_type::template::{TemplateBound, TemplateResult};
 
-use super::function_call_return_type_fetcher::add_special_param_dataflow;
+use super::function_call_return_type_fetcher::{add_special_param_dataflow, get_hook_return_type};
 
 pub(crate) fn fetch(
     statements_analyzer: &StatementsAnalyzer,
@@ -48,7 +48,15 @@ pub(crate) fn fetch(
 ) -> TUnion {
     let codebase = statements_analyzer.get_codebase();
 
-    let mut return_type_candidate = if let Some(return_type) =
+    let mut return_type_candidate = if let Some(return_type) = get_hook_return_type(
+        statements_analyzer,
+        &FunctionLikeIdentifier::Method(method_id.0, method_id.1),
+        &FunctionLikeIdentifier::Method(declaring_method_id.0, declaring_method_id.1),
+        call_expr.1,
+        analysis_data,
+    ) {
+        return_type
+    } else if let Some(return_type) =
         get_special_method_return(method_id, statements_analyzer.get_interner())
     {
         return_type
@@ -237,6 +245,18 @@ fn add_dataflow(
 
     if let GraphKind::WholeProgram(_) = &data_flow_graph.kind {
         if !context.allow_taints {
+            if statements_analyzer
+                .get_config()
+                .security_config
+                .record_cutoffs
+            {
+                data_flow_graph.add_cutoff(
+                    &method_id.to_string(statements_analyzer.get_interner()),
+                    TaintCutoffReason::IgnorePathIfTrue,
+                    statements_analyzer.get_hpos(call_pos),
+                );
+            }
+
             return return_type_candidate;
         }
     }
@@ -4,13 +4,17 @@ use hakana_reflection_info::classlike_info::Variance;
 use hakana_reflection_info::{StrId, EFFECT_WRITE_GLOBALS};
 
 use hakana_reflection_info::data_flow::node::DataFlowNode;
+use hakana_reflection_info::functionlike_identifier::FunctionLikeIdentifier;
 use hakana_reflection_info::functionlike_info::FunctionLikeInfo;
 use hakana_type::template::standin_type_replacer::get_most_specific_type_from_bounds;
 use rustc_hash::{FxHashMap, FxHashSet};
 
+use crate::architecture_analyzer;
+use crate::deprecation_analyzer;
 use crate::expr::call_analyzer::{check_method_args, get_generic_param_for_offset};
 use crate::expression_analyzer;
 use crate::function_analysis_data::FunctionAnalysisData;
+use crate::module_analyzer;
 use crate::scope_analyzer::ScopeAnalyzer;
 use crate::scope_context::ScopeContext;
 use crate::statements_analyzer::StatementsAnalyzer;
@@ -98,6 +102,16 @@ pub(crate) fn analyze(
                                 ));
                             };
 
+                        analysis_data.set_symbol_position(&id.0, (name_string, StrId::EMPTY));
+
+                        architecture_analyzer::check_reference(
+                            statements_analyzer,
+                            (name_string, StrId::EMPTY),
+                            statements_analyzer.get_hpos(&id.0),
+                            analysis_data,
+                            &context.function_context,
+                        );
+
                         get_named_object(name_string)
                     }
                 }
@@ -296,15 +310,23 @@ fn analyze_named_constructor(
         // todo complain about abstract instantiation
     }
 
-    if storage.is_deprecated
-        && if let Some(calling_class) = &context.function_context.calling_class {
-            calling_class != &classlike_name
-        } else {
-            true
-        }
-    {
-        // todo complain about deprecated class
-    }
+    let classlike_name_pos = statements_analyzer.get_hpos(expr.0 .1);
+
+    deprecation_analyzer::check_classlike(
+        statements_analyzer,
+        &classlike_name,
+        classlike_name_pos,
+        analysis_data,
+        &context.function_context,
+    );
+
+    module_analyzer::check_classlike(
+        statements_analyzer,
+        &classlike_name,
+        classlike_name_pos,
+        analysis_data,
+        &context.function_context,
+    );
 
     let mut generic_type_params = None;
 
@@ -345,6 +367,15 @@ fn analyze_named_constructor(
 
         let method_storage = codebase.get_method(&declaring_method_id).unwrap();
 
+        deprecation_analyzer::check_functionlike(
+            statements_analyzer,
+            &FunctionLikeIdentifier::Method(declaring_method_id.0, declaring_method_id.1),
+            method_storage,
+            statements_analyzer.get_hpos(pos),
+            analysis_data,
+            &context.function_context,
+        );
+
         check_method_args(
             statements_analyzer,
             analysis_data,
@@ -1,3 +1,5 @@
+use crate::architecture_analyzer;
+use crate::deprecation_analyzer;
 use crate::expression_analyzer;
 use crate::function_analysis_data::FunctionAnalysisData;
 use crate::scope_analyzer::ScopeAnalyzer;
@@ -5,7 +7,7 @@ use crate::scope_context::ScopeContext;
 use crate::statements_analyzer::StatementsAnalyzer;
 use crate::stmt_analyzer::AnalysisError;
 use hakana_reflection_info::t_atomic::TAtomic;
-use hakana_reflection_info::EFFECT_WRITE_PROPS;
+use hakana_reflection_info::{StrId, EFFECT_WRITE_PROPS};
 use hakana_type::{get_mixed_any, get_named_object, wrap_atomic};
 use oxidized::pos::Pos;
 use oxidized::{aast, ast_defs};
@@ -105,6 +107,25 @@ pub(crate) fn analyze(
 
                             classlike_name = Some(name_string);
 
+                            analysis_data
+                                .set_symbol_position(&id.0, (name_string, StrId::EMPTY));
+
+                            architecture_analyzer::check_reference(
+                                statements_analyzer,
+                                (name_string, StrId::EMPTY),
+                                statements_analyzer.get_hpos(&id.0),
+                                analysis_data,
+                                &context.function_context,
+                            );
+
+                            deprecation_analyzer::check_classlike(
+                                statements_analyzer,
+                                &name_string,
+                                statements_analyzer.get_hpos(&id.0),
+                                analysis_data,
+                                &context.function_context,
+                            );
+
                             get_named_object(name_string)
                         } else {
                             return Err(AnalysisError::InternalError(
@@ -368,6 +368,7 @@ pub(crate) fn apply_effects(
             (pos.start_offset() as u32, pos.end_offset() as u32),
             EFFECT_IMPURE,
         );
+        analysis_data.add_callee_effects(pos, EFFECT_IMPURE);
         return;
     }
 
@@ -378,6 +379,7 @@ pub(crate) fn apply_effects(
                     (pos.start_offset() as u32, pos.end_offset() as u32),
                     stored_effects,
                 );
+                analysis_data.add_callee_effects(pos, stored_effects);
             }
         }
         FnEffect::Arg(arg_offset) => {
@@ -388,17 +390,13 @@ pub(crate) fn apply_effects(
                 )) {
                     for arg_atomic_type in &arg_type.types {
                         if let TAtomic::TClosure { effects, .. } = arg_atomic_type {
-                            if let Some(evaluated_effects) = effects {
-                                analysis_data.expr_effects.insert(
-                                    (pos.start_offset() as u32, pos.end_offset() as u32),
-                                    *evaluated_effects,
-                                );
-                            } else {
-                                analysis_data.expr_effects.insert(
-                                    (pos.start_offset() as u32, pos.end_offset() as u32),
-                                    EFFECT_IMPURE,
-                                );
-                            }
+                            let evaluated_effects = effects.unwrap_or(EFFECT_IMPURE);
+
+                            analysis_data.expr_effects.insert(
+                                (pos.start_offset() as u32, pos.end_offset() as u32),
+                                evaluated_effects,
+                            );
+                            analysis_data.add_callee_effects(pos, evaluated_effects);
                         }
                     }
                 }
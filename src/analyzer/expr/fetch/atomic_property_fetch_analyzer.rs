@@ -1,6 +1,7 @@
 use crate::scope_analyzer::ScopeAnalyzer;
 use crate::stmt_analyzer::AnalysisError;
 use crate::{
+    architecture_analyzer, deprecation_analyzer,
     expr::call::arguments_analyzer::get_template_types_for_call,
     function_analysis_data::FunctionAnalysisData,
 };
@@ -139,6 +140,25 @@ pub(crate) fn analyze(
             false,
         );
 
+    analysis_data.set_symbol_position(expr.1.pos(), (*declaring_property_class, prop_name));
+
+    architecture_analyzer::check_reference(
+        statements_analyzer,
+        (*declaring_property_class, prop_name),
+        statements_analyzer.get_hpos(expr.1.pos()),
+        analysis_data,
+        &context.function_context,
+    );
+
+    deprecation_analyzer::check_property(
+        statements_analyzer,
+        declaring_property_class,
+        &prop_name,
+        statements_analyzer.get_hpos(expr.1.pos()),
+        analysis_data,
+        &context.function_context,
+    );
+
     // TODO: self::propertyFetchCanBeAnalyzed
 
     // TODO: handleNonExistentProperty
@@ -1,6 +1,8 @@
 use crate::function_analysis_data::FunctionAnalysisData;
 use crate::stmt_analyzer::AnalysisError;
-use crate::{expression_analyzer, scope_analyzer::ScopeAnalyzer};
+use crate::{
+    architecture_analyzer, deprecation_analyzer, expression_analyzer, scope_analyzer::ScopeAnalyzer,
+};
 use crate::{scope_context::ScopeContext, statements_analyzer::StatementsAnalyzer};
 use hakana_reflection_info::ast::get_id_name;
 use hakana_reflection_info::codebase_info::CodebaseInfo;
@@ -42,7 +44,29 @@ pub(crate) fn analyze(
                     &mut is_static,
                     statements_analyzer.get_file_analyzer().resolved_names,
                 ) {
-                    Some(value) => value,
+                    Some(value) => {
+                        analysis_data.set_symbol_position(&id.0, (value, StrId::EMPTY));
+
+                        architecture_analyzer::check_reference(
+                            statements_analyzer,
+                            (value, StrId::EMPTY),
+                            statements_analyzer.get_hpos(&id.0),
+                            analysis_data,
+                            &context.function_context,
+                        );
+
+                        if id.1 != "parent" {
+                            deprecation_analyzer::check_classlike(
+                                statements_analyzer,
+                                &value,
+                                statements_analyzer.get_hpos(&id.0),
+                                analysis_data,
+                                &context.function_context,
+                            );
+                        }
+
+                        value
+                    }
                     None => return Err(AnalysisError::UserError),
                 }
             } else {
@@ -72,6 +96,7 @@ pub(crate) fn analyze(
                                         context,
                                         name,
                                         const_name,
+                                        expr.1 .0,
                                         *is_this,
                                         statements_analyzer,
                                         pos,
@@ -128,6 +153,7 @@ pub(crate) fn analyze(
         context,
         &classlike_name,
         const_name,
+        expr.1 .0,
         is_static,
         statements_analyzer,
         pos,
@@ -144,6 +170,7 @@ fn analyse_known_class_constant(
     context: &mut ScopeContext,
     classlike_name: &StrId,
     const_name: &String,
+    const_name_pos: &Pos,
     is_this: bool,
     statements_analyzer: &StatementsAnalyzer,
     pos: &Pos,
@@ -246,6 +273,16 @@ fn analyse_known_class_constant(
             false,
         );
 
+    analysis_data.set_symbol_position(const_name_pos, (*classlike_name, const_name));
+
+    architecture_analyzer::check_reference(
+        statements_analyzer,
+        (*classlike_name, const_name),
+        statements_analyzer.get_hpos(const_name_pos),
+        analysis_data,
+        &context.function_context,
+    );
+
     let classlike_storage = codebase.classlike_infos.get(classlike_name).unwrap();
 
     if !classlike_storage.constants.contains_key(&const_name) {
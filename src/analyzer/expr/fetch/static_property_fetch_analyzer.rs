@@ -4,12 +4,14 @@ use super::{
 };
 use crate::function_analysis_data::FunctionAnalysisData;
 use crate::stmt_analyzer::AnalysisError;
-use crate::{expression_analyzer, scope_analyzer::ScopeAnalyzer};
+use crate::{
+    architecture_analyzer, deprecation_analyzer, expression_analyzer, scope_analyzer::ScopeAnalyzer,
+};
 use crate::{scope_context::ScopeContext, statements_analyzer::StatementsAnalyzer};
 use hakana_reflection_info::ast::get_id_name;
 use hakana_reflection_info::issue::{Issue, IssueKind};
 use hakana_reflection_info::t_atomic::TAtomic;
-use hakana_reflection_info::EFFECT_READ_PROPS;
+use hakana_reflection_info::{StrId, EFFECT_READ_PROPS};
 use hakana_type::type_expander::TypeExpansionOptions;
 use hakana_type::{
     get_named_object,
@@ -37,7 +39,7 @@ pub(crate) fn analyze(
         aast::ClassId_::CIexpr(lhs_expr) => {
             if let aast::Expr_::Id(id) = &lhs_expr.2 {
                 let mut is_static = false;
-                if let Some(id) = get_id_name(
+                if let Some(name) = get_id_name(
                     id,
                     &context.function_context.calling_class,
                     context.function_context.calling_class_final,
@@ -45,7 +47,27 @@ pub(crate) fn analyze(
                     &mut is_static,
                     statements_analyzer.get_file_analyzer().resolved_names,
                 ) {
-                    id
+                    analysis_data.set_symbol_position(&id.0, (name, StrId::EMPTY));
+
+                    architecture_analyzer::check_reference(
+                        statements_analyzer,
+                        (name, StrId::EMPTY),
+                        statements_analyzer.get_hpos(&id.0),
+                        analysis_data,
+                        &context.function_context,
+                    );
+
+                    if id.1 != "parent" {
+                        deprecation_analyzer::check_classlike(
+                            statements_analyzer,
+                            &name,
+                            statements_analyzer.get_hpos(&id.0),
+                            analysis_data,
+                            &context.function_context,
+                        );
+                    }
+
+                    name
                 } else {
                     return Err(AnalysisError::InternalError(
                         "Unable to resolve static classlike name".to_string(),
@@ -163,6 +185,32 @@ pub(crate) fn analyze(
             false,
         );
 
+    if let aast::ClassGetExpr::CGstring(str) = &stmt_name {
+        let declaring_property_class = codebase
+            .get_declaring_class_for_property(&property_id.0, &property_id.1)
+            .copied()
+            .unwrap_or(property_id.0);
+
+        analysis_data.set_symbol_position(&str.0, (declaring_property_class, property_id.1));
+
+        architecture_analyzer::check_reference(
+            statements_analyzer,
+            (declaring_property_class, property_id.1),
+            statements_analyzer.get_hpos(&str.0),
+            analysis_data,
+            &context.function_context,
+        );
+
+        deprecation_analyzer::check_property(
+            statements_analyzer,
+            &declaring_property_class,
+            &property_id.1,
+            statements_analyzer.get_hpos(&str.0),
+            analysis_data,
+            &context.function_context,
+        );
+    }
+
     // Handle scoped property fetches
     if context.has_variable(&var_id) {
         let mut stmt_type = (**context.vars_in_scope.get(&var_id).unwrap()).clone();
@@ -206,7 +254,6 @@ pub(crate) fn analyze(
         return Err(AnalysisError::UserError);
     };
 
-    // TODO AtomicPropertyFetchAnalyzer::checkPropertyDeprecation
     // TODO ClassLikeAnalyzer::checkPropertyVisibility
     // TODO if ($codebase->alter_code) {
 
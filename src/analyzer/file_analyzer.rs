@@ -10,10 +10,14 @@ use hakana_reflection_info::code_location::HPos;
 use hakana_reflection_info::codebase_info::CodebaseInfo;
 use hakana_reflection_info::data_flow::graph::DataFlowGraph;
 use hakana_reflection_info::function_context::FunctionContext;
+use hakana_reflection_info::issue::{Issue, IssueKind};
 use hakana_reflection_info::type_resolution::TypeResolutionContext;
 use hakana_reflection_info::{FileSource, Interner, StrId};
 use oxidized::aast;
+use oxidized::pos::Pos;
 use rustc_hash::FxHashMap;
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
 
 pub struct InternalError(pub String, pub HPos);
 
@@ -49,7 +53,7 @@ impl<'a> FileAnalyzer<'a> {
         &mut self,
         program: &aast::Program<(), ()>,
         analysis_result: &mut AnalysisResult,
-    ) -> Result<(), InternalError> {
+    ) {
         let mut analysis_data = FunctionAnalysisData::new(
             DataFlowGraph::new(self.analysis_config.graph_kind),
             &self.file_source,
@@ -75,30 +79,26 @@ impl<'a> FileAnalyzer<'a> {
                 self.namespace_name = Some(namespace_declaration.0 .1.to_string());
 
                 for namespace_statement in namespace_declaration.1 {
-                    def_analyzer::analyze(
-                        self,
+                    self.analyze_def(
                         &statements_analyzer,
                         namespace_statement,
                         &mut context,
-                        &mut None,
                         &mut analysis_data,
                         analysis_result,
-                    )?;
+                    );
                 }
 
                 if !namespace_declaration.1.is_empty() {
                     self.namespace_name = None;
                 }
             } else {
-                def_analyzer::analyze(
-                    self,
+                self.analyze_def(
                     &statements_analyzer,
                     declaration,
                     &mut context,
-                    &mut None,
                     &mut analysis_data,
                     analysis_result,
-                )?;
+                );
             }
         }
 
@@ -109,10 +109,62 @@ impl<'a> FileAnalyzer<'a> {
                 .get_file_analyzer()
                 .get_file_source()
                 .file_path,
+            &None,
             false,
+            self.analysis_config.collect_expr_types,
         );
+    }
 
-        Ok(())
+    /// Analyzes a single def, reporting any internal error or panic as an issue so that
+    /// one bad def doesn't stop the rest of the file from being analyzed
+    fn analyze_def(
+        &mut self,
+        statements_analyzer: &StatementsAnalyzer,
+        def: &aast::Def<(), ()>,
+        context: &mut ScopeContext,
+        analysis_data: &mut FunctionAnalysisData,
+        analysis_result: &mut AnalysisResult,
+    ) {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            def_analyzer::analyze(
+                self,
+                statements_analyzer,
+                def,
+                context,
+                &mut None,
+                analysis_data,
+                analysis_result,
+            )
+        }));
+
+        let error = match result {
+            Ok(Ok(())) => return,
+            Ok(Err(error)) => error,
+            Err(panic_payload) => {
+                let (def_description, def_pos) = get_def_description(def);
+
+                InternalError(
+                    format!(
+                        "Panic while analyzing {}: {}",
+                        def_description,
+                        get_panic_message(&*panic_payload)
+                    ),
+                    if let Some(def_pos) = def_pos {
+                        statements_analyzer.get_hpos(def_pos)
+                    } else {
+                        HPos::file_start(self.file_source.file_path)
+                    },
+                )
+            }
+        };
+
+        // internal errors can't be suppressed, so they skip the usual issue filtering
+        analysis_data.issues_to_emit.push(Issue::new(
+            IssueKind::InternalError,
+            error.0,
+            error.1,
+            &None,
+        ));
     }
 
     pub fn get_file_source(&self) -> &FileSource {
@@ -141,3 +193,25 @@ impl ScopeAnalyzer for FileAnalyzer<'_> {
         self.analysis_config
     }
 }
+
+fn get_def_description(def: &aast::Def<(), ()>) -> (String, Option<&Pos>) {
+    match def {
+        aast::Def::Fun(boxed) => (format!("function {}", boxed.name.1), Some(boxed.name.pos())),
+        aast::Def::Class(boxed) => (format!("class {}", boxed.name.1), Some(boxed.name.pos())),
+        aast::Def::Constant(boxed) => {
+            (format!("constant {}", boxed.name.1), Some(boxed.name.pos()))
+        }
+        aast::Def::Stmt(boxed) => ("top-level statement".to_string(), Some(&boxed.0)),
+        _ => ("declaration".to_string(), None),
+    }
+}
+
+pub fn get_panic_message(panic_payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = panic_payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic_payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
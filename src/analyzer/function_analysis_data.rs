@@ -1,7 +1,6 @@
 use crate::{config::Config, scope_context::CaseScope};
 use hakana_reflection_info::analysis_result::Replacement;
 use hakana_reflection_info::code_location::StmtStart;
-use hakana_reflection_info::FileSource;
 use hakana_reflection_info::{
     assertion::Assertion,
     data_flow::graph::{DataFlowGraph, GraphKind, WholeProgramKind},
@@ -10,6 +9,7 @@ use hakana_reflection_info::{
     symbol_references::SymbolReferences,
     t_union::TUnion,
 };
+use hakana_reflection_info::{FileSource, StrId};
 use hakana_type::template::TemplateBound;
 use oxidized::{ast_defs::Pos, prim_defs::Comment};
 use rustc_hash::{FxHashMap, FxHashSet};
@@ -17,12 +17,14 @@ use std::{collections::BTreeMap, rc::Rc};
 
 pub struct FunctionAnalysisData {
     pub expr_types: FxHashMap<(u32, u32), Rc<TUnion>>,
+    pub symbol_positions: FxHashMap<(u32, u32), (StrId, StrId)>,
     pub if_true_assertions: FxHashMap<(u32, u32), FxHashMap<String, Vec<Assertion>>>,
     pub if_false_assertions: FxHashMap<(u32, u32), FxHashMap<String, Vec<Assertion>>>,
     pub data_flow_graph: DataFlowGraph,
     pub case_scopes: Vec<CaseScope>,
     pub issues_to_emit: Vec<Issue>,
     pub inferred_return_types: Vec<TUnion>,
+    pub has_valueless_return: bool,
     pub fully_matched_switch_offsets: FxHashSet<usize>,
     pub closures: FxHashMap<Pos, FunctionLikeInfo>,
     pub closure_spans: Vec<(u32, u32)>,
@@ -33,6 +35,7 @@ pub struct FunctionAnalysisData {
     pub symbol_references: SymbolReferences,
     pub issue_filter: Option<FxHashSet<IssueKind>>,
     pub expr_effects: FxHashMap<(u32, u32), u8>,
+    pub callee_effects: FxHashMap<(u32, u32), u8>,
     pub issue_counts: FxHashMap<IssueKind, usize>,
     recording_level: usize,
     recorded_issues: Vec<Vec<Issue>>,
@@ -55,10 +58,12 @@ impl FunctionAnalysisData {
     ) -> Self {
         Self {
             expr_types: FxHashMap::default(),
+            symbol_positions: FxHashMap::default(),
             data_flow_graph,
             case_scopes: Vec::new(),
             issues_to_emit: Vec::new(),
             inferred_return_types: Vec::new(),
+            has_valueless_return: false,
             fully_matched_switch_offsets: FxHashSet::default(),
             recording_level: 0,
             recorded_issues: vec![],
@@ -73,6 +78,7 @@ impl FunctionAnalysisData {
             symbol_references: SymbolReferences::new(),
             issue_filter: None,
             expr_effects: FxHashMap::default(),
+            callee_effects: FxHashMap::default(),
             hakana_fixme_or_ignores: hakana_fixme_or_ignores
                 .unwrap_or(get_hakana_fixmes_and_ignores(comments, all_custom_issues)),
             expr_fixme_positions: FxHashMap::default(),
@@ -123,30 +129,7 @@ impl FunctionAnalysisData {
         if let Some(insertion_start) = &issue.pos.insertion_start {
             self.add_replacement(
                 (insertion_start.offset, insertion_start.offset),
-                Replacement::Substitute(
-                    format!(
-                        "/* HAKANA_FIXME[{}]{} */{}",
-                        issue.kind.to_string(),
-                        if let IssueKind::UnusedParameter
-                        | IssueKind::UnusedAssignment
-                        | IssueKind::UnusedAssignmentInClosure
-                        | IssueKind::UnusedAssignmentStatement
-                        | IssueKind::UnusedStatement
-                        | IssueKind::UnusedFunction
-                        | IssueKind::UnusedPrivateMethod = issue.kind
-                        {
-                            "".to_string()
-                        } else {
-                            " ".to_string() + &issue.description
-                        },
-                        if insertion_start.add_newline {
-                            "\n".to_string() + &"\t".repeat(insertion_start.column as usize)
-                        } else {
-                            " ".to_string()
-                        }
-                    )
-                    .to_string(),
-                ),
+                Replacement::Substitute(issue.get_fixme_comment(insertion_start)),
             );
 
             true
@@ -403,6 +386,15 @@ impl FunctionAnalysisData {
         }
     }
 
+    /// Records the effects a call has because of the function it calls, which can't be
+    /// told apart from its arguments' effects once they're combined
+    pub(crate) fn add_callee_effects(&mut self, call_pos: &Pos, effects: u8) {
+        *self
+            .callee_effects
+            .entry((call_pos.start_offset() as u32, call_pos.end_offset() as u32))
+            .or_insert(0) |= effects;
+    }
+
     pub(crate) fn copy_effects(&mut self, source_pos_1: &Pos, destination_pos: &Pos) {
         self.expr_effects.insert(
             (
@@ -524,6 +516,14 @@ impl FunctionAnalysisData {
         }
     }
 
+    /// Records the symbol or class member referenced by the name at a given position,
+    /// so that the language server can answer definition and references requests
+    #[inline]
+    pub fn set_symbol_position(&mut self, pos: &Pos, symbol: (StrId, StrId)) {
+        self.symbol_positions
+            .insert((pos.start_offset() as u32, pos.end_offset() as u32), symbol);
+    }
+
     pub(crate) fn get_unused_hakana_fixme_positions(&self) -> Vec<(u32, u32, u32, u32, bool)> {
         let mut unused_fixme_positions = vec![];
 
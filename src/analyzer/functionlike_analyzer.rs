@@ -1,12 +1,17 @@
+use crate::architecture_analyzer;
 use crate::config::Config;
 use crate::custom_hook::FunctionLikeParamData;
 use crate::dataflow::unused_variable_analyzer::{
     add_unused_expression_replacements, check_variables_used,
 };
+use crate::deprecation_analyzer;
 use crate::expr::call_analyzer::reconcile_lower_bounds_with_upper_bounds;
 use crate::expr::fetch::atomic_property_fetch_analyzer;
 use crate::expression_analyzer;
 use crate::file_analyzer::InternalError;
+use crate::module_analyzer;
+use crate::purity_analyzer;
+use crate::return_type_fixer;
 use crate::scope_analyzer::ScopeAnalyzer;
 use crate::scope_context::ScopeContext;
 use crate::statements_analyzer::StatementsAnalyzer;
@@ -17,9 +22,10 @@ use hakana_reflection_info::analysis_result::{AnalysisResult, Replacement};
 use hakana_reflection_info::classlike_info::ClassLikeInfo;
 use hakana_reflection_info::code_location::{FilePath, HPos, StmtStart};
 use hakana_reflection_info::codebase_info::CodebaseInfo;
-use hakana_reflection_info::data_flow::graph::{DataFlowGraph, GraphKind};
+use hakana_reflection_info::data_flow::graph::{DataFlowGraph, GraphKind, WholeProgramKind};
 use hakana_reflection_info::data_flow::node::{DataFlowNode, DataFlowNodeKind, VariableSourceKind};
 use hakana_reflection_info::data_flow::path::PathKind;
+use hakana_reflection_info::data_flow::taint_summary::TaintSummary;
 use hakana_reflection_info::function_context::{FunctionContext, FunctionLikeIdentifier};
 use hakana_reflection_info::functionlike_info::{FnEffect, FunctionLikeInfo};
 use hakana_reflection_info::issue::{Issue, IssueKind};
@@ -465,6 +471,35 @@ impl<'a> FunctionLikeAnalyzer<'a> {
             analysis_data.issue_filter = Some(issue_filter.clone());
         }
 
+        if let (Some(return_type), Some(return_type_location)) = (
+            &functionlike_storage.return_type,
+            functionlike_storage.return_type_location,
+        ) {
+            deprecation_analyzer::check_type_hint(
+                statements_analyzer,
+                return_type,
+                return_type_location,
+                &mut analysis_data,
+                &context.function_context,
+            );
+
+            module_analyzer::check_type_hint(
+                statements_analyzer,
+                return_type,
+                return_type_location,
+                &mut analysis_data,
+                &context.function_context,
+            );
+
+            architecture_analyzer::check_type_hint(
+                statements_analyzer,
+                return_type,
+                return_type_location,
+                &mut analysis_data,
+                &context.function_context,
+            );
+        }
+
         let mut completed_analysis = false;
 
         match self.add_param_types_to_context(
@@ -587,6 +622,21 @@ impl<'a> FunctionLikeAnalyzer<'a> {
             );
         }
 
+        if completed_analysis
+            && (functionlike_storage.has_pure_attribute
+                || (config.check_pure_contexts
+                    && matches!(functionlike_storage.effects, FnEffect::Pure)))
+        {
+            purity_analyzer::check_function_body(
+                statements_analyzer,
+                fb_ast,
+                functionlike_storage.method_info.is_some()
+                    && functionlike_storage.name == StrId::CONSTRUCT,
+                &mut analysis_data,
+                &context.function_context,
+            );
+        }
+
         if config.remove_fixmes && parent_analysis_data.is_none() {
             for unused_fixme_position in analysis_data.get_unused_hakana_fixme_positions() {
                 analysis_data.add_replacement(
@@ -725,6 +775,21 @@ impl<'a> FunctionLikeAnalyzer<'a> {
             }
         }
 
+        if parent_analysis_data.is_none()
+            && completed_analysis
+            && config.issues_to_fix.contains(&IssueKind::MissingReturnType)
+            && !config.add_fixmes
+        {
+            return_type_fixer::fix_return_type(
+                statements_analyzer,
+                functionlike_storage,
+                params,
+                &inferred_return_type,
+                !context.has_returned,
+                &mut analysis_data,
+            );
+        }
+
         let mut effects = 0;
 
         if let FnEffect::Unknown = functionlike_storage.effects {
@@ -774,6 +839,16 @@ impl<'a> FunctionLikeAnalyzer<'a> {
                 .expr_effects
                 .extend(analysis_data.expr_effects);
 
+            if statements_analyzer.get_config().collect_expr_types {
+                parent_analysis_data
+                    .expr_types
+                    .extend(analysis_data.expr_types);
+
+                parent_analysis_data
+                    .symbol_positions
+                    .extend(analysis_data.symbol_positions);
+            }
+
             for (kind, count) in analysis_data.issue_counts {
                 *parent_analysis_data.issue_counts.entry(kind).or_insert(0) += count;
             }
@@ -829,7 +904,9 @@ impl<'a> FunctionLikeAnalyzer<'a> {
                     .get_file_analyzer()
                     .get_file_source()
                     .file_path,
+                &context.function_context.calling_functionlike_id,
                 functionlike_storage.ignore_taint_path,
+                statements_analyzer.get_config().collect_expr_types,
             );
         }
 
@@ -846,6 +923,36 @@ impl<'a> FunctionLikeAnalyzer<'a> {
     ) -> Result<(), AnalysisError> {
         for (i, param) in functionlike_storage.params.iter().enumerate() {
             let mut param_type = if let Some(param_type) = &param.signature_type {
+                let type_location = if let Some(type_location) = &param.signature_type_location {
+                    *type_location
+                } else {
+                    param.name_location
+                };
+
+                deprecation_analyzer::check_type_hint(
+                    statements_analyzer,
+                    param_type,
+                    type_location,
+                    analysis_data,
+                    &context.function_context,
+                );
+
+                module_analyzer::check_type_hint(
+                    statements_analyzer,
+                    param_type,
+                    type_location,
+                    analysis_data,
+                    &context.function_context,
+                );
+
+                architecture_analyzer::check_type_hint(
+                    statements_analyzer,
+                    param_type,
+                    type_location,
+                    analysis_data,
+                    &context.function_context,
+                );
+
                 for type_node in param_type.get_all_child_nodes() {
                     if let hakana_reflection_info::t_union::TypeNode::Atomic(atomic) = type_node {
                         match atomic {
@@ -1374,7 +1481,9 @@ pub(crate) fn update_analysis_result_with_tast(
     analysis_data: FunctionAnalysisData,
     analysis_result: &mut AnalysisResult,
     file_path: &FilePath,
+    calling_functionlike_id: &Option<FunctionLikeIdentifier>,
     ignore_taint_path: bool,
+    collect_expr_types: bool,
 ) {
     if !analysis_data.replacements.is_empty() {
         analysis_result
@@ -1395,6 +1504,25 @@ pub(crate) fn update_analysis_result_with_tast(
         }
     }
 
+    if collect_expr_types {
+        analysis_result
+            .expr_types
+            .entry(*file_path)
+            .or_default()
+            .extend(
+                analysis_data
+                    .expr_types
+                    .into_iter()
+                    .map(|(k, v)| (k, (*v).clone())),
+            );
+
+        analysis_result
+            .symbol_positions
+            .entry(*file_path)
+            .or_default()
+            .extend(analysis_data.symbol_positions);
+    }
+
     let mut issues_to_emit = analysis_data.issues_to_emit;
 
     issues_to_emit.sort_by(|a, b| a.pos.start_offset.partial_cmp(&b.pos.start_offset).unwrap());
@@ -1405,17 +1533,33 @@ pub(crate) fn update_analysis_result_with_tast(
         .or_default()
         .extend(issues_to_emit.into_iter().unique().collect::<Vec<_>>());
 
-    if let GraphKind::WholeProgram(_) = &analysis_data.data_flow_graph.kind {
+    analysis_result
+        .symbol_references
+        .extend(analysis_data.symbol_references);
+
+    if let GraphKind::WholeProgram(whole_program_kind) = &analysis_data.data_flow_graph.kind {
         if !ignore_taint_path {
-            analysis_result
-                .program_dataflow_graph
-                .add_graph(analysis_data.data_flow_graph);
+            if let WholeProgramKind::Taint = whole_program_kind {
+                // taint graphs are stored per function, so they can be cached and added to
+                // the whole-program graph once analysis is complete
+                analysis_result
+                    .taint_summaries
+                    .entry(*file_path)
+                    .or_default()
+                    .entry(match calling_functionlike_id {
+                        Some(FunctionLikeIdentifier::Function(id)) => (*id, StrId::EMPTY),
+                        Some(FunctionLikeIdentifier::Method(a, b)) => (*a, *b),
+                        None => (file_path.0, StrId::EMPTY),
+                    })
+                    .or_default()
+                    .extend(TaintSummary::new(analysis_data.data_flow_graph));
+            } else {
+                analysis_result
+                    .program_dataflow_graph
+                    .add_graph(analysis_data.data_flow_graph);
+            }
         }
     } else {
-        analysis_result
-            .symbol_references
-            .extend(analysis_data.symbol_references);
-
         for (source_id, c) in analysis_data.data_flow_graph.mixed_source_counts {
             if let Some(existing_count) = analysis_result.mixed_source_counts.get_mut(&source_id) {
                 existing_count.extend(c);
@@ -1,19 +1,30 @@
 mod algebra_analyzer;
+mod architecture_analyzer;
+mod banned_function_analyzer;
 mod classlike_analyzer;
 pub mod config;
 pub mod custom_hook;
 mod def_analyzer;
+mod deprecation_analyzer;
 pub mod expr;
 mod expression_analyzer;
 pub mod file_analyzer;
 mod formula_generator;
 mod functionlike_analyzer;
+mod module_analyzer;
+mod property_initialization_analyzer;
+mod purity_analyzer;
+mod return_type_fixer;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod plugin_hook;
 pub mod reconciler;
 pub mod scope_analyzer;
 pub mod scope_context;
 pub mod statements_analyzer;
 mod stmt;
 mod stmt_analyzer;
+mod template_variance_analyzer;
+mod type_hint_position_collector;
 
 pub mod dataflow;
 pub mod function_analysis_data;
@@ -0,0 +1,170 @@
+use hakana_reflection_info::code_location::{FilePath, HPos};
+use hakana_reflection_info::function_context::FunctionContext;
+use hakana_reflection_info::functionlike_identifier::FunctionLikeIdentifier;
+use hakana_reflection_info::functionlike_info::FunctionLikeInfo;
+use hakana_reflection_info::issue::{Issue, IssueKind};
+use hakana_reflection_info::t_union::TUnion;
+use hakana_reflection_info::StrId;
+
+use crate::function_analysis_data::FunctionAnalysisData;
+use crate::scope_analyzer::ScopeAnalyzer;
+use crate::statements_analyzer::StatementsAnalyzer;
+
+pub(crate) fn check_functionlike(
+    statements_analyzer: &StatementsAnalyzer,
+    functionlike_id: &FunctionLikeIdentifier,
+    functionlike_storage: &FunctionLikeInfo,
+    pos: HPos,
+    analysis_data: &mut FunctionAnalysisData,
+    function_context: &FunctionContext,
+) {
+    if !functionlike_storage.is_internal {
+        return;
+    }
+
+    let (issue_kind, description) = match functionlike_id {
+        FunctionLikeIdentifier::Function(_) => {
+            (IssueKind::InternalFunctionUsedOutsideModule, "Function")
+        }
+        FunctionLikeIdentifier::Method(_, _) => {
+            (IssueKind::InternalMethodUsedOutsideModule, "Method")
+        }
+    };
+
+    check_module(
+        statements_analyzer,
+        issue_kind,
+        format!(
+            "{} {}",
+            description,
+            functionlike_id.to_string(statements_analyzer.get_interner())
+        ),
+        &functionlike_storage.internal_to,
+        &functionlike_storage.def_location.file_path,
+        pos,
+        analysis_data,
+        function_context,
+    );
+}
+
+pub(crate) fn check_classlike(
+    statements_analyzer: &StatementsAnalyzer,
+    classlike_name: &StrId,
+    pos: HPos,
+    analysis_data: &mut FunctionAnalysisData,
+    function_context: &FunctionContext,
+) {
+    let classlike_storage = if let Some(classlike_storage) = statements_analyzer
+        .get_codebase()
+        .classlike_infos
+        .get(classlike_name)
+    {
+        classlike_storage
+    } else {
+        return;
+    };
+
+    if !classlike_storage.is_internal {
+        return;
+    }
+
+    check_module(
+        statements_analyzer,
+        IssueKind::InternalClassUsedOutsideModule,
+        format!(
+            "Class {}",
+            statements_analyzer.get_interner().lookup(classlike_name)
+        ),
+        &classlike_storage.internal_to,
+        &classlike_storage.def_location.file_path,
+        pos,
+        analysis_data,
+        function_context,
+    );
+}
+
+/// Checks the classes referenced by a type hint
+pub(crate) fn check_type_hint(
+    statements_analyzer: &StatementsAnalyzer,
+    type_hint: &TUnion,
+    pos: HPos,
+    analysis_data: &mut FunctionAnalysisData,
+    function_context: &FunctionContext,
+) {
+    for name in type_hint.get_referenced_type_names() {
+        check_classlike(
+            statements_analyzer,
+            &name,
+            pos,
+            analysis_data,
+            function_context,
+        );
+    }
+}
+
+/// Gets the module a file belongs to, either from a `module` declaration in the file
+/// or from the `modules` section of the config
+fn get_file_module<'a>(
+    statements_analyzer: &'a StatementsAnalyzer,
+    file_path: &FilePath,
+) -> Option<&'a String> {
+    if let Some(module) = statements_analyzer
+        .get_codebase()
+        .files
+        .get(file_path)
+        .and_then(|file_info| file_info.module.as_ref())
+    {
+        return Some(module);
+    }
+
+    statements_analyzer
+        .get_config()
+        .get_module_for_file(statements_analyzer.get_interner().lookup(&file_path.0))
+}
+
+fn check_module(
+    statements_analyzer: &StatementsAnalyzer,
+    issue_kind: IssueKind,
+    description: String,
+    internal_to: &Option<String>,
+    def_file_path: &FilePath,
+    pos: HPos,
+    analysis_data: &mut FunctionAnalysisData,
+    function_context: &FunctionContext,
+) {
+    let symbol_module = if let Some(symbol_module) = internal_to
+        .as_ref()
+        .or_else(|| get_file_module(statements_analyzer, def_file_path))
+    {
+        symbol_module
+    } else {
+        // an internal symbol outside of any module can't be used incorrectly
+        return;
+    };
+
+    let calling_module = get_file_module(statements_analyzer, statements_analyzer.get_file_path());
+
+    if calling_module == Some(symbol_module) {
+        return;
+    }
+
+    analysis_data.maybe_add_issue(
+        Issue::new(
+            issue_kind,
+            format!(
+                "{} is internal to module {}, and cannot be used {}",
+                description,
+                symbol_module,
+                if let Some(calling_module) = calling_module {
+                    format!("from module {}", calling_module)
+                } else {
+                    "outside of it".to_string()
+                }
+            ),
+            pos,
+            &function_context.calling_functionlike_id,
+        ),
+        statements_analyzer.get_config(),
+        statements_analyzer.get_file_path_actual(),
+    );
+}
@@ -0,0 +1,386 @@
+//! Hooks loaded at runtime from shared libraries, so that rules can be written and versioned
+//! separately from Hakana itself.
+//!
+//! A plugin exports `hakana_plugin_init`, which is called with the ABI version this build of
+//! Hakana supports and returns a pointer to a `HakanaPlugin` that lives as long as the library,
+//! or null if the plugin doesn't support that version. Everything passed across the boundary is
+//! `#[repr(C)]`, and all strings are UTF-8 that's only valid for the duration of the call.
+
+use std::error::Error;
+use std::ffi::c_void;
+
+use hakana_reflection_info::code_location::HPos;
+use hakana_reflection_info::function_context::FunctionLikeIdentifier;
+use hakana_reflection_info::issue::{Issue, IssueKind};
+use hakana_reflection_info::{Interner, StrId};
+use oxidized::aast;
+
+use crate::custom_hook::{AfterArgAnalysisData, AfterExprAnalysisData, CustomHook, InternalHook};
+use crate::function_analysis_data::FunctionAnalysisData;
+use crate::scope_analyzer::ScopeAnalyzer;
+use crate::statements_analyzer::StatementsAnalyzer;
+
+pub const HAKANA_PLUGIN_ABI_VERSION: u32 = 1;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct HakanaPluginStr {
+    pub ptr: *const u8,
+    pub len: usize,
+}
+
+impl HakanaPluginStr {
+    fn new(str: &str) -> Self {
+        Self {
+            ptr: str.as_ptr(),
+            len: str.len(),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The pointer must be null or point to `len` readable bytes
+    unsafe fn to_owned_string(self) -> String {
+        if self.ptr.is_null() {
+            return String::new();
+        }
+
+        String::from_utf8_lossy(std::slice::from_raw_parts(self.ptr, self.len)).into_owned()
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct HakanaPluginPos {
+    pub start_offset: u32,
+    pub end_offset: u32,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub start_column: u16,
+    pub end_column: u16,
+}
+
+impl From<HPos> for HakanaPluginPos {
+    fn from(pos: HPos) -> Self {
+        Self {
+            start_offset: pos.start_offset,
+            end_offset: pos.end_offset,
+            start_line: pos.start_line,
+            end_line: pos.end_line,
+            start_column: pos.start_column,
+            end_column: pos.end_column,
+        }
+    }
+}
+
+#[repr(C)]
+pub struct HakanaPluginExprData {
+    pub file_path: HakanaPluginStr,
+    /// The function or method (`Foo::bar`) containing the expression, or empty
+    pub calling_functionlike_id: HakanaPluginStr,
+    pub pos: HakanaPluginPos,
+    /// The inferred type of the expression, or empty if it doesn't have one
+    pub expr_type: HakanaPluginStr,
+    /// For calls and `new` expressions, the function, method or class being called, or empty
+    pub callee: HakanaPluginStr,
+}
+
+#[repr(C)]
+pub struct HakanaPluginArgData {
+    pub file_path: HakanaPluginStr,
+    pub calling_functionlike_id: HakanaPluginStr,
+    /// The function or method being called
+    pub functionlike_id: HakanaPluginStr,
+    pub argument_offset: usize,
+    pub arg_pos: HakanaPluginPos,
+    pub function_call_pos: HakanaPluginPos,
+    pub arg_value_type: HakanaPluginStr,
+    pub param_type: HakanaPluginStr,
+}
+
+#[repr(C)]
+pub struct HakanaPluginIssueSink {
+    pub context: *mut c_void,
+    /// Adds an issue of a built-in kind, or of one of the plugin's custom issue names,
+    /// in the file being analyzed
+    pub add_issue: extern "C" fn(
+        context: *mut c_void,
+        issue_name: HakanaPluginStr,
+        description: HakanaPluginStr,
+        pos: HakanaPluginPos,
+    ),
+}
+
+type AfterExprAnalysisFn =
+    extern "C" fn(data: *const HakanaPluginExprData, sink: *const HakanaPluginIssueSink);
+
+type AfterArgAnalysisFn =
+    extern "C" fn(data: *const HakanaPluginArgData, sink: *const HakanaPluginIssueSink);
+
+#[repr(C)]
+pub struct HakanaPlugin {
+    pub abi_version: u32,
+    pub custom_issue_names: *const HakanaPluginStr,
+    pub custom_issue_names_len: usize,
+    pub after_expr_analysis: Option<AfterExprAnalysisFn>,
+    pub after_argument_analysis: Option<AfterArgAnalysisFn>,
+}
+
+type PluginInitFn = unsafe extern "C" fn(abi_version: u32) -> *const HakanaPlugin;
+
+#[derive(Debug)]
+pub struct PluginHook {
+    custom_issue_names: Vec<String>,
+    after_expr_analysis: Option<AfterExprAnalysisFn>,
+    after_argument_analysis: Option<AfterArgAnalysisFn>,
+    // the library has to stay loaded for as long as its callbacks can be called
+    _library: libloading::Library,
+}
+
+impl PluginHook {
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        let library = unsafe { libloading::Library::new(path)? };
+
+        let plugin = unsafe {
+            let init = library.get::<PluginInitFn>(b"hakana_plugin_init")?;
+            init(HAKANA_PLUGIN_ABI_VERSION)
+        };
+
+        if plugin.is_null() || unsafe { (*plugin).abi_version } != HAKANA_PLUGIN_ABI_VERSION {
+            return Err(format!(
+                "Plugin {} does not support Hakana plugin ABI version {}",
+                path, HAKANA_PLUGIN_ABI_VERSION
+            )
+            .into());
+        }
+
+        let plugin = unsafe { &*plugin };
+
+        let custom_issue_names = if plugin.custom_issue_names.is_null() {
+            vec![]
+        } else {
+            unsafe {
+                std::slice::from_raw_parts(plugin.custom_issue_names, plugin.custom_issue_names_len)
+            }
+            .iter()
+            .map(|issue_name| unsafe { issue_name.to_owned_string() })
+            .collect()
+        };
+
+        Ok(Self {
+            custom_issue_names,
+            after_expr_analysis: plugin.after_expr_analysis,
+            after_argument_analysis: plugin.after_argument_analysis,
+            _library: library,
+        })
+    }
+}
+
+impl InternalHook for PluginHook {
+    fn after_expr_analysis(
+        &self,
+        analysis_data: &mut FunctionAnalysisData,
+        after_expr_analysis_data: AfterExprAnalysisData,
+    ) {
+        let after_expr_analysis = if let Some(after_expr_analysis) = self.after_expr_analysis {
+            after_expr_analysis
+        } else {
+            return;
+        };
+
+        let statements_analyzer = after_expr_analysis_data.statements_analyzer;
+        let interner = statements_analyzer.get_interner();
+        let expr = after_expr_analysis_data.expr;
+        let calling_functionlike_id = &after_expr_analysis_data
+            .context
+            .function_context
+            .calling_functionlike_id;
+
+        let calling_functionlike_name = calling_functionlike_id
+            .map(|id| id.to_string(interner))
+            .unwrap_or_default();
+        let expr_type = analysis_data
+            .get_expr_type(&expr.1)
+            .map(|t| t.get_id(Some(interner)))
+            .unwrap_or_default();
+        let callee = get_callee(expr, analysis_data, interner).unwrap_or_default();
+
+        let data = HakanaPluginExprData {
+            file_path: HakanaPluginStr::new(statements_analyzer.get_file_path_actual()),
+            calling_functionlike_id: HakanaPluginStr::new(&calling_functionlike_name),
+            pos: statements_analyzer.get_hpos(&expr.1).into(),
+            expr_type: HakanaPluginStr::new(&expr_type),
+            callee: HakanaPluginStr::new(&callee),
+        };
+
+        with_issue_sink(
+            statements_analyzer,
+            analysis_data,
+            calling_functionlike_id,
+            |sink| after_expr_analysis(&data, sink),
+        );
+    }
+
+    fn after_argument_analysis(
+        &self,
+        analysis_data: &mut FunctionAnalysisData,
+        after_arg_analysis_data: AfterArgAnalysisData,
+    ) {
+        let after_argument_analysis =
+            if let Some(after_argument_analysis) = self.after_argument_analysis {
+                after_argument_analysis
+            } else {
+                return;
+            };
+
+        let statements_analyzer = after_arg_analysis_data.statements_analyzer;
+        let interner = statements_analyzer.get_interner();
+        let calling_functionlike_id = &after_arg_analysis_data
+            .context
+            .function_context
+            .calling_functionlike_id;
+
+        let calling_functionlike_name = calling_functionlike_id
+            .map(|id| id.to_string(interner))
+            .unwrap_or_default();
+        let functionlike_name = after_arg_analysis_data.functionlike_id.to_string(interner);
+        let arg_value_type = after_arg_analysis_data
+            .arg_value_type
+            .get_id(Some(interner));
+        let param_type = after_arg_analysis_data.param_type.get_id(Some(interner));
+
+        let data = HakanaPluginArgData {
+            file_path: HakanaPluginStr::new(statements_analyzer.get_file_path_actual()),
+            calling_functionlike_id: HakanaPluginStr::new(&calling_functionlike_name),
+            functionlike_id: HakanaPluginStr::new(&functionlike_name),
+            argument_offset: after_arg_analysis_data.argument_offset,
+            arg_pos: statements_analyzer
+                .get_hpos(after_arg_analysis_data.arg.1.pos())
+                .into(),
+            function_call_pos: statements_analyzer
+                .get_hpos(after_arg_analysis_data.function_call_pos)
+                .into(),
+            arg_value_type: HakanaPluginStr::new(&arg_value_type),
+            param_type: HakanaPluginStr::new(&param_type),
+        };
+
+        with_issue_sink(
+            statements_analyzer,
+            analysis_data,
+            calling_functionlike_id,
+            |sink| after_argument_analysis(&data, sink),
+        );
+    }
+
+    fn get_custom_issue_names(&self) -> Vec<&str> {
+        self.custom_issue_names
+            .iter()
+            .map(|issue_name| issue_name.as_str())
+            .collect()
+    }
+}
+
+impl CustomHook for PluginHook {}
+
+struct IssueSinkContext<'a, 'b> {
+    statements_analyzer: &'a StatementsAnalyzer<'a>,
+    analysis_data: &'b mut FunctionAnalysisData,
+    calling_functionlike_id: &'a Option<FunctionLikeIdentifier>,
+}
+
+fn with_issue_sink<'a>(
+    statements_analyzer: &'a StatementsAnalyzer<'a>,
+    analysis_data: &mut FunctionAnalysisData,
+    calling_functionlike_id: &'a Option<FunctionLikeIdentifier>,
+    callback: impl FnOnce(&HakanaPluginIssueSink),
+) {
+    let mut context = IssueSinkContext {
+        statements_analyzer,
+        analysis_data,
+        calling_functionlike_id,
+    };
+
+    let sink = HakanaPluginIssueSink {
+        context: &mut context as *mut IssueSinkContext as *mut c_void,
+        add_issue,
+    };
+
+    callback(&sink);
+}
+
+extern "C" fn add_issue(
+    context: *mut c_void,
+    issue_name: HakanaPluginStr,
+    description: HakanaPluginStr,
+    pos: HakanaPluginPos,
+) {
+    let context = unsafe { &mut *(context as *mut IssueSinkContext) };
+    let statements_analyzer = context.statements_analyzer;
+    let config = statements_analyzer.get_config();
+
+    let issue_name = unsafe { issue_name.to_owned_string() };
+
+    // unknown issue names are dropped, since we can't unwind back into the plugin
+    let issue_kind = if let Ok(issue_kind) =
+        IssueKind::from_str_custom(&issue_name, &config.all_custom_issues)
+    {
+        issue_kind
+    } else {
+        return;
+    };
+
+    context.analysis_data.maybe_add_issue(
+        Issue::new(
+            issue_kind,
+            unsafe { description.to_owned_string() },
+            HPos {
+                file_path: *statements_analyzer.get_file_path(),
+                start_offset: pos.start_offset,
+                end_offset: pos.end_offset,
+                start_line: pos.start_line,
+                end_line: pos.end_line,
+                start_column: pos.start_column,
+                end_column: pos.end_column,
+                insertion_start: None,
+            },
+            context.calling_functionlike_id,
+        ),
+        config,
+        statements_analyzer.get_file_path_actual(),
+    );
+}
+
+/// Gets the symbol a call or `new` expression refers to, as recorded when it was analyzed
+fn get_callee(
+    expr: &aast::Expr<(), ()>,
+    analysis_data: &FunctionAnalysisData,
+    interner: &Interner,
+) -> Option<String> {
+    let name_pos = match &expr.2 {
+        aast::Expr_::Call(boxed) => match &boxed.func.2 {
+            aast::Expr_::Id(id) => &id.0,
+            aast::Expr_::ObjGet(boxed) => boxed.1.pos(),
+            aast::Expr_::ClassConst(boxed) => &boxed.1 .0,
+            _ => return None,
+        },
+        aast::Expr_::New(boxed) => match &boxed.0 .2 {
+            aast::ClassId_::CIexpr(lhs_expr) => lhs_expr.pos(),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    let (symbol_name, member_name) = analysis_data
+        .symbol_positions
+        .get(&(name_pos.start_offset() as u32, name_pos.end_offset() as u32))?;
+
+    Some(if *member_name == StrId::EMPTY {
+        interner.lookup(symbol_name).to_string()
+    } else {
+        format!(
+            "{}::{}",
+            interner.lookup(symbol_name),
+            interner.lookup(member_name)
+        )
+    })
+}
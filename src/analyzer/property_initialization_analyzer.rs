@@ -0,0 +1,240 @@
+use hakana_reflection_info::classlike_info::ClassLikeInfo;
+use hakana_reflection_info::codebase_info::symbols::SymbolKind;
+use hakana_reflection_info::codebase_info::CodebaseInfo;
+use hakana_reflection_info::function_context::FunctionContext;
+use hakana_reflection_info::issue::{Issue, IssueKind};
+use hakana_reflection_info::property_info::{PropertyInfo, PropertyKind};
+use hakana_reflection_info::StrId;
+use oxidized::aast;
+use rustc_hash::FxHashSet;
+
+use crate::function_analysis_data::FunctionAnalysisData;
+use crate::scope_analyzer::ScopeAnalyzer;
+use crate::statements_analyzer::StatementsAnalyzer;
+
+/// Checks that every property that needs a value is assigned by the constructor, and
+/// that the constructor doesn't read any of those properties before assigning them
+pub(crate) fn check_class(
+    statements_analyzer: &StatementsAnalyzer,
+    stmt: &aast::Class_<(), ()>,
+    classlike_storage: &ClassLikeInfo,
+    analysis_data: &mut FunctionAnalysisData,
+    function_context: &FunctionContext,
+) {
+    if !matches!(classlike_storage.kind, SymbolKind::Class) || stmt.is_xhp {
+        return;
+    }
+
+    let codebase = statements_analyzer.get_codebase();
+    let interner = statements_analyzer.get_interner();
+
+    let constructor = stmt
+        .methods
+        .iter()
+        .find(|m| m.name.1.eq_ignore_ascii_case("__construct"));
+
+    if !classlike_storage.is_abstract {
+        if let Some(initialized_properties) = get_constructor_initialized_properties(
+            codebase,
+            &classlike_storage.name,
+            classlike_storage.name,
+            analysis_data,
+        ) {
+            let mut uninitialized_properties = classlike_storage
+                .declaring_property_ids
+                .iter()
+                .filter(|(property_name, declaring_class)| {
+                    if initialized_properties.contains(*property_name) {
+                        return false;
+                    }
+
+                    let declaring_storage = if let Some(declaring_storage) =
+                        codebase.classlike_infos.get(*declaring_class)
+                    {
+                        declaring_storage
+                    } else {
+                        return false;
+                    };
+
+                    // a concrete parent class gets its own issue
+                    if *declaring_class != &classlike_storage.name
+                        && matches!(declaring_storage.kind, SymbolKind::Class)
+                        && !declaring_storage.is_abstract
+                    {
+                        return false;
+                    }
+
+                    declaring_storage
+                        .properties
+                        .get(*property_name)
+                        .map_or(false, needs_initialization)
+                })
+                .map(|(property_name, declaring_class)| {
+                    (
+                        interner.lookup(declaring_class),
+                        interner.lookup(property_name),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            uninitialized_properties.sort();
+
+            let issue_pos = if let Some(constructor) = constructor {
+                statements_analyzer.get_hpos(constructor.name.pos())
+            } else {
+                classlike_storage.name_location
+            };
+
+            for (declaring_class_name, property_name) in uninitialized_properties {
+                analysis_data.maybe_add_issue(
+                    Issue::new(
+                        IssueKind::PropertyNotSetInConstructor,
+                        format!(
+                            "Property {}::${} is not initialized in the constructor of {}",
+                            declaring_class_name,
+                            property_name,
+                            interner.lookup(&classlike_storage.name),
+                        ),
+                        issue_pos,
+                        &function_context.calling_functionlike_id,
+                    ),
+                    statements_analyzer.get_config(),
+                    statements_analyzer.get_file_path_actual(),
+                );
+            }
+        }
+    }
+
+    if constructor.is_none() {
+        return;
+    }
+
+    let parent_initialized_properties = classlike_storage
+        .direct_parent_class
+        .and_then(|parent_class| {
+            get_constructor_initialized_properties(
+                codebase,
+                &parent_class,
+                classlike_storage.name,
+                analysis_data,
+            )
+        })
+        .unwrap_or_default();
+
+    for property_read in &classlike_storage.uninitialized_property_reads {
+        let property_name = property_read.property_name;
+
+        if property_read.after_parent_constructor
+            && parent_initialized_properties.contains(&property_name)
+        {
+            continue;
+        }
+
+        let declaring_class = if let Some(declaring_class) =
+            classlike_storage.declaring_property_ids.get(&property_name)
+        {
+            declaring_class
+        } else {
+            continue;
+        };
+
+        if !codebase
+            .classlike_infos
+            .get(declaring_class)
+            .and_then(|declaring_storage| declaring_storage.properties.get(&property_name))
+            .map_or(false, needs_initialization)
+        {
+            continue;
+        }
+
+        analysis_data.maybe_add_issue(
+            Issue::new(
+                IssueKind::UninitializedPropertyRead,
+                format!(
+                    "Property {}::${} is read before it is initialized",
+                    interner.lookup(declaring_class),
+                    interner.lookup(&property_name),
+                ),
+                property_read.pos,
+                &function_context.calling_functionlike_id,
+            ),
+            statements_analyzer.get_config(),
+            statements_analyzer.get_file_path_actual(),
+        );
+    }
+}
+
+/// Gets the properties guaranteed to be assigned by the constructor that runs when an
+/// instance of the given class is created, or None if that can't be determined.
+///
+/// The result depends on the constructors of the class's ancestors, so the referencing
+/// class gets a reference to each of them.
+fn get_constructor_initialized_properties(
+    codebase: &CodebaseInfo,
+    classlike_name: &StrId,
+    referencing_class: StrId,
+    analysis_data: &mut FunctionAnalysisData,
+) -> Option<FxHashSet<StrId>> {
+    if *classlike_name != referencing_class {
+        analysis_data
+            .symbol_references
+            .add_symbol_reference_to_class_member(
+                referencing_class,
+                (*classlike_name, StrId::CONSTRUCT),
+                false,
+            );
+    }
+
+    let classlike_storage = codebase.classlike_infos.get(classlike_name)?;
+
+    if classlike_storage.methods.contains(&StrId::CONSTRUCT) {
+        let mut initialized_properties = classlike_storage.initialized_properties.clone();
+
+        if classlike_storage.constructor_calls_parent {
+            if let Some(parent_class) = &classlike_storage.direct_parent_class {
+                initialized_properties.extend(get_constructor_initialized_properties(
+                    codebase,
+                    parent_class,
+                    referencing_class,
+                    analysis_data,
+                )?);
+            }
+        }
+
+        return Some(initialized_properties);
+    }
+
+    // constructors from traits aren't walked
+    if let Some(declaring_class) = classlike_storage
+        .declaring_method_ids
+        .get(&StrId::CONSTRUCT)
+    {
+        if let Some(declaring_storage) = codebase.classlike_infos.get(declaring_class) {
+            if matches!(declaring_storage.kind, SymbolKind::Trait) {
+                return None;
+            }
+        }
+    }
+
+    if let Some(parent_class) = &classlike_storage.direct_parent_class {
+        get_constructor_initialized_properties(
+            codebase,
+            parent_class,
+            referencing_class,
+            analysis_data,
+        )
+    } else {
+        Some(FxHashSet::default())
+    }
+}
+
+fn needs_initialization(property_storage: &PropertyInfo) -> bool {
+    !property_storage.is_static
+        && !property_storage.has_default
+        && !property_storage.is_promoted
+        && !property_storage.is_late_init
+        && matches!(property_storage.kind, PropertyKind::Property)
+        && !property_storage.type_.is_nullable()
+        && !property_storage.type_.is_mixed()
+        && !property_storage.type_.is_null()
+}
@@ -0,0 +1,240 @@
+use hakana_reflection_info::function_context::FunctionContext;
+use hakana_reflection_info::issue::{Issue, IssueKind};
+use hakana_reflection_info::{
+    EFFECT_READ_GLOBALS, EFFECT_READ_PROPS, EFFECT_WRITE_GLOBALS, EFFECT_WRITE_PROPS,
+};
+use oxidized::aast;
+use oxidized::aast_visitor::{AstParams, Node, Visitor};
+use oxidized::ast_defs::{self, Pos};
+use rustc_hash::FxHashMap;
+
+use crate::function_analysis_data::FunctionAnalysisData;
+use crate::scope_analyzer::ScopeAnalyzer;
+use crate::statements_analyzer::StatementsAnalyzer;
+
+/// Reports the side effects in the body of a function that must be pure, either because
+/// it has a `<<Hakana\Pure>>` attribute or because it's declared with `[]` contexts.
+///
+/// This runs after the body has been analyzed, using the effects recorded for each expression.
+pub(crate) fn check_function_body(
+    statements_analyzer: &StatementsAnalyzer,
+    fb_ast: &Vec<aast::Stmt<(), ()>>,
+    is_constructor: bool,
+    analysis_data: &mut FunctionAnalysisData,
+    function_context: &FunctionContext,
+) {
+    let function_name = if let Some(functionlike_id) = &function_context.calling_functionlike_id {
+        functionlike_id.to_string(statements_analyzer.get_interner())
+    } else {
+        return;
+    };
+
+    let mut checker = PurityChecker {
+        expr_effects: &analysis_data.expr_effects,
+        callee_effects: &analysis_data.callee_effects,
+        is_constructor,
+        impurities: vec![],
+    };
+
+    for stmt in fb_ast {
+        stmt.visit(&mut (), &mut checker).ok();
+    }
+
+    let impurities = checker.impurities;
+
+    for (issue_kind, effects, pos) in impurities {
+        let description = match issue_kind {
+            IssueKind::ImpureFunctionCall => "this call",
+            IssueKind::ImpurePropertyAssignment => "this assignment",
+            _ => "this expression",
+        };
+
+        analysis_data.maybe_add_issue(
+            Issue::new(
+                issue_kind,
+                format!(
+                    "Function {} must be pure, but {} {}",
+                    function_name,
+                    description,
+                    describe_effects(effects)
+                ),
+                statements_analyzer.get_hpos(pos),
+                &function_context.calling_functionlike_id,
+            ),
+            statements_analyzer.get_config(),
+            statements_analyzer.get_file_path_actual(),
+        );
+    }
+}
+
+fn describe_effects(effects: u8) -> String {
+    let mut descriptions = vec![];
+
+    if effects & EFFECT_READ_PROPS != 0 {
+        descriptions.push("reads properties");
+    }
+
+    if effects & EFFECT_READ_GLOBALS != 0 {
+        descriptions.push("reads globals");
+    }
+
+    if effects & EFFECT_WRITE_PROPS != 0 {
+        descriptions.push("writes properties");
+    }
+
+    if effects & EFFECT_WRITE_GLOBALS != 0 {
+        descriptions.push("writes globals");
+    }
+
+    descriptions.join(", ")
+}
+
+struct PurityChecker<'a, 'ast> {
+    expr_effects: &'a FxHashMap<(u32, u32), u8>,
+    callee_effects: &'a FxHashMap<(u32, u32), u8>,
+    is_constructor: bool,
+    impurities: Vec<(IssueKind, u8, &'ast Pos)>,
+}
+
+impl<'a, 'ast> PurityChecker<'a, 'ast> {
+    fn get_effects(&self, pos: &Pos) -> u8 {
+        *self
+            .expr_effects
+            .get(&(pos.start_offset() as u32, pos.end_offset() as u32))
+            .unwrap_or(&0)
+    }
+
+    // the effects of an expression that don't come from any of its subexpressions. A call's
+    // effects are combined with its arguments', so whatever the callee itself does is added
+    // back in case an argument has the same effects
+    fn get_own_effects(&self, expr: &'ast aast::Expr<(), ()>) -> u8 {
+        let mut child_collector = ChildExprCollector { children: vec![] };
+        expr.recurse(&mut (), &mut child_collector).ok();
+
+        let child_effects = child_collector
+            .children
+            .into_iter()
+            .fold(0, |effects, child| effects | self.get_effects(&child.1));
+
+        let callee_effects = *self
+            .callee_effects
+            .get(&(expr.1.start_offset() as u32, expr.1.end_offset() as u32))
+            .unwrap_or(&0);
+
+        (self.get_effects(&expr.1) & !child_effects) | callee_effects
+    }
+
+    fn is_property_target(&self, expr: &aast::Expr<(), ()>) -> bool {
+        match &expr.2 {
+            aast::Expr_::ObjGet(boxed) => {
+                if !matches!(boxed.3, ast_defs::PropOrMethod::IsProp) {
+                    return false;
+                }
+
+                // constructors are allowed to initialize the object's own properties
+                if self.is_constructor {
+                    if let aast::Expr_::Lvar(lhs_var) = &boxed.0 .2 {
+                        return lhs_var.1 .1 != "$this";
+                    }
+                }
+
+                true
+            }
+            aast::Expr_::ClassGet(_) => true,
+            aast::Expr_::ArrayGet(boxed) => self.is_property_target(&boxed.0),
+            _ => false,
+        }
+    }
+}
+
+impl<'a, 'ast> Visitor<'ast> for PurityChecker<'a, 'ast> {
+    type Params = AstParams<(), ()>;
+
+    fn object(&mut self) -> &mut dyn Visitor<'ast, Params = Self::Params> {
+        self
+    }
+
+    fn visit_expr(&mut self, c: &mut (), expr: &'ast aast::Expr<(), ()>) -> Result<(), ()> {
+        match &expr.2 {
+            aast::Expr_::Lfun(_) | aast::Expr_::Efun(_) => {
+                // closures are checked where they're called
+                return Ok(());
+            }
+            aast::Expr_::Binop(boxed) => {
+                if let ast_defs::Bop::Eq(_) = boxed.bop {
+                    if self.is_property_target(&boxed.lhs) {
+                        self.impurities.push((
+                            IssueKind::ImpurePropertyAssignment,
+                            EFFECT_WRITE_PROPS,
+                            &expr.1,
+                        ));
+
+                        return boxed.rhs.visit(c, self);
+                    }
+                }
+            }
+            aast::Expr_::Unop(boxed) => {
+                if let ast_defs::Uop::Uincr
+                | ast_defs::Uop::Udecr
+                | ast_defs::Uop::Upincr
+                | ast_defs::Uop::Updecr = boxed.0
+                {
+                    if self.is_property_target(&boxed.1) {
+                        self.impurities.push((
+                            IssueKind::ImpurePropertyAssignment,
+                            EFFECT_WRITE_PROPS,
+                            &expr.1,
+                        ));
+
+                        return Ok(());
+                    }
+                }
+            }
+            aast::Expr_::Call(_) | aast::Expr_::New(_) => {
+                let own_effects = self.get_own_effects(expr)
+                    & (EFFECT_READ_GLOBALS | EFFECT_WRITE_PROPS | EFFECT_WRITE_GLOBALS);
+
+                if own_effects != 0 {
+                    self.impurities
+                        .push((IssueKind::ImpureFunctionCall, own_effects, &expr.1));
+                }
+            }
+            aast::Expr_::Lvar(lid) => {
+                // $$ carries the effects of the left-hand side of the pipe, reported there
+                if lid.1 .1 != "$$" && self.get_effects(&expr.1) & EFFECT_READ_GLOBALS != 0 {
+                    self.impurities.push((
+                        IssueKind::ImpureGlobalRead,
+                        EFFECT_READ_GLOBALS,
+                        &expr.1,
+                    ));
+                }
+            }
+            aast::Expr_::ClassGet(_) => {
+                // static properties are global state
+                self.impurities
+                    .push((IssueKind::ImpureGlobalRead, EFFECT_READ_GLOBALS, &expr.1));
+            }
+            _ => {}
+        }
+
+        expr.recurse(c, self)
+    }
+}
+
+struct ChildExprCollector<'ast> {
+    children: Vec<&'ast aast::Expr<(), ()>>,
+}
+
+impl<'ast> Visitor<'ast> for ChildExprCollector<'ast> {
+    type Params = AstParams<(), ()>;
+
+    fn object(&mut self) -> &mut dyn Visitor<'ast, Params = Self::Params> {
+        self
+    }
+
+    fn visit_expr(&mut self, _: &mut (), expr: &'ast aast::Expr<(), ()>) -> Result<(), ()> {
+        self.children.push(expr);
+
+        Ok(())
+    }
+}
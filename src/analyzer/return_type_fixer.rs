@@ -0,0 +1,550 @@
+use hakana_reflection_info::analysis_result::Replacement;
+use hakana_reflection_info::functionlike_info::FunctionLikeInfo;
+use hakana_reflection_info::member_visibility::MemberVisibility;
+use hakana_reflection_info::t_atomic::TAtomic;
+use hakana_reflection_info::t_union::TUnion;
+use hakana_reflection_info::{Interner, StrId};
+use hakana_type::add_optional_union_type;
+use oxidized::aast;
+
+use crate::function_analysis_data::FunctionAnalysisData;
+use crate::scope_analyzer::ScopeAnalyzer;
+use crate::statements_analyzer::StatementsAnalyzer;
+
+/// Adds the inferred return type to a function or method that's missing one, either
+/// because it has no return hint or because it's hinted with `HH\FIXME\MISSING_RETURN_TYPE`.
+///
+/// Methods that override or are overridden by other methods are only fixed when they can't be
+/// overridden further, since each method's inferred type could be incompatible with the others.
+///
+/// When `tighten_return_types` is enabled this also replaces `mixed` and `dynamic` return
+/// hints with the inferred type, as long as no overriding method could depend on the looser type.
+pub(crate) fn fix_return_type(
+    statements_analyzer: &StatementsAnalyzer,
+    functionlike_storage: &FunctionLikeInfo,
+    params: &[aast::FunParam<(), ()>],
+    inferred_return_type: &Option<TUnion>,
+    end_is_reachable: bool,
+    analysis_data: &mut FunctionAnalysisData,
+) {
+    // generators get their return types from yields, which aren't tracked here
+    if functionlike_storage.has_yield {
+        return;
+    }
+
+    // falling off the end of the body or a bare `return;` returns nothing, and only a void
+    // hint allows that, so there's no hint that also allows the values returned elsewhere
+    if (end_is_reachable || analysis_data.has_valueless_return)
+        && !analysis_data.inferred_return_types.is_empty()
+    {
+        return;
+    }
+
+    if let Some(method_info) = &functionlike_storage.method_info {
+        if functionlike_storage.name == StrId::CONSTRUCT || method_info.is_abstract {
+            return;
+        }
+    }
+
+    let interner = statements_analyzer.get_interner();
+
+    if let Some(declared_return_type) = &functionlike_storage.return_type {
+        if !statements_analyzer.get_config().tighten_return_types
+            || !is_loose_return_type(declared_return_type, functionlike_storage.is_async)
+            || analysis_data.inferred_return_types.is_empty()
+            || !can_tighten_return_type(statements_analyzer, functionlike_storage)
+        {
+            return;
+        }
+
+        let return_type_location = if let Some(location) = functionlike_storage.return_type_location
+        {
+            location
+        } else {
+            return;
+        };
+
+        let mut tightened_return_type = None;
+
+        for callsite_return_type in &analysis_data.inferred_return_types {
+            tightened_return_type = Some(add_optional_union_type(
+                callsite_return_type.clone(),
+                tightened_return_type.as_ref(),
+                statements_analyzer.get_codebase(),
+            ));
+        }
+
+        let tightened_return_type = tightened_return_type.unwrap();
+
+        if is_loose_return_type(&tightened_return_type, functionlike_storage.is_async) {
+            return;
+        }
+
+        if let Some(hint) = get_return_hint(&tightened_return_type, interner) {
+            analysis_data.add_replacement(
+                (
+                    return_type_location.start_offset,
+                    return_type_location.end_offset,
+                ),
+                Replacement::Substitute(hint),
+            );
+        }
+
+        return;
+    }
+
+    if !can_tighten_return_type(statements_analyzer, functionlike_storage)
+        && is_in_method_hierarchy(statements_analyzer, functionlike_storage)
+    {
+        return;
+    }
+
+    let hint = if let Some(hint) = inferred_return_type
+        .as_ref()
+        .and_then(|inferred_return_type| get_return_hint(inferred_return_type, interner))
+    {
+        hint
+    } else {
+        return;
+    };
+
+    if let Some(return_type_location) = functionlike_storage.return_type_location {
+        // the hint is HH\FIXME\MISSING_RETURN_TYPE
+        analysis_data.add_replacement(
+            (
+                return_type_location.start_offset,
+                return_type_location.end_offset,
+            ),
+            Replacement::Substitute(hint),
+        );
+    } else if let Some(insertion_point) = get_return_hint_insertion_point(
+        &statements_analyzer
+            .get_file_analyzer()
+            .get_file_source()
+            .file_contents,
+        functionlike_storage,
+        params,
+    ) {
+        analysis_data.insert_at(insertion_point, format!(": {}", hint));
+    }
+}
+
+fn is_loose_return_type(return_type: &TUnion, is_async: bool) -> bool {
+    if is_async && return_type.types.len() == 1 {
+        if let TAtomic::TNamedObject {
+            name: StrId::AWAITABLE,
+            type_params: Some(type_params),
+            ..
+        } = return_type.get_single()
+        {
+            return type_params[0].is_mixed();
+        }
+    }
+
+    return_type.is_mixed()
+}
+
+/// Narrowing the return type of a method that's overridden elsewhere could make the
+/// overriding method's return type invalid
+fn can_tighten_return_type(
+    statements_analyzer: &StatementsAnalyzer,
+    functionlike_storage: &FunctionLikeInfo,
+) -> bool {
+    let method_info = if let Some(method_info) = &functionlike_storage.method_info {
+        method_info
+    } else {
+        return true;
+    };
+
+    if method_info.is_final || matches!(method_info.visibility, MemberVisibility::Private) {
+        return true;
+    }
+
+    method_info
+        .defining_fqcln
+        .and_then(|classlike_name| {
+            statements_analyzer
+                .get_codebase()
+                .classlike_infos
+                .get(&classlike_name)
+        })
+        .map_or(false, |classlike_storage| classlike_storage.is_final)
+}
+
+/// Whether the method overrides a parent method or is overridden by a child method, in which
+/// case inferring each return type separately could produce incompatible hints
+fn is_in_method_hierarchy(
+    statements_analyzer: &StatementsAnalyzer,
+    functionlike_storage: &FunctionLikeInfo,
+) -> bool {
+    let codebase = statements_analyzer.get_codebase();
+
+    let classlike_name = if let Some(classlike_name) = functionlike_storage
+        .method_info
+        .as_ref()
+        .and_then(|method_info| method_info.defining_fqcln)
+    {
+        classlike_name
+    } else {
+        return false;
+    };
+
+    let classlike_storage =
+        if let Some(classlike_storage) = codebase.classlike_infos.get(&classlike_name) {
+            classlike_storage
+        } else {
+            return true;
+        };
+
+    if classlike_storage
+        .overridden_method_ids
+        .contains_key(&functionlike_storage.name)
+    {
+        return true;
+    }
+
+    codebase
+        .get_all_descendants(&classlike_name)
+        .into_iter()
+        .any(|descendant| {
+            codebase
+                .classlike_infos
+                .get(&descendant)
+                .map_or(true, |descendant_storage| {
+                    descendant_storage
+                        .methods
+                        .contains(&functionlike_storage.name)
+                })
+        })
+}
+
+/// Finds the offset just after a function's parameter list (and any contexts), where a
+/// return hint would go
+fn get_return_hint_insertion_point(
+    file_contents: &str,
+    functionlike_storage: &FunctionLikeInfo,
+    params: &[aast::FunParam<(), ()>],
+) -> Option<u32> {
+    let bytes = file_contents.as_bytes();
+
+    let mut offset = if let Some(last_param) = params.last() {
+        if let Some(default_expr) = &last_param.expr {
+            default_expr.1.end_offset()
+        } else {
+            last_param.pos.end_offset()
+        }
+    } else {
+        let mut offset = functionlike_storage.name_location?.end_offset as usize;
+
+        // skip past any type parameters
+        if let Some(b'<') = bytes.get(skip_whitespace(bytes, offset)) {
+            offset = skip_balanced(bytes, skip_whitespace(bytes, offset), b'<', b'>')?;
+        }
+
+        offset = skip_whitespace(bytes, offset);
+
+        if bytes.get(offset) != Some(&b'(') {
+            return None;
+        }
+
+        offset + 1
+    };
+
+    // find the parenthesis that closes the parameter list
+    let mut depth = 0;
+
+    loop {
+        match bytes.get(offset)? {
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' if depth == 0 => break,
+            b')' | b']' | b'}' => depth -= 1,
+            b'\'' | b'"' => {
+                offset = skip_string(bytes, offset)?;
+                continue;
+            }
+            _ => {}
+        }
+
+        offset += 1;
+    }
+
+    offset += 1;
+
+    // contexts like [] or [defaults] come before the return hint
+    let after_whitespace = skip_whitespace(bytes, offset);
+
+    if bytes.get(after_whitespace) == Some(&b'[') {
+        offset = skip_balanced(bytes, after_whitespace, b'[', b']')?;
+    }
+
+    Some(offset as u32)
+}
+
+fn skip_whitespace(bytes: &[u8], mut offset: usize) -> usize {
+    while let Some(b' ' | b'\t' | b'\n' | b'\r') = bytes.get(offset) {
+        offset += 1;
+    }
+
+    offset
+}
+
+// returns the offset just after the bracket that closes the one at the given offset
+fn skip_balanced(bytes: &[u8], mut offset: usize, open: u8, close: u8) -> Option<usize> {
+    let mut depth = 0;
+
+    loop {
+        let byte = *bytes.get(offset)?;
+
+        offset += 1;
+
+        if byte == open {
+            depth += 1;
+        } else if byte == close {
+            depth -= 1;
+
+            if depth == 0 {
+                return Some(offset);
+            }
+        }
+    }
+}
+
+// returns the offset just after the quote that closes the string at the given offset
+fn skip_string(bytes: &[u8], mut offset: usize) -> Option<usize> {
+    let quote = bytes[offset];
+
+    offset += 1;
+
+    loop {
+        match *bytes.get(offset)? {
+            b'\\' => offset += 2,
+            byte if byte == quote => return Some(offset + 1),
+            _ => offset += 1,
+        }
+    }
+}
+
+/// Renders an inferred return type as a Hack hint, or None if there's no hint that
+/// describes it without losing information
+fn get_return_hint(return_type: &TUnion, interner: &Interner) -> Option<String> {
+    if return_type.is_nothing() {
+        return Some("noreturn".to_string());
+    }
+
+    get_union_hint(return_type, interner)
+}
+
+fn get_union_hint(union: &TUnion, interner: &Interner) -> Option<String> {
+    let mut is_nullable = false;
+    let mut hints = vec![];
+
+    for atomic in &union.types {
+        match atomic {
+            TAtomic::TNull => {
+                is_nullable = true;
+            }
+            TAtomic::TNothing if union.types.len() > 1 => {}
+            _ => {
+                let hint = get_atomic_hint(atomic, interner)?;
+
+                if !hints.contains(&hint) {
+                    hints.push(hint);
+                }
+            }
+        }
+    }
+
+    let hint = match hints.len() {
+        0 => {
+            return if is_nullable {
+                Some("null".to_string())
+            } else {
+                None
+            };
+        }
+        1 => hints.remove(0),
+        _ => {
+            if hints
+                .iter()
+                .all(|hint| matches!(hint.as_str(), "int" | "string" | "arraykey"))
+            {
+                "arraykey".to_string()
+            } else if hints
+                .iter()
+                .all(|hint| matches!(hint.as_str(), "int" | "float" | "num"))
+            {
+                "num".to_string()
+            } else {
+                return None;
+            }
+        }
+    };
+
+    if !is_nullable {
+        return Some(hint);
+    }
+
+    match hint.as_str() {
+        "mixed" | "nonnull" => Some("mixed".to_string()),
+        "void" | "nothing" | "null" => None,
+        _ => Some(format!("?{}", hint)),
+    }
+}
+
+fn get_atomic_hint(atomic: &TAtomic, interner: &Interner) -> Option<String> {
+    Some(match atomic {
+        TAtomic::TInt | TAtomic::TLiteralInt { .. } => "int".to_string(),
+        TAtomic::TString | TAtomic::TLiteralString { .. } | TAtomic::TStringWithFlags(..) => {
+            "string".to_string()
+        }
+        TAtomic::TFloat => "float".to_string(),
+        TAtomic::TBool | TAtomic::TTrue | TAtomic::TFalse => "bool".to_string(),
+        TAtomic::TArraykey { from_any: false } => "arraykey".to_string(),
+        TAtomic::TNum => "num".to_string(),
+        TAtomic::TVoid => "void".to_string(),
+        TAtomic::TNothing => "nothing".to_string(),
+        TAtomic::TResource => "resource".to_string(),
+        TAtomic::TMixed => "mixed".to_string(),
+        TAtomic::TMixedWithFlags(is_any, _, _, is_nonnull) => {
+            if *is_any {
+                return None;
+            }
+
+            if *is_nonnull {
+                "nonnull".to_string()
+            } else {
+                "mixed".to_string()
+            }
+        }
+        TAtomic::TEnum { name, .. } => get_classlike_hint(interner.lookup(name)),
+        TAtomic::TEnumLiteralCase { enum_name, .. } => {
+            get_classlike_hint(interner.lookup(enum_name))
+        }
+        TAtomic::TNamedObject {
+            name,
+            type_params,
+            is_this,
+            ..
+        } => {
+            if *is_this {
+                "this".to_string()
+            } else {
+                get_classlike_hint(interner.lookup(name))
+                    + &get_type_params_hint(type_params, interner)?
+            }
+        }
+        TAtomic::TTypeAlias {
+            name, type_params, ..
+        } => {
+            get_classlike_hint(interner.lookup(name))
+                + &get_type_params_hint(type_params, interner)?
+        }
+        TAtomic::TGenericParam { param_name, .. } => interner.lookup(param_name).to_string(),
+        TAtomic::TVec {
+            known_items: None,
+            type_param,
+            ..
+        } => format!("vec<{}>", get_union_hint(type_param, interner)?),
+        TAtomic::TKeyset { type_param } => {
+            format!("keyset<{}>", get_union_hint(type_param, interner)?)
+        }
+        TAtomic::TDict {
+            known_items: None,
+            params,
+            shape_name: None,
+            ..
+        } => {
+            if let Some((key_param, value_param)) = params {
+                format!(
+                    "dict<{}, {}>",
+                    get_union_hint(key_param, interner)?,
+                    get_union_hint(value_param, interner)?
+                )
+            } else {
+                "dict<nothing, nothing>".to_string()
+            }
+        }
+        TAtomic::TDict {
+            shape_name: Some((shape_name, shape_member_name)),
+            ..
+        } => {
+            let shape_hint = get_classlike_hint(interner.lookup(shape_name));
+
+            if let Some(shape_member_name) = shape_member_name {
+                format!("{}::{}", shape_hint, interner.lookup(shape_member_name))
+            } else {
+                shape_hint
+            }
+        }
+        TAtomic::TClassname { as_type } => {
+            format!("classname<{}>", get_atomic_hint(as_type, interner)?)
+        }
+        TAtomic::TLiteralClassname { name } => {
+            format!("classname<{}>", get_classlike_hint(interner.lookup(name)))
+        }
+        TAtomic::TGenericClassname { param_name, .. } => {
+            format!("classname<{}>", interner.lookup(param_name))
+        }
+        TAtomic::TTypename { as_type } => {
+            format!("typename<{}>", get_atomic_hint(as_type, interner)?)
+        }
+        TAtomic::TGenericTypename { param_name, .. } => {
+            format!("typename<{}>", interner.lookup(param_name))
+        }
+        TAtomic::TClosure {
+            params,
+            return_type,
+            ..
+        } => {
+            let mut param_hints = vec![];
+
+            for param in params {
+                let param_hint = get_union_hint(param.signature_type.as_ref()?, interner)?;
+
+                param_hints.push(if param.is_variadic {
+                    format!("{}...", param_hint)
+                } else if param.is_inout {
+                    format!("inout {}", param_hint)
+                } else if param.is_optional {
+                    format!("optional {}", param_hint)
+                } else {
+                    param_hint
+                });
+            }
+
+            format!(
+                "(function({}): {})",
+                param_hints.join(", "),
+                get_return_hint(return_type.as_ref()?, interner)?
+            )
+        }
+        _ => {
+            return None;
+        }
+    })
+}
+
+fn get_type_params_hint(type_params: &Option<Vec<TUnion>>, interner: &Interner) -> Option<String> {
+    if let Some(type_params) = type_params {
+        let mut type_param_hints = vec![];
+
+        for type_param in type_params {
+            type_param_hints.push(get_union_hint(type_param, interner)?);
+        }
+
+        Some(format!("<{}>", type_param_hints.join(", ")))
+    } else {
+        Some("".to_string())
+    }
+}
+
+// names in the HH namespace are available everywhere, everything else is fully-qualified
+fn get_classlike_hint(name: &str) -> String {
+    if let Some(unqualified_name) = name.strip_prefix("HH\\") {
+        if !unqualified_name.contains('\\') {
+            return unqualified_name.to_string();
+        }
+    }
+
+    format!("\\{}", name)
+}
@@ -6,7 +6,7 @@ use hakana_reflection_info::function_context::FunctionLikeIdentifier;
 use hakana_reflection_info::StrId;
 use hakana_reflection_info::{
     data_flow::{
-        graph::{DataFlowGraph, GraphKind},
+        graph::{DataFlowGraph, GraphKind, TaintCutoffReason},
         node::DataFlowNode,
         path::PathKind,
     },
@@ -153,6 +153,8 @@ pub(crate) fn analyze(
         analysis_data
             .inferred_return_types
             .push(inferred_return_type.clone());
+    } else {
+        analysis_data.has_valueless_return = true;
     }
 
     let expected_return_type = if let Some(expected_return_type) = &functionlike_storage.return_type
@@ -570,24 +572,41 @@ fn handle_dataflow(
         }
         data_flow_graph.add_node(return_node);
     } else {
-        if !inferred_type.has_taintable_value() {
-            return;
-        }
-
-        if !context.allow_taints {
-            return;
-        }
-
         let codebase = statements_analyzer.get_codebase();
 
-        for at in &inferred_type.types {
-            if let Some(shape_name) = at.get_shape_name() {
-                if let Some(t) = codebase.type_definitions.get(shape_name) {
-                    if t.shape_field_taints.is_some() {
-                        return;
-                    }
+        if !inferred_type.has_taintable_value()
+            || !context.allow_taints
+            || inferred_type.types.iter().any(|at| {
+                at.get_shape_name()
+                    .and_then(|shape_name| codebase.type_definitions.get(shape_name))
+                    .map_or(false, |t| t.shape_field_taints.is_some())
+            })
+        {
+            if statements_analyzer
+                .get_config()
+                .security_config
+                .record_cutoffs
+            {
+                let cutoff_reason = if !inferred_type.has_taintable_value() {
+                    TaintCutoffReason::NonTaintableType(
+                        inferred_type.get_id(Some(statements_analyzer.get_interner())),
+                    )
+                } else if !context.allow_taints {
+                    TaintCutoffReason::IgnorePathIfTrue
+                } else {
+                    TaintCutoffReason::ShapeSource
+                };
+
+                for parent_node in &inferred_type.parent_nodes {
+                    data_flow_graph.add_cutoff(
+                        &parent_node.id,
+                        cutoff_reason.clone(),
+                        statements_analyzer.get_hpos(return_expr.pos()),
+                    );
                 }
             }
+
+            return;
         }
 
         let return_expr_node = DataFlowNode::get_for_assignment(
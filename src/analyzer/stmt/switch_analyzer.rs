@@ -1,6 +1,13 @@
-use hakana_reflection_info::{codebase_info::CodebaseInfo, Interner, StrId};
+use hakana_reflection_info::{
+    codebase_info::{symbols::SymbolKind, CodebaseInfo},
+    issue::{Issue, IssueKind},
+    t_atomic::TAtomic,
+    t_union::TUnion,
+    Interner, StrId,
+};
 use hakana_type::{combine_union_types, get_mixed_any};
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
+use itertools::Itertools;
 use oxidized::{aast, aast::Pos};
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::rc::Rc;
@@ -210,6 +217,17 @@ pub(crate) fn analyze(
         )?;
     }
 
+    if let Some(subject_type) = original_context.vars_in_scope.get(&switch_var_id) {
+        check_enum_exhaustiveness(
+            statements_analyzer,
+            stmt,
+            pos,
+            subject_type,
+            analysis_data,
+            context,
+        );
+    }
+
     let mut possibly_redefined_vars = switch_scope.possibly_redefined_vars.unwrap_or_default();
     if let Some(new_vars_in_scope) = switch_scope.new_vars_in_scope {
         possibly_redefined_vars.retain(|k, _| !new_vars_in_scope.contains_key(k));
@@ -247,6 +265,145 @@ pub(crate) fn analyze(
     Ok(())
 }
 
+/// Reports switches over enum values that leave some cases unhandled without a default,
+/// and defaults that can never be reached because every case is handled
+fn check_enum_exhaustiveness(
+    statements_analyzer: &StatementsAnalyzer,
+    stmt: (
+        &aast::Expr<(), ()>,
+        &Vec<aast::Case<(), ()>>,
+        &Option<aast::DefaultCase<(), ()>>,
+    ),
+    pos: &Pos,
+    subject_type: &TUnion,
+    analysis_data: &mut FunctionAnalysisData,
+    context: &ScopeContext,
+) {
+    let codebase = statements_analyzer.get_codebase();
+    let interner = statements_analyzer.get_interner();
+
+    let mut subject_cases = IndexSet::new();
+
+    for atomic in &subject_type.types {
+        match atomic {
+            TAtomic::TEnum { name, .. } => {
+                if let Some(enum_storage) = codebase.classlike_infos.get(name) {
+                    for member_name in enum_storage.constants.keys() {
+                        subject_cases.insert((*name, *member_name));
+                    }
+                } else {
+                    return;
+                }
+            }
+            TAtomic::TEnumLiteralCase {
+                enum_name,
+                member_name,
+                ..
+            } => {
+                subject_cases.insert((*enum_name, *member_name));
+            }
+            TAtomic::TEnumClassLabel {
+                class_name: Some(class_name),
+                member_name,
+            } => {
+                subject_cases.insert((*class_name, *member_name));
+            }
+            _ => {
+                return;
+            }
+        }
+    }
+
+    let mut handled_cases = FxHashSet::default();
+
+    for case in stmt.1 {
+        let case_type = if let Some(case_type) = analysis_data.get_expr_type(case.0.pos()) {
+            case_type
+        } else {
+            return;
+        };
+
+        for atomic in &case_type.types {
+            match atomic {
+                TAtomic::TEnumLiteralCase {
+                    enum_name,
+                    member_name,
+                    ..
+                } => {
+                    handled_cases.insert((*enum_name, *member_name));
+                }
+                TAtomic::TEnumClassLabel {
+                    class_name: Some(class_name),
+                    member_name,
+                } => {
+                    handled_cases.insert((*class_name, *member_name));
+                }
+                _ => {
+                    // we can't tell which values a non-literal case handles
+                    return;
+                }
+            }
+        }
+    }
+
+    let unhandled_cases = subject_cases
+        .into_iter()
+        .filter(|enum_case| !handled_cases.contains(enum_case))
+        .collect::<Vec<_>>();
+
+    if let Some(default_case) = stmt.2 {
+        if unhandled_cases.is_empty() {
+            analysis_data.maybe_add_issue(
+                Issue::new(
+                    IssueKind::UnreachableDefault,
+                    format!(
+                        "Default case is unreachable because every value of {} is handled",
+                        subject_type.get_id(Some(interner))
+                    ),
+                    statements_analyzer.get_hpos(&default_case.0),
+                    &context.function_context.calling_functionlike_id,
+                ),
+                statements_analyzer.get_config(),
+                statements_analyzer.get_file_path_actual(),
+            );
+        }
+    } else if !unhandled_cases.is_empty() {
+        analysis_data.maybe_add_issue(
+            Issue::new(
+                IssueKind::NonExhaustiveSwitch,
+                format!(
+                    "Switch over {} does not handle {}",
+                    subject_type.get_id(Some(interner)),
+                    unhandled_cases
+                        .into_iter()
+                        .map(|(class_name, member_name)| {
+                            let separator = if matches!(
+                                codebase.symbols.all.get(&class_name),
+                                Some(SymbolKind::EnumClass)
+                            ) {
+                                "#"
+                            } else {
+                                "::"
+                            };
+
+                            format!(
+                                "{}{}{}",
+                                interner.lookup(&class_name),
+                                separator,
+                                interner.lookup(&member_name)
+                            )
+                        })
+                        .join(", ")
+                ),
+                statements_analyzer.get_hpos(pos),
+                &context.function_context.calling_functionlike_id,
+            ),
+            statements_analyzer.get_config(),
+            statements_analyzer.get_file_path_actual(),
+        );
+    }
+}
+
 fn update_case_exit_map(
     codebase: &CodebaseInfo,
     interner: &Interner,
@@ -0,0 +1,398 @@
+use hakana_reflection_info::classlike_info::{ClassLikeInfo, Variance};
+use hakana_reflection_info::codebase_info::CodebaseInfo;
+use hakana_reflection_info::function_context::FunctionContext;
+use hakana_reflection_info::issue::{Issue, IssueKind};
+use hakana_reflection_info::member_visibility::MemberVisibility;
+use hakana_reflection_info::t_atomic::TAtomic;
+use hakana_reflection_info::t_union::TUnion;
+use hakana_reflection_info::StrId;
+use oxidized::aast;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::function_analysis_data::FunctionAnalysisData;
+use crate::scope_analyzer::ScopeAnalyzer;
+use crate::statements_analyzer::StatementsAnalyzer;
+
+/// Checks that covariant (`+T`) class templates only appear where values are read out of
+/// the class, and contravariant (`-T`) templates only where values are passed in
+pub(crate) fn check_class(
+    statements_analyzer: &StatementsAnalyzer,
+    stmt: &aast::Class_<(), ()>,
+    classlike_storage: &ClassLikeInfo,
+    analysis_data: &mut FunctionAnalysisData,
+    function_context: &FunctionContext,
+) {
+    let template_variances = classlike_storage
+        .template_types
+        .keys()
+        .enumerate()
+        .filter_map(
+            |(i, param_name)| match classlike_storage.generic_variance.get(&i) {
+                Some(Variance::Covariant) => Some((*param_name, Variance::Covariant)),
+                Some(Variance::Contravariant) => Some((*param_name, Variance::Contravariant)),
+                _ => None,
+            },
+        )
+        .collect::<FxHashMap<_, _>>();
+
+    if template_variances.is_empty() {
+        return;
+    }
+
+    let codebase = statements_analyzer.get_codebase();
+    let interner = statements_analyzer.get_interner();
+    let class_name = interner.lookup(&classlike_storage.name);
+
+    let checker = VarianceChecker {
+        codebase,
+        classlike_name: &classlike_storage.name,
+        template_variances: &template_variances,
+    };
+
+    let mut violations = vec![];
+
+    for method_name in &classlike_storage.methods {
+        if method_name == &StrId::CONSTRUCT {
+            continue;
+        }
+
+        let functionlike_storage = if let Some(functionlike_storage) = codebase
+            .functionlike_infos
+            .get(&(classlike_storage.name, *method_name))
+        {
+            functionlike_storage
+        } else {
+            continue;
+        };
+
+        // class templates aren't in scope in static methods
+        if functionlike_storage
+            .method_info
+            .as_ref()
+            .map_or(false, |method_info| method_info.is_static)
+        {
+            continue;
+        }
+
+        let method_name = interner.lookup(method_name);
+
+        for param in &functionlike_storage.params {
+            if let Some(param_type) = &param.signature_type {
+                violations.extend(
+                    checker
+                        .get_violations(
+                            param_type,
+                            if param.is_inout {
+                                Variance::Invariant
+                            } else {
+                                Variance::Contravariant
+                            },
+                        )
+                        .into_iter()
+                        .map(|(param_name, variance)| {
+                            (
+                                param_name,
+                                variance,
+                                format!(
+                                    "the type of parameter {} of {}::{}",
+                                    param.name, class_name, method_name
+                                ),
+                                param.signature_type_location.unwrap_or(param.location),
+                            )
+                        }),
+                );
+            }
+        }
+
+        if let (Some(return_type), Some(return_type_location)) = (
+            &functionlike_storage.return_type,
+            functionlike_storage.return_type_location,
+        ) {
+            violations.extend(
+                checker
+                    .get_violations(return_type, Variance::Covariant)
+                    .into_iter()
+                    .map(|(param_name, variance)| {
+                        (
+                            param_name,
+                            variance,
+                            format!("the return type of {}::{}", class_name, method_name),
+                            return_type_location,
+                        )
+                    }),
+            );
+        }
+    }
+
+    let readonly_properties = get_readonly_property_names(stmt);
+
+    for (property_name, property_storage) in &classlike_storage.properties {
+        if property_storage.is_static {
+            continue;
+        }
+
+        let property_name = interner.lookup(property_name);
+
+        // only the class itself can write to private properties, and any values it writes
+        // come through method params that are checked above
+        let is_readonly = readonly_properties.contains(property_name)
+            || property_storage.soft_readonly
+            || matches!(property_storage.visibility, MemberVisibility::Private);
+
+        let property_location =
+            if let Some(location) = property_storage.type_pos.or(property_storage.pos) {
+                location
+            } else {
+                continue;
+            };
+
+        violations.extend(
+            checker
+                .get_violations(
+                    &property_storage.type_,
+                    if is_readonly {
+                        Variance::Covariant
+                    } else {
+                        Variance::Invariant
+                    },
+                )
+                .into_iter()
+                .map(|(param_name, variance)| {
+                    (
+                        param_name,
+                        variance,
+                        format!(
+                            "the type of {}property {}::${}",
+                            if is_readonly { "" } else { "mutable " },
+                            class_name,
+                            property_name
+                        ),
+                        property_location,
+                    )
+                }),
+        );
+    }
+
+    let resolved_names = statements_analyzer.get_file_analyzer().resolved_names;
+
+    for parent_hint in stmt.extends.iter().chain(stmt.implements.iter()) {
+        let parent_name = if let aast::Hint_::Happly(parent_name, _) = &*parent_hint.1 {
+            if let Some(parent_name) = resolved_names.get(&parent_name.0.start_offset()) {
+                parent_name
+            } else {
+                continue;
+            }
+        } else {
+            continue;
+        };
+
+        let (parent_storage, type_params) = if let (Some(parent_storage), Some(type_params)) = (
+            codebase.classlike_infos.get(parent_name),
+            classlike_storage.template_extended_offsets.get(parent_name),
+        ) {
+            (parent_storage, type_params)
+        } else {
+            continue;
+        };
+
+        for (i, type_param) in type_params.iter().enumerate() {
+            violations.extend(
+                checker
+                    .get_violations(
+                        type_param,
+                        parent_storage
+                            .generic_variance
+                            .get(&i)
+                            .cloned()
+                            .unwrap_or(Variance::Invariant),
+                    )
+                    .into_iter()
+                    .map(|(param_name, variance)| {
+                        (
+                            param_name,
+                            variance,
+                            format!("the type arguments of {}", interner.lookup(parent_name)),
+                            statements_analyzer.get_hpos(&parent_hint.0),
+                        )
+                    }),
+            );
+        }
+    }
+
+    let mut seen_violations = FxHashSet::default();
+
+    for (param_name, variance, description, pos) in violations {
+        if !seen_violations.insert((param_name, description.clone(), pos.start_offset)) {
+            continue;
+        }
+
+        analysis_data.maybe_add_issue(
+            Issue::new(
+                IssueKind::InvalidTemplateVariance,
+                format!(
+                    "{} template {} of {} cannot be used in {}",
+                    match variance {
+                        Variance::Contravariant => "Contravariant",
+                        _ => "Covariant",
+                    },
+                    interner.lookup(&param_name),
+                    class_name,
+                    description
+                ),
+                pos,
+                &function_context.calling_functionlike_id,
+            ),
+            statements_analyzer.get_config(),
+            statements_analyzer.get_file_path_actual(),
+        );
+    }
+}
+
+fn get_readonly_property_names(stmt: &aast::Class_<(), ()>) -> FxHashSet<&str> {
+    stmt.vars
+        .iter()
+        .filter(|class_var| class_var.readonly)
+        .map(|class_var| class_var.id.1.as_str())
+        .collect()
+}
+
+struct VarianceChecker<'a> {
+    codebase: &'a CodebaseInfo,
+    classlike_name: &'a StrId,
+    template_variances: &'a FxHashMap<StrId, Variance>,
+}
+
+impl<'a> VarianceChecker<'a> {
+    /// Gets the class templates that appear in the given type with a variance that
+    /// isn't allowed in that position
+    fn get_violations(&self, union: &TUnion, position: Variance) -> Vec<(StrId, Variance)> {
+        let mut violations = vec![];
+
+        self.collect_violations(union, &position, &mut violations);
+
+        violations
+    }
+
+    fn collect_violations(
+        &self,
+        union: &TUnion,
+        position: &Variance,
+        violations: &mut Vec<(StrId, Variance)>,
+    ) {
+        for atomic in &union.types {
+            match atomic {
+                TAtomic::TGenericParam {
+                    param_name,
+                    defining_entity,
+                    ..
+                } => {
+                    if defining_entity != self.classlike_name {
+                        continue;
+                    }
+
+                    match (self.template_variances.get(param_name), position) {
+                        (
+                            Some(Variance::Covariant),
+                            Variance::Contravariant | Variance::Invariant,
+                        ) => {
+                            violations.push((*param_name, Variance::Covariant));
+                        }
+                        (
+                            Some(Variance::Contravariant),
+                            Variance::Covariant | Variance::Invariant,
+                        ) => {
+                            violations.push((*param_name, Variance::Contravariant));
+                        }
+                        _ => {}
+                    }
+                }
+                TAtomic::TNamedObject {
+                    name,
+                    type_params: Some(type_params),
+                    ..
+                } => {
+                    let classlike_storage =
+                        if let Some(classlike_storage) = self.codebase.classlike_infos.get(name) {
+                            classlike_storage
+                        } else {
+                            continue;
+                        };
+
+                    for (i, type_param) in type_params.iter().enumerate() {
+                        let param_position = match classlike_storage.generic_variance.get(&i) {
+                            Some(Variance::Covariant) => position.clone(),
+                            Some(Variance::Contravariant) => flip(position),
+                            _ => Variance::Invariant,
+                        };
+
+                        self.collect_violations(type_param, &param_position, violations);
+                    }
+                }
+                TAtomic::TVec {
+                    known_items,
+                    type_param,
+                    ..
+                } => {
+                    if let Some(known_items) = known_items {
+                        for (_, item_type) in known_items.values() {
+                            self.collect_violations(item_type, position, violations);
+                        }
+                    }
+
+                    self.collect_violations(type_param, position, violations);
+                }
+                TAtomic::TDict {
+                    known_items,
+                    params,
+                    ..
+                } => {
+                    if let Some(known_items) = known_items {
+                        for (_, item_type) in known_items.values() {
+                            self.collect_violations(item_type, position, violations);
+                        }
+                    }
+
+                    if let Some((key_param, value_param)) = params {
+                        self.collect_violations(key_param, position, violations);
+                        self.collect_violations(value_param, position, violations);
+                    }
+                }
+                TAtomic::TKeyset { type_param } => {
+                    self.collect_violations(type_param, position, violations);
+                }
+                TAtomic::TClosure {
+                    params,
+                    return_type,
+                    ..
+                } => {
+                    for param in params {
+                        if let Some(param_type) = &param.signature_type {
+                            self.collect_violations(
+                                param_type,
+                                &if param.is_inout {
+                                    Variance::Invariant
+                                } else {
+                                    flip(position)
+                                },
+                                violations,
+                            );
+                        }
+                    }
+
+                    if let Some(return_type) = return_type {
+                        self.collect_violations(return_type, position, violations);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn flip(position: &Variance) -> Variance {
+    match position {
+        Variance::Invariant => Variance::Invariant,
+        Variance::Covariant => Variance::Contravariant,
+        Variance::Contravariant => Variance::Covariant,
+    }
+}
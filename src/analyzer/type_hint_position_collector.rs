@@ -0,0 +1,58 @@
+use hakana_reflection_info::codebase_info::CodebaseInfo;
+use hakana_reflection_info::StrId;
+use oxidized::{
+    aast,
+    aast_visitor::{visit, AstParams, Node, Visitor},
+};
+use rustc_hash::FxHashMap;
+
+struct Scanner<'a> {
+    resolved_names: &'a FxHashMap<usize, StrId>,
+    codebase: &'a CodebaseInfo,
+}
+
+impl<'ast> Visitor<'ast> for Scanner<'_> {
+    type Params = AstParams<FxHashMap<(u32, u32), (StrId, StrId)>, ()>;
+
+    fn object(&mut self) -> &mut dyn Visitor<'ast, Params = Self::Params> {
+        self
+    }
+
+    fn visit_hint_(
+        &mut self,
+        symbol_positions: &mut FxHashMap<(u32, u32), (StrId, StrId)>,
+        hint: &aast::Hint_,
+    ) -> Result<(), ()> {
+        if let aast::Hint_::Happly(id, _) = hint {
+            if let Some(name) = self.resolved_names.get(&id.0.start_offset()) {
+                // generic params and builtin types don't resolve to a definition
+                if self.codebase.classlike_infos.contains_key(name)
+                    || self.codebase.type_definitions.contains_key(name)
+                {
+                    symbol_positions.insert(
+                        (id.0.start_offset() as u32, id.0.end_offset() as u32),
+                        (*name, StrId::EMPTY),
+                    );
+                }
+            }
+        }
+
+        hint.recurse(symbol_positions, self)
+    }
+}
+
+/// Collects the positions of the classes and type aliases named in a definition's type hints,
+/// wherever they appear, so the language server can resolve them like other references
+pub(crate) fn collect(
+    def: &aast::Def<(), ()>,
+    resolved_names: &FxHashMap<usize, StrId>,
+    codebase: &CodebaseInfo,
+    symbol_positions: &mut FxHashMap<(u32, u32), (StrId, StrId)>,
+) {
+    let mut scanner = Scanner {
+        resolved_names,
+        codebase,
+    };
+
+    visit(&mut scanner, symbol_positions, def).unwrap();
+}
@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use hakana_reflection_info::issue::Issue;
+use xxhash_rust::xxh3::xxh3_64;
+
+// file path => issue kind => snippet fingerprint => count
+pub type Baseline = BTreeMap<String, BTreeMap<String, BTreeMap<String, usize>>>;
+
+pub struct BaselineEntry {
+    pub file_path: String,
+    pub issue_kind: String,
+    pub fingerprint: String,
+    pub count: usize,
+}
+
+pub fn generate_baseline(
+    issues: &BTreeMap<String, Vec<&Issue>>,
+    root_dir: &str,
+) -> Result<Baseline, String> {
+    let mut baseline = Baseline::new();
+
+    for (file_path, file_issues) in issues {
+        let file_contents = read_file_contents(root_dir, file_path)?;
+
+        for issue in file_issues {
+            *baseline
+                .entry(file_path.clone())
+                .or_default()
+                .entry(issue.kind.to_string())
+                .or_default()
+                .entry(get_issue_fingerprint(issue, &file_contents))
+                .or_insert(0) += 1;
+        }
+    }
+
+    Ok(baseline)
+}
+
+pub fn read_baseline(baseline_path: &str) -> Result<Baseline, String> {
+    let contents = fs::read_to_string(baseline_path)
+        .map_err(|e| format!("Could not read baseline file {}: {}", baseline_path, e))?;
+
+    serde_json::from_str(&contents)
+        .map_err(|e| format!("Could not parse baseline file {}: {}", baseline_path, e))
+}
+
+pub fn write_baseline(baseline_path: &str, baseline: &Baseline) -> Result<(), String> {
+    let contents = serde_json::to_string_pretty(baseline).unwrap();
+
+    fs::write(baseline_path, contents)
+        .map_err(|e| format!("Could not write baseline file {}: {}", baseline_path, e))
+}
+
+/// Removes all issues that match an entry in the baseline, and returns
+/// the baseline entries that were not matched by any issue
+pub fn filter_issues_using_baseline<'a>(
+    issues: BTreeMap<String, Vec<&'a Issue>>,
+    mut baseline: Baseline,
+    root_dir: &str,
+) -> Result<(BTreeMap<String, Vec<&'a Issue>>, Vec<BaselineEntry>), String> {
+    let mut filtered_issues = BTreeMap::new();
+
+    for (file_path, file_issues) in issues {
+        let file_baseline = if let Some(file_baseline) = baseline.get_mut(&file_path) {
+            file_baseline
+        } else {
+            filtered_issues.insert(file_path, file_issues);
+            continue;
+        };
+
+        let file_contents = read_file_contents(root_dir, &file_path)?;
+
+        let mut remaining_issues = vec![];
+
+        for issue in file_issues {
+            let fingerprint = get_issue_fingerprint(issue, &file_contents);
+
+            if let Some(count) = file_baseline
+                .get_mut(&issue.kind.to_string())
+                .and_then(|kind_baseline| kind_baseline.get_mut(&fingerprint))
+            {
+                if *count > 0 {
+                    *count -= 1;
+                    continue;
+                }
+            }
+
+            remaining_issues.push(issue);
+        }
+
+        if !remaining_issues.is_empty() {
+            filtered_issues.insert(file_path, remaining_issues);
+        }
+    }
+
+    let mut unmatched_entries = vec![];
+
+    for (file_path, file_baseline) in baseline {
+        for (issue_kind, kind_baseline) in file_baseline {
+            for (fingerprint, count) in kind_baseline {
+                if count > 0 {
+                    unmatched_entries.push(BaselineEntry {
+                        file_path: file_path.clone(),
+                        issue_kind: issue_kind.clone(),
+                        fingerprint,
+                        count,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok((filtered_issues, unmatched_entries))
+}
+
+fn read_file_contents(root_dir: &str, file_path: &str) -> Result<String, String> {
+    let path = format!("{}/{}", root_dir, file_path);
+
+    fs::read_to_string(&path).map_err(|e| format!("Could not read file {}: {}", path, e))
+}
+
+/// The fingerprint ignores line and column numbers, and whitespace inside
+/// the snippet, so issues still match after code has moved around
+fn get_issue_fingerprint(issue: &Issue, file_contents: &str) -> String {
+    let snippet = file_contents
+        .get(issue.pos.start_offset as usize..issue.pos.end_offset as usize)
+        .unwrap_or("");
+
+    // the hash is stored in baseline files, so it has to be stable across builds and platforms
+    let hash = xxh3_64(
+        snippet
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .as_bytes(),
+    );
+
+    format!("{:016x}", hash)
+}
@@ -0,0 +1,326 @@
+use std::collections::BTreeMap;
+
+use hakana_reflection_info::code_location::HPos;
+use hakana_reflection_info::codebase_info::CodebaseInfo;
+use hakana_reflection_info::data_flow::graph::DataFlowGraph;
+use hakana_reflection_info::data_flow::node::{DataFlowNode, DataFlowNodeKind};
+use hakana_reflection_info::data_flow::path::DataFlowPath;
+use hakana_reflection_info::taint::SinkType;
+use hakana_reflection_info::{Interner, StrId};
+use rustc_hash::FxHashSet;
+use serde_json::{json, Value};
+
+pub enum GraphDumpFilter {
+    /// Only nodes belonging to the given function or method (e.g. `Foo::bar`)
+    Function(String),
+    /// Only nodes reachable from the given source id
+    Source(String),
+}
+
+pub fn get_graph_dot(
+    graph: &DataFlowGraph,
+    filter: &Option<GraphDumpFilter>,
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+    cwd: &str,
+) -> String {
+    let (nodes, edges) = get_filtered_graph(graph, filter, codebase, interner);
+
+    let mut dot = "digraph dataflow {\n".to_string();
+
+    for node in nodes.values() {
+        let mut label = node.get_label().clone();
+
+        if let Some(pos) = get_node_pos(node) {
+            label += &format!("\n{}:{}", get_pos_path(pos, interner, cwd), pos.start_line);
+        }
+
+        let shape = match &node.kind {
+            DataFlowNodeKind::TaintSource { .. }
+            | DataFlowNodeKind::DataSource { .. }
+            | DataFlowNodeKind::VariableUseSource { .. }
+            | DataFlowNodeKind::ForLoopInit { .. } => "box",
+            DataFlowNodeKind::TaintSink { .. } | DataFlowNodeKind::VariableUseSink { .. } => {
+                "doubleoctagon"
+            }
+            DataFlowNodeKind::Vertex { .. } => "ellipse",
+        };
+
+        dot += &format!(
+            "  \"{}\" [label=\"{}\", shape={}];\n",
+            escape_dot_string(&node.id),
+            escape_dot_string(&label),
+            shape
+        );
+    }
+
+    for ((from_id, to_id), path) in edges {
+        let mut label = path.kind.to_unique_string();
+
+        if let Some(added_taints) = &path.added_taints {
+            label += &format!(" +[{}]", get_taints_string(added_taints));
+        }
+
+        if let Some(removed_taints) = &path.removed_taints {
+            label += &format!(" -[{}]", get_taints_string(removed_taints));
+        }
+
+        dot += &format!(
+            "  \"{}\" -> \"{}\" [label=\"{}\"];\n",
+            escape_dot_string(from_id),
+            escape_dot_string(to_id),
+            escape_dot_string(label.trim())
+        );
+    }
+
+    dot += "}\n";
+
+    dot
+}
+
+pub fn get_graph_json(
+    graph: &DataFlowGraph,
+    filter: &Option<GraphDumpFilter>,
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+    cwd: &str,
+) -> Value {
+    let (nodes, edges) = get_filtered_graph(graph, filter, codebase, interner);
+
+    json!({
+        "nodes": nodes
+            .values()
+            .map(|node| {
+                let mut node_json = json!({
+                    "id": node.id,
+                    "label": node.get_label(),
+                    "kind": match &node.kind {
+                        DataFlowNodeKind::Vertex { .. } => "vertex",
+                        DataFlowNodeKind::VariableUseSource { .. } => "variable_use_source",
+                        DataFlowNodeKind::VariableUseSink { .. } => "variable_use_sink",
+                        DataFlowNodeKind::ForLoopInit { .. } => "for_loop_init",
+                        DataFlowNodeKind::DataSource { .. } => "data_source",
+                        DataFlowNodeKind::TaintSource { .. } => "taint_source",
+                        DataFlowNodeKind::TaintSink { .. } => "taint_sink",
+                    },
+                });
+
+                if let Some(pos) = get_node_pos(node) {
+                    node_json["file"] = json!(get_pos_path(pos, interner, cwd));
+                    node_json["line"] = json!(pos.start_line);
+                }
+
+                match &node.kind {
+                    DataFlowNodeKind::TaintSource { types, .. } => {
+                        let mut types = types.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+                        types.sort();
+                        node_json["types"] = json!(types);
+                    }
+                    DataFlowNodeKind::TaintSink { types, .. } => {
+                        node_json["types"] = json!(get_sorted_taints(types));
+                    }
+                    _ => {}
+                }
+
+                node_json
+            })
+            .collect::<Vec<_>>(),
+        "edges": edges
+            .into_iter()
+            .map(|((from_id, to_id), path)| {
+                json!({
+                    "from": from_id,
+                    "to": to_id,
+                    "kind": path.kind.to_unique_string(),
+                    "added_taints": path.added_taints.as_ref().map(get_sorted_taints),
+                    "removed_taints": path.removed_taints.as_ref().map(get_sorted_taints),
+                })
+            })
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Gets the nodes and edges to dump, sorted by id so that dumps of the same graph can be diffed
+fn get_filtered_graph<'a>(
+    graph: &'a DataFlowGraph,
+    filter: &Option<GraphDumpFilter>,
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+) -> (
+    BTreeMap<&'a String, &'a DataFlowNode>,
+    BTreeMap<(&'a String, &'a String), &'a DataFlowPath>,
+) {
+    let node_ids = filter
+        .as_ref()
+        .map(|filter| get_filtered_node_ids(graph, filter, codebase, interner));
+
+    let is_included = |id: &String| {
+        node_ids
+            .as_ref()
+            .map_or(true, |node_ids| node_ids.contains(id))
+    };
+
+    // a function's incoming edges show how it's called, but anything flowing into
+    // the nodes reachable from a source is irrelevant to that source
+    let include_incoming_edges = matches!(filter, Some(GraphDumpFilter::Function(_)));
+
+    let mut edges = BTreeMap::new();
+    let mut edge_node_ids = FxHashSet::default();
+
+    for (from_id, to) in &graph.forward_edges {
+        let is_from_included = is_included(from_id);
+
+        for (to_id, path) in to {
+            if is_from_included || (include_incoming_edges && is_included(to_id)) {
+                edges.insert((from_id, to_id), path);
+                edge_node_ids.insert(from_id);
+                edge_node_ids.insert(to_id);
+            }
+        }
+    }
+
+    let mut nodes = BTreeMap::new();
+
+    for node in graph
+        .vertices
+        .values()
+        .chain(graph.sources.values())
+        .chain(graph.sinks.values())
+    {
+        if is_included(&node.id) || edge_node_ids.contains(&node.id) {
+            nodes.insert(&node.id, node);
+        }
+    }
+
+    (nodes, edges)
+}
+
+fn get_filtered_node_ids(
+    graph: &DataFlowGraph,
+    filter: &GraphDumpFilter,
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+) -> FxHashSet<String> {
+    match filter {
+        GraphDumpFilter::Function(function_name) => {
+            get_function_node_ids(graph, function_name, codebase, interner)
+        }
+        GraphDumpFilter::Source(source_id) => get_reachable_node_ids(graph, source_id),
+    }
+}
+
+fn get_function_node_ids(
+    graph: &DataFlowGraph,
+    function_name: &String,
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+) -> FxHashSet<String> {
+    let functionlike_key = if let Some((class_name, method_name)) = function_name.split_once("::") {
+        interner.get(class_name).zip(interner.get(method_name))
+    } else {
+        interner.get(function_name).map(|name| (name, StrId::EMPTY))
+    };
+
+    let def_location = functionlike_key
+        .and_then(|key| codebase.functionlike_infos.get(&key))
+        .map(|functionlike_info| functionlike_info.def_location);
+
+    graph
+        .vertices
+        .values()
+        .chain(graph.sources.values())
+        .chain(graph.sinks.values())
+        .filter(|node| {
+            // params and return values use the function name as their id prefix,
+            // everything else inside the function body is identified by its position
+            node.id == *function_name
+                || node
+                    .id
+                    .strip_prefix(function_name.as_str())
+                    .map_or(false, |suffix| {
+                        suffix.starts_with('#') || suffix.starts_with('-')
+                    })
+                || def_location.map_or(false, |def_location| {
+                    get_node_pos(node).map_or(false, |pos| {
+                        pos.file_path == def_location.file_path
+                            && pos.start_offset >= def_location.start_offset
+                            && pos.end_offset <= def_location.end_offset
+                    })
+                })
+        })
+        .map(|node| node.id.clone())
+        .collect()
+}
+
+/// Follows forward edges from the given source, hopping between specialized
+/// and unspecialized nodes the same way the search for sinks does
+fn get_reachable_node_ids(graph: &DataFlowGraph, source_id: &String) -> FxHashSet<String> {
+    let mut reachable_node_ids = FxHashSet::default();
+
+    let mut node_ids = graph
+        .sources
+        .values()
+        .filter(|source| source.id == *source_id || source.get_label() == source_id)
+        .map(|source| source.id.clone())
+        .collect::<Vec<_>>();
+
+    while let Some(node_id) = node_ids.pop() {
+        if !reachable_node_ids.insert(node_id.clone()) {
+            continue;
+        }
+
+        if let Some(to) = graph.forward_edges.get(&node_id) {
+            node_ids.extend(to.keys().cloned());
+        }
+
+        if let Some(DataFlowNodeKind::Vertex {
+            unspecialized_id: Some(unspecialized_id),
+            ..
+        }) = graph.vertices.get(&node_id).map(|node| &node.kind)
+        {
+            node_ids.push(unspecialized_id.clone());
+        }
+
+        if let Some(specializations) = graph.specializations.get(&node_id) {
+            node_ids.extend(
+                specializations
+                    .iter()
+                    .map(|specialization| format!("{}-{}", node_id, specialization)),
+            );
+        }
+    }
+
+    reachable_node_ids
+}
+
+fn get_node_pos(node: &DataFlowNode) -> Option<&HPos> {
+    match &node.kind {
+        DataFlowNodeKind::Vertex { pos, .. }
+        | DataFlowNodeKind::TaintSource { pos, .. }
+        | DataFlowNodeKind::TaintSink { pos, .. } => pos.as_ref(),
+        DataFlowNodeKind::VariableUseSource { pos, .. }
+        | DataFlowNodeKind::VariableUseSink { pos }
+        | DataFlowNodeKind::DataSource { pos, .. } => Some(pos),
+        DataFlowNodeKind::ForLoopInit { .. } => None,
+    }
+}
+
+fn get_pos_path(pos: &HPos, interner: &Interner, cwd: &str) -> String {
+    pos.file_path.get_relative_path(interner, cwd)
+}
+
+fn get_sorted_taints(taints: &FxHashSet<SinkType>) -> Vec<String> {
+    let mut taints = taints.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+    taints.sort();
+    taints
+}
+
+fn get_taints_string(taints: &FxHashSet<SinkType>) -> String {
+    get_sorted_taints(taints).join(", ")
+}
+
+fn escape_dot_string(str: &str) -> String {
+    str.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -1,14 +1,19 @@
 use clap::{arg, Command};
+use graph_dump::GraphDumpFilter;
 use hakana_analyzer::config::{self};
 use hakana_analyzer::custom_hook::CustomHook;
+use hakana_analyzer::dataflow::program_analyzer;
 use hakana_logger::{Logger, Verbosity};
 use hakana_reflection_info::analysis_result::{AnalysisResult, CheckPointEntry, Replacement};
+use hakana_reflection_info::code_location::FilePath;
+use hakana_reflection_info::codebase_info::CodebaseInfo;
 use hakana_reflection_info::data_flow::graph::{GraphKind, WholeProgramKind};
-use hakana_reflection_info::issue::IssueKind;
+use hakana_reflection_info::issue::{Issue, IssueKind, IssueSeverity};
 use hakana_reflection_info::Interner;
+use hakana_workhorse::file::FileStatus;
 use indexmap::IndexMap;
 use rand::Rng;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::collections::BTreeMap;
 use std::env;
 use std::fs::{self, File};
@@ -18,6 +23,9 @@ use std::process::exit;
 use std::sync::Arc;
 use test_runners::test_runner::TestRunner;
 
+pub mod baseline;
+pub mod graph_dump;
+pub mod sarif;
 pub mod test_runners;
 
 pub fn init(
@@ -135,6 +143,36 @@ pub fn init(
                     arg!(--"output" <PATH>)
                         .required(false)
                         .help("File to save output to"),
+                )
+                .arg(
+                    arg!(--"output-format" <FORMAT>)
+                        .required(false)
+                        .possible_values(["sarif"])
+                        .help("Format of the output file — defaults to checkpoint JSON"),
+                )
+                .arg(
+                    arg!(--"set-baseline" <PATH>)
+                        .required(false)
+                        .conflicts_with("use-baseline")
+                        .help("Save all current issues to a baseline file"),
+                )
+                .arg(
+                    arg!(--"use-baseline" <PATH>)
+                        .required(false)
+                        .help("Only report issues that are not in the given baseline file"),
+                )
+                .arg(
+                    arg!(--"since" <REF>)
+                        .required(false)
+                        .conflicts_with("changed-files")
+                        .conflicts_with("no-cache")
+                        .help("Only analyze files changed since this git ref, plus dependents"),
+                )
+                .arg(
+                    arg!(--"changed-files" <PATH>)
+                        .required(false)
+                        .conflicts_with("no-cache")
+                        .help("Only analyze the files listed in this file, plus dependents"),
                 ),
         )
         .subcommand(
@@ -282,6 +320,11 @@ pub fn init(
                         .required(false)
                         .help("Filter the files that are fixed"),
                 )
+                .arg(
+                    arg!(--"tighten-return-types")
+                        .required(false)
+                        .help("When fixing MissingReturnType, also narrow mixed and dynamic return types"),
+                )
                 .arg(
                     arg!(--"threads" <PATH>)
                         .required(false)
@@ -314,6 +357,43 @@ pub fn init(
                         .required(false)
                         .help("Length of the longest allowable path"),
                 )
+                .arg(
+                    arg!(--"dump-graph" <PATH>)
+                        .required(false)
+                        .help("File to save the data-flow graph to — uses JSON if the path ends with .json, and Graphviz DOT otherwise"),
+                )
+                .arg(
+                    arg!(--"dump-graph-function" <NAME>)
+                        .required(false)
+                        .help("Only dump the parts of the graph belonging to the given function or method"),
+                )
+                .arg(
+                    arg!(--"dump-graph-source" <ID>)
+                        .required(false)
+                        .help("Only dump the parts of the graph reachable from the given source"),
+                )
+                .arg(
+                    arg!(--"explain-from" <LOCATION>)
+                        .required(false)
+                        .requires("explain-to")
+                        .help("Explain where data from the given file:line stops flowing"),
+                )
+                .arg(
+                    arg!(--"explain-to" <LOCATION>)
+                        .required(false)
+                        .requires("explain-from")
+                        .help("The file:line that data is expected to flow to"),
+                )
+                .arg(
+                    arg!(--"no-cache")
+                        .required(false)
+                        .help("Whether to ignore the cache"),
+                )
+                .arg(
+                    arg!(--"diff")
+                        .required(false)
+                        .help("Whether to reuse cached taint summaries for unchanged files"),
+                )
                 .arg(
                     arg!(--"debug")
                         .required(false)
@@ -323,6 +403,12 @@ pub fn init(
                     arg!(--"output" <PATH>)
                         .required(false)
                         .help("File to save output to"),
+                )
+                .arg(
+                    arg!(--"output-format" <FORMAT>)
+                        .required(false)
+                        .possible_values(["sarif"])
+                        .help("Format of the output file — defaults to checkpoint JSON"),
                 ),
         )
         .subcommand(
@@ -346,6 +432,21 @@ pub fn init(
                         .required(false)
                         .help("Length of the longest allowable path"),
                 )
+                .arg(
+                    arg!(--"dump-graph" <PATH>)
+                        .required(false)
+                        .help("File to save the data-flow graph to — uses JSON if the path ends with .json, and Graphviz DOT otherwise"),
+                )
+                .arg(
+                    arg!(--"dump-graph-function" <NAME>)
+                        .required(false)
+                        .help("Only dump the parts of the graph belonging to the given function or method"),
+                )
+                .arg(
+                    arg!(--"dump-graph-source" <ID>)
+                        .required(false)
+                        .help("Only dump the parts of the graph reachable from the given source"),
+                )
                 .arg(
                     arg!(--"debug")
                         .required(false)
@@ -475,6 +576,7 @@ pub fn init(
                 config_path,
                 sub_matches,
                 analysis_hooks,
+                cache_dir,
                 threads,
                 logger,
                 header,
@@ -607,22 +709,30 @@ fn do_fix(
     logger: Logger,
     header: &str,
 ) {
-    let issue_name = sub_matches.value_of("issue").unwrap().to_string();
-    let issue_kind = IssueKind::from_str_custom(&issue_name, &all_custom_issues).unwrap();
-
     let filter = sub_matches.value_of("filter").map(|f| f.to_string());
 
     let mut config = config::Config::new(root_dir.clone(), all_custom_issues);
-    config.hooks = analysis_hooks;
+    config.hooks = analysis_hooks.into_iter().map(Arc::from).collect();
+
+    // plugins loaded from the config file can add custom issue names
+    update_config_from_file(&mut config, &cwd, config_path.unwrap());
+
+    let issue_name = sub_matches.value_of("issue").unwrap().to_string();
+    let issue_kind = if let Ok(issue_kind) =
+        IssueKind::from_str_custom(&issue_name, &config.all_custom_issues)
+    {
+        issue_kind
+    } else {
+        println!("Invalid issue type {}", issue_name);
+        exit(1);
+    };
 
     config.find_unused_expressions = issue_kind.is_unused_expression();
     config.find_unused_definitions = issue_kind.is_unused_definition();
     config.issues_to_fix.insert(issue_kind);
 
-    let config_path = config_path.unwrap();
-
-    if config_path.exists() {
-        config.update_from_file(&cwd, config_path).ok();
+    if sub_matches.is_present("tighten-return-types") {
+        config.tighten_return_types = true;
     }
 
     config.allowed_issues = None;
@@ -665,13 +775,9 @@ fn do_remove_unused_fixmes(
 
     let mut config = config::Config::new(root_dir.clone(), all_custom_issues);
 
-    config.hooks = analysis_hooks;
+    config.hooks = analysis_hooks.into_iter().map(Arc::from).collect();
 
-    let config_path = config_path.unwrap();
-
-    if config_path.exists() {
-        config.update_from_file(cwd, config_path).ok();
-    }
+    update_config_from_file(&mut config, cwd, config_path.unwrap());
     config.allowed_issues = None;
 
     config.find_unused_expressions = true;
@@ -713,39 +819,33 @@ fn do_add_fixmes(
     logger: Logger,
     header: &str,
 ) {
+    let filter = sub_matches.value_of("filter").map(|f| f.to_string());
+
+    let mut config = config::Config::new(root_dir.clone(), all_custom_issues);
+
+    config.hooks = analysis_hooks.into_iter().map(Arc::from).collect();
+    config.find_unused_expressions = true;
+    config.find_unused_definitions = true;
+
+    // plugins loaded from the config file can add custom issue names
+    update_config_from_file(&mut config, cwd, config_path.unwrap());
+
     let filter_issue_strings = sub_matches
         .values_of("issue")
         .map(|values| values.collect::<FxHashSet<_>>());
 
-    let mut issue_kinds_filter = FxHashSet::default();
-
     if let Some(filter_issue_strings) = filter_issue_strings {
         for filter_issue_string in filter_issue_strings {
             if let Ok(issue_kind) =
-                IssueKind::from_str_custom(filter_issue_string, &all_custom_issues)
+                IssueKind::from_str_custom(filter_issue_string, &config.all_custom_issues)
             {
-                issue_kinds_filter.insert(issue_kind);
+                config.issues_to_fix.insert(issue_kind);
             } else {
                 println!("Invalid issue type {}", filter_issue_string);
                 exit(1);
             }
         }
     }
-
-    let filter = sub_matches.value_of("filter").map(|f| f.to_string());
-
-    let mut config = config::Config::new(root_dir.clone(), all_custom_issues);
-
-    config.issues_to_fix.extend(issue_kinds_filter);
-    config.hooks = analysis_hooks;
-    config.find_unused_expressions = true;
-    config.find_unused_definitions = true;
-
-    let config_path = config_path.unwrap();
-
-    if config_path.exists() {
-        config.update_from_file(cwd, config_path).ok();
-    }
     config.allowed_issues = None;
 
     config.add_fixmes = true;
@@ -797,6 +897,7 @@ fn do_migrate(
                 false
             }
         })
+        .map(Arc::from)
         .collect();
 
     if config.hooks.is_empty() {
@@ -804,11 +905,7 @@ fn do_migrate(
         exit(1);
     }
 
-    let config_path = config_path.unwrap();
-
-    if config_path.exists() {
-        config.update_from_file(cwd, config_path).ok();
-    }
+    update_config_from_file(&mut config, cwd, config_path.unwrap());
     config.allowed_issues = None;
 
     let file_path = format!("{}/{}", cwd, migration_source);
@@ -880,6 +977,7 @@ fn do_migration_candidates(
                 false
             }
         })
+        .map(Arc::from)
         .collect();
 
     if config.hooks.is_empty() {
@@ -887,11 +985,7 @@ fn do_migration_candidates(
         exit(1);
     }
 
-    let config_path = config_path.unwrap();
-
-    if config_path.exists() {
-        config.update_from_file(cwd, config_path).ok();
-    }
+    update_config_from_file(&mut config, cwd, config_path.unwrap());
     config.allowed_issues = None;
 
     let config = Arc::new(config);
@@ -937,11 +1031,7 @@ fn do_find_paths(
     let mut config = config::Config::new(cwd.clone(), all_custom_issues);
     config.graph_kind = GraphKind::WholeProgram(WholeProgramKind::Query);
 
-    let config_path = config_path.unwrap();
-
-    if config_path.exists() {
-        config.update_from_file(cwd, config_path).ok();
-    }
+    update_config_from_file(&mut config, cwd, config_path.unwrap());
     config.allowed_issues = None;
 
     config.security_config.max_depth =
@@ -951,15 +1041,17 @@ fn do_find_paths(
             20
         };
 
-    config.hooks = analysis_hooks;
+    config.hooks = analysis_hooks.into_iter().map(Arc::from).collect();
 
     let root_dir = config.root_dir.clone();
 
+    let config = Arc::new(config);
+
     let result = hakana_workhorse::scan_and_analyze(
         Vec::new(),
         None,
         None,
-        Arc::new(config),
+        config.clone(),
         None,
         threads,
         Arc::new(logger),
@@ -970,16 +1062,25 @@ fn do_find_paths(
     );
 
     if let Ok((analysis_result, successful_run_data)) = result {
-        for (file_path, issues) in
-            analysis_result.get_all_issues(&successful_run_data.interner, &root_dir, true)
-        {
-            for issue in issues {
-                *had_error = true;
-                println!("{}", issue.format(&file_path));
-            }
-        }
+        dump_graph(
+            sub_matches,
+            cwd,
+            &analysis_result,
+            &successful_run_data.codebase,
+            &successful_run_data.interner,
+        );
+
+        let all_issues =
+            analysis_result.get_all_issues(&successful_run_data.interner, &root_dir, true);
+
+        print_issues(
+            &all_issues,
+            &config,
+            &successful_run_data.interner,
+            had_error,
+        );
 
-        if !*had_error {
+        if all_issues.is_empty() {
             println!("\nNo security issues found!\n");
         }
     }
@@ -991,6 +1092,7 @@ fn do_security_check(
     config_path: Option<&Path>,
     sub_matches: &clap::ArgMatches,
     analysis_hooks: Vec<Box<dyn CustomHook>>,
+    cache_dir: String,
     threads: u8,
     logger: Logger,
     header: &str,
@@ -999,14 +1101,27 @@ fn do_security_check(
     let mut config = config::Config::new(cwd.clone(), all_custom_issues);
     config.graph_kind = GraphKind::WholeProgram(WholeProgramKind::Taint);
 
-    let config_path = config_path.unwrap();
+    let explain_locations = if let (Some(explain_from), Some(explain_to)) = (
+        sub_matches.value_of("explain-from"),
+        sub_matches.value_of("explain-to"),
+    ) {
+        Some((
+            get_explain_location(explain_from, cwd),
+            get_explain_location(explain_to, cwd),
+        ))
+    } else {
+        None
+    };
 
-    if config_path.exists() {
-        config.update_from_file(cwd, config_path).ok();
-    }
+    // cached taint summaries don't record where taints stop flowing
+    config.ast_diff = sub_matches.is_present("diff") && explain_locations.is_none();
+    config.security_config.record_cutoffs = explain_locations.is_some();
+
+    update_config_from_file(&mut config, cwd, config_path.unwrap());
     config.allowed_issues = None;
 
     let output_file = sub_matches.value_of("output").map(|f| f.to_string());
+    let output_format = sub_matches.value_of("output-format").map(|f| f.to_string());
 
     config.security_config.max_depth =
         if let Some(val) = sub_matches.value_of("max-depth").map(|f| f.to_string()) {
@@ -1015,18 +1130,33 @@ fn do_security_check(
             20
         };
 
-    config.hooks = analysis_hooks;
+    config.hooks = analysis_hooks.into_iter().map(Arc::from).collect();
 
     let root_dir = config.root_dir.clone();
 
+    let config = Arc::new(config);
+    let logger = Arc::new(logger);
+
+    // security checks get their own cache, so the cached issues and taint summaries
+    // always match the cached codebase
+    let security_cache_dir = format!("{}/security", cache_dir);
+
+    if !Path::new(&security_cache_dir).is_dir() && fs::create_dir(&security_cache_dir).is_err() {
+        panic!("could not create security cache directory");
+    }
+
     let result = hakana_workhorse::scan_and_analyze(
         Vec::new(),
         None,
         None,
-        Arc::new(config),
-        None,
+        config.clone(),
+        if sub_matches.is_present("no-cache") {
+            None
+        } else {
+            Some(&security_cache_dir)
+        },
         threads,
-        Arc::new(logger),
+        logger.clone(),
         header,
         None,
         None,
@@ -1034,24 +1164,48 @@ fn do_security_check(
     );
 
     if let Ok((analysis_result, successful_run_data)) = result {
-        for (file_path, issues) in
-            analysis_result.get_all_issues(&successful_run_data.interner, &root_dir, true)
-        {
-            for issue in issues {
-                *had_error = true;
-                println!("{}", issue.format(&file_path));
-            }
+        dump_graph(
+            sub_matches,
+            cwd,
+            &analysis_result,
+            &successful_run_data.codebase,
+            &successful_run_data.interner,
+        );
+
+        if let Some((explain_from, explain_to)) = explain_locations {
+            explain_taint_paths(
+                &analysis_result,
+                &successful_run_data.codebase,
+                &successful_run_data.interner,
+                &config,
+                &logger,
+                explain_from,
+                explain_to,
+            );
+            return;
         }
 
-        if !*had_error {
+        let all_issues =
+            analysis_result.get_all_issues(&successful_run_data.interner, &root_dir, true);
+
+        print_issues(
+            &all_issues,
+            &config,
+            &successful_run_data.interner,
+            had_error,
+        );
+
+        if all_issues.is_empty() {
             println!("\nNo security issues found!\n");
         }
 
         if let Some(output_file) = output_file {
             write_output_files(
                 output_file,
+                output_format,
                 cwd,
-                &analysis_result,
+                &all_issues,
+                &config,
                 &successful_run_data.interner,
             );
         }
@@ -1074,6 +1228,7 @@ fn do_analysis(
     let filter = sub_matches.value_of("filter").map(|f| f.to_string());
 
     let output_file = sub_matches.value_of("output").map(|f| f.to_string());
+    let output_format = sub_matches.value_of("output-format").map(|f| f.to_string());
 
     let ignored = sub_matches
         .values_of("ignore")
@@ -1085,7 +1240,41 @@ fn do_analysis(
     let ignore_mixed_issues = sub_matches.is_present("ignore-mixed-issues");
     let show_issue_stats = sub_matches.is_present("show-issue-stats");
     let do_ast_diff = sub_matches.is_present("diff");
+    let set_baseline = sub_matches
+        .value_of("set-baseline")
+        .map(|f| get_path_relative_to_cwd(f, cwd));
+
+    let baseline = if let Some(use_baseline) = sub_matches.value_of("use-baseline") {
+        match baseline::read_baseline(&get_path_relative_to_cwd(use_baseline, cwd)) {
+            Ok(baseline) => Some(baseline),
+            Err(error) => {
+                println!("\nERROR: {}\n", error);
+                exit(1);
+            }
+        }
+    } else {
+        None
+    };
 
+    let mut config = config::Config::new(root_dir.to_string(), all_custom_issues);
+    config.find_unused_expressions = find_unused_expressions;
+    config.find_unused_definitions = find_unused_definitions;
+    config.ignore_mixed_issues = ignore_mixed_issues;
+    config.ast_diff = do_ast_diff;
+
+    let file_changes = get_file_changes(sub_matches, root_dir, &cache_dir);
+    let only_report_analyzed_files = file_changes.is_some();
+
+    // only the files affected by those changes are reanalyzed, which needs the AST diff
+    if only_report_analyzed_files {
+        config.ast_diff = true;
+    }
+
+    config.hooks = analysis_hooks.into_iter().map(Arc::from).collect();
+
+    update_config_from_file(&mut config, cwd, config_path.unwrap());
+
+    // plugins loaded from the config file can add custom issue names
     let mut issue_kinds_filter = FxHashSet::default();
 
     let filter_issue_strings = sub_matches
@@ -1095,7 +1284,7 @@ fn do_analysis(
     if let Some(filter_issue_strings) = filter_issue_strings {
         for filter_issue_string in filter_issue_strings {
             if let Ok(issue_kind) =
-                IssueKind::from_str_custom(filter_issue_string, &all_custom_issues)
+                IssueKind::from_str_custom(filter_issue_string, &config.all_custom_issues)
             {
                 issue_kinds_filter.insert(issue_kind);
             } else {
@@ -1105,20 +1294,6 @@ fn do_analysis(
         }
     }
 
-    let mut config = config::Config::new(root_dir.to_string(), all_custom_issues);
-    config.find_unused_expressions = find_unused_expressions;
-    config.find_unused_definitions = find_unused_definitions;
-    config.ignore_mixed_issues = ignore_mixed_issues;
-    config.ast_diff = do_ast_diff;
-
-    config.hooks = analysis_hooks;
-
-    let config_path = config_path.unwrap();
-
-    if config_path.exists() {
-        config.update_from_file(cwd, config_path).ok();
-    }
-
     // do this after we've loaded from file, as they can be overridden
     if !issue_kinds_filter.is_empty() {
         config.allowed_issues = Some(issue_kinds_filter);
@@ -1126,43 +1301,112 @@ fn do_analysis(
 
     let root_dir = config.root_dir.clone();
 
+    let config = Arc::new(config);
+
+    let use_cache = !sub_matches.is_present("no-cache");
+
+    // the cache is about to change, so it no longer matches the revision it was built from
+    if use_cache {
+        fs::remove_file(get_cache_revision_path(&cache_dir)).ok();
+    }
+
     let result = hakana_workhorse::scan_and_analyze(
         Vec::new(),
         filter,
         ignored,
-        Arc::new(config),
-        if sub_matches.is_present("no-cache") {
-            None
-        } else {
-            Some(&cache_dir)
-        },
+        config.clone(),
+        if use_cache { Some(&cache_dir) } else { None },
         threads,
         Arc::new(logger),
         header,
         None,
         None,
-        None,
+        file_changes,
     );
 
     if let Ok((analysis_result, successful_run_data)) = result {
-        for (file_path, issues) in
-            analysis_result.get_all_issues(&successful_run_data.interner, &root_dir, true)
-        {
-            for issue in issues {
-                *had_error = true;
-                println!("{}", issue.format(&file_path));
+        if use_cache {
+            if let Some(revision) = get_clean_git_revision(&root_dir) {
+                fs::write(get_cache_revision_path(&cache_dir), revision).ok();
+            }
+        }
+
+        let mut all_issues =
+            analysis_result.get_all_issues(&successful_run_data.interner, &root_dir, true);
+
+        if only_report_analyzed_files {
+            // issues loaded from the cache are for files that weren't affected
+            all_issues.retain(|_, issues| {
+                issues.retain(|issue| {
+                    analysis_result
+                        .analyzed_files
+                        .contains(&issue.pos.file_path)
+                });
+                !issues.is_empty()
+            });
+        }
+
+        if let Some(set_baseline) = set_baseline {
+            if let Err(error) = baseline::generate_baseline(&all_issues, &root_dir)
+                .and_then(|baseline| baseline::write_baseline(&set_baseline, &baseline))
+            {
+                println!("\nERROR: {}\n", error);
+                exit(1);
+            }
+
+            println!(
+                "\nBaseline with {} issues written to {}\n",
+                all_issues.values().map(|v| v.len()).sum::<usize>(),
+                set_baseline
+            );
+
+            return;
+        }
+
+        if let Some(baseline) = baseline {
+            let (filtered_issues, unmatched_entries) =
+                match baseline::filter_issues_using_baseline(all_issues, baseline, &root_dir) {
+                    Ok(result) => result,
+                    Err(error) => {
+                        println!("\nERROR: {}\n", error);
+                        exit(1);
+                    }
+                };
+
+            all_issues = filtered_issues;
+
+            if !unmatched_entries.is_empty() {
+                println!("Baseline entries that no longer match any issue:\n");
+
+                for entry in unmatched_entries {
+                    println!(
+                        "{} - {} - {} (x{})",
+                        entry.issue_kind, entry.file_path, entry.fingerprint, entry.count
+                    );
+                }
+
+                println!();
             }
         }
 
-        if !*had_error {
+        print_issues(
+            &all_issues,
+            &config,
+            &successful_run_data.interner,
+            had_error,
+        );
+
+        if all_issues.is_empty() {
             println!("\nNo issues reported!\n");
         }
 
         if let Some(output_file) = output_file {
             write_output_files(
                 output_file,
+                output_format,
                 cwd,
-                &analysis_result,
+                &all_issues,
+                &config,
                 &successful_run_data.interner,
             );
         }
@@ -1197,24 +1441,317 @@ fn do_analysis(
     }
 }
 
+/// Gets the files changed since a git ref (`--since`) or listed in a file (`--changed-files`),
+/// keyed by their full path. Unchanged files are loaded from the cache, so this exits if the
+/// cache can't be trusted to reflect everything else.
+fn get_file_changes(
+    sub_matches: &clap::ArgMatches,
+    root_dir: &str,
+    cache_dir: &str,
+) -> Option<FxHashMap<String, FileStatus>> {
+    let changed_paths = if let Some(since) = sub_matches.value_of("since") {
+        let since_revision = run_git_command(
+            root_dir,
+            &["rev-parse", "--verify", &format!("{}^{{commit}}", since)],
+        )
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+
+        let cache_revision = fs::read_to_string(get_cache_revision_path(cache_dir)).ok();
+
+        if cache_revision.as_deref().map(|r| r.trim()) != Some(since_revision.as_str()) {
+            println!(
+                "\nERROR: the cache in {} was not built from {}. Run hakana analyze on a clean checkout of {} first.\n",
+                cache_dir, since, since
+            );
+            exit(1);
+        }
+
+        // with renames detected only the new path would be listed, leaving the old one cached
+        let mut changed_paths = run_git_command(
+            root_dir,
+            &[
+                "diff",
+                "--name-only",
+                "--no-renames",
+                "--relative",
+                since,
+                "--",
+            ],
+        );
+        changed_paths.extend(run_git_command(
+            root_dir,
+            &["ls-files", "--others", "--exclude-standard"],
+        ));
+        changed_paths
+    } else if let Some(changed_files) = sub_matches.value_of("changed-files") {
+        match fs::read_to_string(changed_files) {
+            Ok(contents) => contents
+                .lines()
+                .map(|line| line.trim().to_string())
+                .filter(|line| !line.is_empty())
+                .collect(),
+            Err(error) => {
+                println!("\nERROR: could not read {}: {}\n", changed_files, error);
+                exit(1);
+            }
+        }
+    } else {
+        return None;
+    };
+
+    if !Path::new(&format!("{}/manifest", cache_dir)).exists() {
+        println!(
+            "\nERROR: only analyzing changed files needs an existing cache in {}. Run hakana analyze first.\n",
+            cache_dir
+        );
+        exit(1);
+    }
+
+    Some(
+        changed_paths
+            .into_iter()
+            .map(|changed_path| {
+                let full_path = if changed_path.starts_with('/') {
+                    changed_path
+                } else {
+                    format!("{}/{}", root_dir, changed_path)
+                };
+
+                let status = if Path::new(&full_path).exists() {
+                    FileStatus::Modified(0, 0)
+                } else {
+                    FileStatus::Deleted
+                };
+
+                (full_path, status)
+            })
+            .collect(),
+    )
+}
+
+fn get_cache_revision_path(cache_dir: &str) -> String {
+    format!("{}/git_revision", cache_dir)
+}
+
+/// Gets the current commit if the working tree has no uncommitted changes to tracked files,
+/// i.e. if results computed from the working tree also hold for that commit
+fn get_clean_git_revision(root_dir: &str) -> Option<String> {
+    let run = |args: &[&str]| {
+        std::process::Command::new("git")
+            .args(args)
+            .current_dir(root_dir)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+
+    if !run(&["status", "--porcelain", "--untracked-files=no"])?.is_empty() {
+        return None;
+    }
+
+    run(&["rev-parse", "--verify", "HEAD"])
+}
+
+fn run_git_command(root_dir: &str, args: &[&str]) -> Vec<String> {
+    let output = match std::process::Command::new("git")
+        .args(args)
+        .current_dir(root_dir)
+        .output()
+    {
+        Ok(output) => output,
+        Err(error) => {
+            println!("\nERROR: could not run git: {}\n", error);
+            exit(1);
+        }
+    };
+
+    if !output.status.success() {
+        println!(
+            "\nERROR: git {} failed: {}\n",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        exit(1);
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.to_string())
+        .collect()
+}
+
+fn get_path_relative_to_cwd(path: &str, cwd: &String) -> String {
+    if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("{}/{}", cwd, path)
+    }
+}
+
+fn get_explain_location(location: &str, cwd: &String) -> (String, u32) {
+    if let Some((file_path, line)) = location.rsplit_once(':') {
+        if let Ok(line) = line.parse::<u32>() {
+            return (get_path_relative_to_cwd(file_path, cwd), line);
+        }
+    }
+
+    println!(
+        "\nERROR: expected a location like path/to/file.hack:12, got {}\n",
+        location
+    );
+    exit(1);
+}
+
+fn explain_taint_paths(
+    analysis_result: &AnalysisResult,
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+    config: &config::Config,
+    logger: &Logger,
+    explain_from: (String, u32),
+    explain_to: (String, u32),
+) {
+    let get_file_path = |file_path: &String| {
+        if let Some(file_id) = interner.get(file_path) {
+            FilePath(file_id)
+        } else {
+            println!("\nERROR: {} was not analyzed\n", file_path);
+            exit(1);
+        }
+    };
+
+    let explanation = program_analyzer::explain_taint_paths(
+        &analysis_result.program_dataflow_graph,
+        config,
+        logger,
+        interner,
+        (get_file_path(&explain_from.0), explain_from.1),
+        (get_file_path(&explain_to.0), explain_to.1),
+    );
+
+    for found_path in &explanation.found_paths {
+        println!(
+            "Found path {}",
+            found_path.get_trace(interner, &config.root_dir)
+        );
+    }
+
+    if explanation.found_paths.is_empty() {
+        println!("\nNo path found\n");
+    }
+
+    if !explanation.stops.is_empty() {
+        println!("Propagation stopped at:");
+
+        let mut stops = explanation
+            .stops
+            .iter()
+            .map(|stop| stop.format(codebase, interner, &config.root_dir))
+            .collect::<Vec<_>>();
+        stops.sort();
+        stops.dedup();
+
+        for stop in stops {
+            println!(" - {}", stop);
+        }
+    }
+}
+
+fn dump_graph(
+    sub_matches: &clap::ArgMatches,
+    cwd: &String,
+    analysis_result: &AnalysisResult,
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+) {
+    let dump_graph_file = if let Some(dump_graph_file) = sub_matches.value_of("dump-graph") {
+        get_path_relative_to_cwd(dump_graph_file, cwd)
+    } else {
+        return;
+    };
+
+    let filter = if let Some(function_name) = sub_matches.value_of("dump-graph-function") {
+        Some(GraphDumpFilter::Function(function_name.to_string()))
+    } else {
+        sub_matches
+            .value_of("dump-graph-source")
+            .map(|source_id| GraphDumpFilter::Source(source_id.to_string()))
+    };
+
+    let graph = &analysis_result.program_dataflow_graph;
+
+    let contents = if dump_graph_file.ends_with(".json") {
+        serde_json::to_string_pretty(&graph_dump::get_graph_json(
+            graph, &filter, codebase, interner, cwd,
+        ))
+        .unwrap()
+    } else {
+        graph_dump::get_graph_dot(graph, &filter, codebase, interner, cwd)
+    };
+
+    if let Err(error) = fs::write(&dump_graph_file, contents) {
+        println!(
+            "\nERROR: could not write graph to {}: {}\n",
+            dump_graph_file, error
+        );
+        exit(1);
+    }
+}
+
+/// Prints issues along with their severity. Only issues with error severity
+/// cause a non-zero exit code
+fn print_issues(
+    all_issues: &BTreeMap<String, Vec<&Issue>>,
+    config: &config::Config,
+    interner: &Interner,
+    had_error: &mut bool,
+) {
+    for (file_path, issues) in all_issues {
+        for issue in issues {
+            let severity = config.get_issue_severity(issue, interner);
+
+            if severity == IssueSeverity::Error {
+                *had_error = true;
+            }
+
+            println!("{}", issue.format(file_path, severity));
+        }
+    }
+}
+
 fn write_output_files(
     output_file: String,
+    output_format: Option<String>,
     cwd: &String,
-    analysis_result: &AnalysisResult,
+    all_issues: &BTreeMap<String, Vec<&Issue>>,
+    config: &config::Config,
     interner: &Interner,
 ) {
-    if output_file.ends_with("checkpoint_results.json") {
-        let output_path = if output_file.starts_with('/') {
-            output_file
-        } else {
-            format!("{}/{}", cwd, output_file)
-        };
+    let output_path = get_path_relative_to_cwd(&output_file, cwd);
+
+    if let Some("sarif") = output_format.as_deref() {
+        let mut output_path = fs::File::create(Path::new(&output_path)).unwrap();
+
+        let sarif_json = serde_json::to_string_pretty(&sarif::get_sarif_output(
+            all_issues, config, interner, cwd,
+        ))
+        .unwrap();
+
+        write!(output_path, "{}", sarif_json).unwrap();
+    } else if output_file.ends_with("checkpoint_results.json") {
         let mut output_path = fs::File::create(Path::new(&output_path)).unwrap();
         let mut checkpoint_entries = vec![];
 
-        for (file_path, issues) in analysis_result.get_all_issues(interner, cwd, true) {
+        for (file_path, issues) in all_issues {
             for issue in issues {
-                checkpoint_entries.push(CheckPointEntry::from_issue(issue, &file_path));
+                checkpoint_entries.push(CheckPointEntry::from_issue(
+                    issue,
+                    file_path,
+                    config.get_issue_severity(issue, interner),
+                ));
             }
         }
 
@@ -1256,6 +1793,19 @@ fn update_files(analysis_result: &mut AnalysisResult, root_dir: &String, interne
     }
 }
 
+fn update_config_from_file(config: &mut config::Config, cwd: &String, config_path: &Path) {
+    if config_path.exists() {
+        if let Err(error) = config.update_from_file(cwd, config_path) {
+            println!(
+                "\nERROR: could not load config {}: {}\n",
+                config_path.display(),
+                error
+            );
+            exit(1);
+        }
+    }
+}
+
 fn replace_contents(
     mut file_contents: String,
     replacements: BTreeMap<(u32, u32), Replacement>,
@@ -1273,49 +1823,15 @@ fn replace_contents(
             .extend(insertion.into_iter().rev().map(Replacement::Substitute));
     }
 
-    for ((mut start, mut end), replacements) in replacements.iter().rev() {
-        for replacement in replacements {
-            match replacement {
-                Replacement::Remove => {
-                    file_contents = file_contents[..start as usize].to_string()
-                        + &*file_contents[end as usize..].to_string();
-                }
-                Replacement::TrimPrecedingWhitespace(beg_of_line) => {
-                    let potential_whitespace =
-                        file_contents[(*beg_of_line as usize)..start as usize].to_string();
-                    if potential_whitespace.trim() == "" {
-                        start = *beg_of_line;
-
-                        if beg_of_line > &0
-                            && &file_contents[((*beg_of_line as usize) - 1)..start as usize] == "\n"
-                        {
-                            start -= 1;
-                        }
-                    }
-
-                    if &file_contents[end as usize..end as usize + 1] == "," {
-                        end += 1;
-                    }
-
-                    file_contents = file_contents[..start as usize].to_string()
-                        + &*file_contents[end as usize..].to_string();
-                }
-                Replacement::TrimTrailingWhitespace(end_of_line) => {
-                    let potential_whitespace =
-                        file_contents[end as usize..(*end_of_line as usize)].to_string();
+    for (span, replacements) in replacements.iter().rev() {
+        let mut span = *span;
 
-                    let trimmed = potential_whitespace.trim();
+        for replacement in replacements {
+            let (start, end, text) = replacement.get_edit(&mut span, &file_contents);
 
-                    file_contents = file_contents[..start as usize].to_string()
-                        + trimmed
-                        + &*file_contents[*end_of_line as usize..].to_string();
-                }
-                Replacement::Substitute(string) => {
-                    file_contents = file_contents[..start as usize].to_string()
-                        + string
-                        + &*file_contents[end as usize..].to_string();
-                }
-            }
+            file_contents = file_contents[..start as usize].to_string()
+                + &text
+                + &*file_contents[end as usize..].to_string();
         }
     }
 
@@ -0,0 +1,114 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use hakana_analyzer::config::Config;
+use hakana_reflection_info::code_location::HPos;
+use hakana_reflection_info::issue::{Issue, IssueSeverity};
+use hakana_reflection_info::Interner;
+use serde_json::{json, Value};
+
+pub fn get_sarif_output(
+    all_issues: &BTreeMap<String, Vec<&Issue>>,
+    config: &Config,
+    interner: &Interner,
+    cwd: &str,
+) -> Value {
+    let mut rule_ids = BTreeSet::new();
+    let mut results = vec![];
+
+    for (file_path, issues) in all_issues {
+        for issue in issues {
+            rule_ids.insert(issue.kind.to_string());
+            results.push(get_sarif_result(
+                issue,
+                file_path,
+                config.get_issue_severity(issue, interner),
+                interner,
+                cwd,
+            ));
+        }
+    }
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "Hakana",
+                    "informationUri": "https://github.com/slackhq/hakana",
+                    "rules": rule_ids
+                        .into_iter()
+                        .map(|id| json!({ "id": id }))
+                        .collect::<Vec<_>>(),
+                }
+            },
+            "results": results,
+        }],
+    })
+}
+
+fn get_sarif_result(
+    issue: &Issue,
+    file_path: &str,
+    severity: IssueSeverity,
+    interner: &Interner,
+    cwd: &str,
+) -> Value {
+    let mut result = json!({
+        "ruleId": issue.kind.to_string(),
+        "level": match severity {
+            IssueSeverity::Error => "error",
+            IssueSeverity::Warning => "warning",
+            IssueSeverity::Info => "note",
+        },
+        "message": { "text": issue.description },
+        "locations": [{
+            "physicalLocation": get_physical_location(file_path, &issue.pos),
+        }],
+    });
+
+    if let Some(taint_trace) = &issue.taint_trace {
+        let thread_flow_locations = taint_trace
+            .iter()
+            .filter_map(|step| {
+                step.pos.as_ref().map(|pos| {
+                    json!({
+                        "location": {
+                            "physicalLocation": get_physical_location(
+                                &pos.file_path.get_relative_path(interner, cwd),
+                                pos,
+                            ),
+                            "message": {
+                                "text": if let Some(path) = &step.path {
+                                    format!("{} ({})", step.label, path)
+                                } else {
+                                    step.label.clone()
+                                },
+                            },
+                        }
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+
+        result["codeFlows"] = json!([{
+            "threadFlows": [{
+                "locations": thread_flow_locations,
+            }],
+        }]);
+    }
+
+    result
+}
+
+fn get_physical_location(file_path: &str, pos: &HPos) -> Value {
+    json!({
+        "artifactLocation": { "uri": file_path },
+        "region": {
+            "startLine": pos.start_line,
+            "startColumn": pos.start_column,
+            "endLine": pos.end_line,
+            "endColumn": pos.end_column,
+        },
+    })
+}
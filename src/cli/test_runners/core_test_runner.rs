@@ -1,12 +1,162 @@
+use hakana_analyzer::custom_hook::{
+    AfterCodebaseAnalysisData, AfterDefAnalysisData, CodebaseAnalysisData, CustomHook,
+    FunctionLikeReturnTypeData, InternalHook,
+};
+use hakana_analyzer::function_analysis_data::FunctionAnalysisData;
+use hakana_analyzer::plugin_hook::PluginHook;
+use hakana_reflection_info::codebase_info::symbols::SymbolKind;
+use hakana_reflection_info::functionlike_identifier::FunctionLikeIdentifier;
+use hakana_reflection_info::issue::{Issue, IssueKind};
+use hakana_reflection_info::t_atomic::TAtomic;
+use hakana_reflection_info::t_union::TUnion;
+
 use super::test_runner::HooksProvider;
 
 pub struct CoreHooksProvider {}
 
 impl HooksProvider for CoreHooksProvider {
-    fn get_hooks_for_test(
-        &self,
-        _: &str,
-    ) -> Vec<Box<dyn hakana_analyzer::custom_hook::CustomHook>> {
+    fn get_hooks_for_test(&self, dir: &str) -> Vec<Box<dyn CustomHook>> {
+        if dir.contains("/ReturnTypeProvider/") {
+            return vec![Box::new(ConfigValueReturnTypeProvider {})];
+        }
+
+        if dir.contains("/InternalError/") {
+            return vec![Box::new(PanickingHook {})];
+        }
+
+        if dir.contains("/CodebaseAnalysis/") {
+            return vec![Box::new(SingleImplementationHook {})];
+        }
+
+        if dir.contains("/Plugin/") {
+            return vec![Box::new(load_test_plugin())];
+        }
+
         vec![]
     }
 }
+
+/// Loads the plugin built from `src/test_plugin`, which is put next to the hakana binary
+fn load_test_plugin() -> PluginHook {
+    let plugin_path = std::env::current_exe().unwrap().with_file_name(format!(
+        "{}hakana_test_plugin{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    ));
+
+    PluginHook::load(plugin_path.to_str().unwrap()).unwrap_or_else(|error| {
+        panic!(
+            "could not load {} ({}), build it with cargo build -p hakana-test-plugin",
+            plugin_path.display(),
+            error
+        )
+    })
+}
+
+/// Infers `int` for `get_config_value('timeout')` and `ConfigSource::get('timeout')` calls,
+/// including calls on subclasses of `ConfigSource`
+#[derive(Debug)]
+struct ConfigValueReturnTypeProvider {}
+
+impl InternalHook for ConfigValueReturnTypeProvider {
+    fn get_functionlike_return_type(
+        &self,
+        return_type_data: FunctionLikeReturnTypeData,
+    ) -> Option<TUnion> {
+        let interner = return_type_data.interner;
+
+        let is_config_getter = match return_type_data.declaring_functionlike_id {
+            FunctionLikeIdentifier::Function(function_name) => {
+                interner.lookup(function_name) == "get_config_value"
+            }
+            FunctionLikeIdentifier::Method(classlike_name, method_name) => {
+                interner.lookup(classlike_name) == "ConfigSource"
+                    && interner.lookup(method_name) == "get"
+            }
+        };
+
+        if !is_config_getter {
+            return None;
+        }
+
+        let key = return_type_data
+            .arg_types
+            .first()
+            .cloned()
+            .flatten()?
+            .get_single_literal_string_value()?;
+
+        if key == "timeout" {
+            Some(TUnion::new(vec![TAtomic::TInt]))
+        } else {
+            None
+        }
+    }
+}
+
+impl CustomHook for ConfigValueReturnTypeProvider {}
+
+/// Panics after analyzing any function named `panics_during_analysis`
+#[derive(Debug)]
+struct PanickingHook {}
+
+impl InternalHook for PanickingHook {
+    fn after_def_analysis(
+        &self,
+        _: &mut FunctionAnalysisData,
+        after_def_analysis_data: AfterDefAnalysisData,
+    ) {
+        if let Some(fun_def) = after_def_analysis_data.def.as_fun() {
+            if fun_def.name.1 == "panics_during_analysis" {
+                panic!("test panic");
+            }
+        }
+    }
+}
+
+impl CustomHook for PanickingHook {}
+
+/// Reports interfaces with only one implementation, which needs the whole codebase
+#[derive(Debug)]
+struct SingleImplementationHook {}
+
+impl InternalHook for SingleImplementationHook {
+    fn after_codebase_analysis(
+        &self,
+        codebase_analysis_data: &mut CodebaseAnalysisData,
+        after_codebase_analysis_data: AfterCodebaseAnalysisData,
+    ) {
+        let codebase = after_codebase_analysis_data.codebase;
+        let interner = after_codebase_analysis_data.interner;
+
+        for (classlike_name, classlike_info) in &codebase.classlike_infos {
+            if !classlike_info.user_defined || !matches!(classlike_info.kind, SymbolKind::Interface)
+            {
+                continue;
+            }
+
+            let descendants = codebase.get_all_descendants(classlike_name);
+
+            if descendants.len() != 1 {
+                continue;
+            }
+
+            codebase_analysis_data.add_issue(Issue::new(
+                IssueKind::CustomIssue("SingleImplementationInterface".to_string()),
+                format!(
+                    "{} only has one implementation, {}",
+                    interner.lookup(classlike_name),
+                    interner.lookup(descendants.iter().next().unwrap())
+                ),
+                classlike_info.name_location,
+                &None,
+            ));
+        }
+    }
+
+    fn get_custom_issue_names(&self) -> Vec<&str> {
+        vec!["SingleImplementationInterface"]
+    }
+}
+
+impl CustomHook for SingleImplementationHook {}
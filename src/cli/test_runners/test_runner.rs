@@ -6,12 +6,14 @@ use hakana_reflection_info::code_location::FilePath;
 use hakana_reflection_info::data_flow::graph::GraphKind;
 use hakana_reflection_info::data_flow::graph::WholeProgramKind;
 use hakana_reflection_info::issue::IssueKind;
+use hakana_workhorse::file::FileStatus;
 use hakana_workhorse::wasm::get_single_file_codebase;
 use hakana_workhorse::SuccessfulScanData;
 use rand::seq::SliceRandom;
 use rand::SeedableRng;
 use rustc_hash::FxHashMap;
 use rustc_hash::FxHashSet;
+use tower_lsp::lsp_types::{GotoDefinitionResponse, Hover, HoverContents, Position};
 
 use std::env;
 use std::fs;
@@ -128,6 +130,15 @@ impl TestRunner {
 
     fn get_config_for_test(&self, dir: &str) -> config::Config {
         let mut analysis_config = config::Config::new(dir.to_string(), FxHashSet::default());
+
+        let config_path = format!("{}/hakana.json", dir);
+
+        if Path::new(&config_path).exists() {
+            analysis_config
+                .update_from_file(&dir.to_string(), Path::new(&config_path))
+                .unwrap();
+        }
+
         analysis_config.find_unused_expressions = dir.contains("/unused/")
             || dir.contains("UnusedAssignment")
             || dir.contains("UnusedParameter")
@@ -142,7 +153,14 @@ impl TestRunner {
             GraphKind::FunctionBody
         };
 
-        analysis_config.hooks = self.0.get_hooks_for_test(dir);
+        for hook in self.0.get_hooks_for_test(dir) {
+            analysis_config.all_custom_issues.extend(
+                hook.get_custom_issue_names()
+                    .into_iter()
+                    .map(|issue_name| issue_name.to_string()),
+            );
+            analysis_config.hooks.push(Arc::from(hook));
+        }
 
         let mut dir_parts = dir.split('/').collect::<Vec<_>>();
 
@@ -215,6 +233,28 @@ impl TestRunner {
             );
         }
 
+        if dir.contains("/baseline/") {
+            return self.run_baseline_test(
+                dir,
+                logger,
+                had_error,
+                test_diagnostics,
+                build_checksum,
+                total_time_in_analysis,
+            );
+        }
+
+        if dir.contains("/language-server/") {
+            return self.run_language_server_test(
+                dir,
+                logger,
+                had_error,
+                test_diagnostics,
+                build_checksum,
+                total_time_in_analysis,
+            );
+        }
+
         let cwd = env::current_dir().unwrap().to_str().unwrap().to_string();
 
         let analysis_config = self.get_config_for_test(&dir);
@@ -298,11 +338,35 @@ impl TestRunner {
                     *total_time_in_analysis += analysis_result.time_in_analysis;
 
                     let mut output = vec![];
-                    for (file_path, issues) in
-                        analysis_result.get_all_issues(&run_data.interner, &dir, true)
-                    {
-                        for issue in issues {
-                            output.push(issue.format(&file_path));
+
+                    if dir.contains("/Sarif/") {
+                        output.push(
+                            serde_json::to_string_pretty(&crate::sarif::get_sarif_output(
+                                &analysis_result.get_all_issues(&run_data.interner, &dir, true),
+                                &config,
+                                &run_data.interner,
+                                &dir,
+                            ))
+                            .unwrap(),
+                        );
+                    } else if dir.contains("/graphDump/") {
+                        output.push(crate::graph_dump::get_graph_dot(
+                            &analysis_result.program_dataflow_graph,
+                            &None,
+                            &run_data.codebase,
+                            &run_data.interner,
+                            &dir,
+                        ));
+                    } else {
+                        for (file_path, issues) in
+                            analysis_result.get_all_issues(&run_data.interner, &dir, true)
+                        {
+                            for issue in issues {
+                                output.push(issue.format(
+                                    &file_path,
+                                    config.get_issue_severity(issue, &run_data.interner),
+                                ));
+                            }
                         }
                     }
 
@@ -392,6 +456,19 @@ impl TestRunner {
         let mut previous_scan_data = None;
         let mut previous_analysis_result = None;
 
+        // like `analyze --changed-files`, these start each step from the previous step's cache
+        // and only pass the files that changed
+        let changed_files_cache_dir = if dir.contains("/changed-files/") {
+            let cache_dir = dir.clone() + "/.hakana_cache";
+            fs::remove_dir_all(&cache_dir).ok();
+            fs::create_dir(&cache_dir).unwrap();
+            Some(cache_dir)
+        } else {
+            None
+        };
+
+        let mut previous_folder: Option<String> = None;
+
         let mut config = self.get_config_for_test(&workdir_base);
         config.ast_diff = true;
         config.find_unused_definitions = true;
@@ -405,6 +482,14 @@ impl TestRunner {
         for folder in folders {
             copy_recursively(folder.clone(), workdir_base.clone()).unwrap();
 
+            let file_changes = if changed_files_cache_dir.is_some() {
+                previous_folder.as_ref().map(|previous_folder| {
+                    get_changed_files(previous_folder, &folder, &workdir_base)
+                })
+            } else {
+                None
+            };
+
             let run_result = hakana_workhorse::scan_and_analyze(
                 stub_dirs.clone(),
                 None,
@@ -413,17 +498,27 @@ impl TestRunner {
                     format!("{}/third-party/xhp-lib/src", cwd),
                 ])),
                 config.clone(),
-                None,
+                changed_files_cache_dir.as_ref(),
                 1,
                 logger.clone(),
                 build_checksum,
-                previous_scan_data,
-                previous_analysis_result,
-                None,
+                if changed_files_cache_dir.is_some() {
+                    None
+                } else {
+                    previous_scan_data
+                },
+                if changed_files_cache_dir.is_some() {
+                    None
+                } else {
+                    previous_analysis_result
+                },
+                file_changes,
             );
 
             fs::remove_dir_all(&workdir_base).unwrap();
 
+            previous_folder = Some(folder);
+
             match run_result {
                 Ok(run_result) => {
                     previous_scan_data = Some(run_result.1);
@@ -445,7 +540,10 @@ impl TestRunner {
             analysis_result.get_all_issues(&run_data.interner, &workdir_base, true)
         {
             for issue in issues {
-                output.push(issue.format(&file_path));
+                output.push(issue.format(
+                    &file_path,
+                    config.get_issue_severity(issue, &run_data.interner),
+                ));
             }
         }
 
@@ -492,6 +590,241 @@ impl TestRunner {
             ("F".to_string(), Some(run_data), Some(analysis_result))
         }
     }
+
+    fn run_baseline_test(
+        &self,
+        dir: String,
+        logger: Arc<Logger>,
+        had_error: &mut bool,
+        test_diagnostics: &mut Vec<(String, String)>,
+        build_checksum: &str,
+        total_time_in_analysis: &mut Duration,
+    ) -> (String, Option<SuccessfulScanData>, Option<AnalysisResult>) {
+        let cwd = env::current_dir().unwrap().to_str().unwrap().to_string();
+
+        logger.log_debug_sync(&format!("running test {}", dir));
+
+        // the baseline is set from the code in a, and then used when analyzing the code in b
+        let mut baseline = None;
+        let mut test_output = vec![];
+
+        for folder in [dir.clone() + "/a", dir.clone() + "/b"] {
+            let config = Arc::new(self.get_config_for_test(&folder));
+
+            let result = hakana_workhorse::scan_and_analyze(
+                vec![cwd.clone() + "/tests/stubs"],
+                None,
+                Some(FxHashSet::from_iter(["tests/stubs/stubs.hack".to_string()])),
+                config.clone(),
+                None,
+                1,
+                logger.clone(),
+                build_checksum,
+                None,
+                None,
+                None,
+            );
+
+            let (analysis_result, run_data) = match result {
+                Ok(result) => result,
+                Err(error) => {
+                    *had_error = true;
+                    test_diagnostics.push((dir, error.to_string()));
+                    return ("F".to_string(), None, None);
+                }
+            };
+
+            *total_time_in_analysis += analysis_result.time_in_analysis;
+
+            let all_issues = analysis_result.get_all_issues(&run_data.interner, &folder, true);
+
+            let previous_baseline = if let Some(previous_baseline) = baseline.take() {
+                previous_baseline
+            } else {
+                match crate::baseline::generate_baseline(&all_issues, &folder) {
+                    Ok(generated_baseline) => baseline = Some(generated_baseline),
+                    Err(error) => {
+                        *had_error = true;
+                        test_diagnostics.push((dir, error));
+                        return ("F".to_string(), None, None);
+                    }
+                }
+                continue;
+            };
+
+            let (filtered_issues, unmatched_entries) =
+                match crate::baseline::filter_issues_using_baseline(
+                    all_issues,
+                    previous_baseline,
+                    &folder,
+                ) {
+                    Ok(result) => result,
+                    Err(error) => {
+                        *had_error = true;
+                        test_diagnostics.push((dir, error));
+                        return ("F".to_string(), None, None);
+                    }
+                };
+
+            for (file_path, issues) in filtered_issues {
+                for issue in issues {
+                    test_output.push(issue.format(
+                        &file_path,
+                        config.get_issue_severity(issue, &run_data.interner),
+                    ));
+                }
+            }
+
+            for entry in unmatched_entries {
+                test_output.push(format!(
+                    "Unmatched baseline entry: {} - {} (x{})\n",
+                    entry.issue_kind, entry.file_path, entry.count
+                ));
+            }
+        }
+
+        let expected_output = fs::read_to_string(dir.clone() + "/output.txt")
+            .unwrap()
+            .trim()
+            .to_string();
+
+        if expected_output == test_output.join("").trim() {
+            (".".to_string(), None, None)
+        } else {
+            test_diagnostics.push((
+                dir,
+                format!("- {}\n+ {}", expected_output, test_output.join("+ ")),
+            ));
+            ("F".to_string(), None, None)
+        }
+    }
+
+    fn run_language_server_test(
+        &self,
+        dir: String,
+        logger: Arc<Logger>,
+        had_error: &mut bool,
+        test_diagnostics: &mut Vec<(String, String)>,
+        build_checksum: &str,
+        total_time_in_analysis: &mut Duration,
+    ) -> (String, Option<SuccessfulScanData>, Option<AnalysisResult>) {
+        let cwd = env::current_dir().unwrap().to_str().unwrap().to_string();
+
+        logger.log_debug_sync(&format!("running test {}", dir));
+
+        // the language server only deals in absolute paths, since it returns file URLs
+        let absolute_dir = format!("{}/{}", cwd, dir);
+
+        let mut config = self.get_config_for_test(&absolute_dir);
+        config.collect_expr_types = true;
+
+        let result = hakana_workhorse::scan_and_analyze(
+            vec![cwd.clone() + "/tests/stubs"],
+            None,
+            Some(FxHashSet::from_iter(["tests/stubs/stubs.hack".to_string()])),
+            Arc::new(config),
+            None,
+            1,
+            logger,
+            build_checksum,
+            None,
+            None,
+            None,
+        );
+
+        let (analysis_result, run_data) = match result {
+            Ok(result) => result,
+            Err(error) => {
+                *had_error = true;
+                test_diagnostics.push((dir, error.to_string()));
+                return ("F".to_string(), None, None);
+            }
+        };
+
+        *total_time_in_analysis += analysis_result.time_in_analysis;
+
+        let input_file = format!("{}/input.hack", absolute_dir);
+        let input_contents = fs::read_to_string(&input_file).unwrap();
+        let input_file_path = FilePath(run_data.interner.get(&input_file).unwrap());
+        let position = get_marked_position(&input_contents);
+
+        let test_output = if dir.contains("/hover/") {
+            if let Some(Hover {
+                contents: HoverContents::Markup(markup),
+                ..
+            }) = hakana_language_server::hover::get_hover(
+                &run_data,
+                &analysis_result,
+                input_file_path,
+                &input_contents,
+                position,
+            ) {
+                markup.value
+            } else {
+                "".to_string()
+            }
+        } else if let Some(GotoDefinitionResponse::Scalar(location)) =
+            hakana_language_server::definition::get_definition(
+                &run_data,
+                &analysis_result,
+                input_file_path,
+                &input_contents,
+                position,
+            )
+        {
+            format!(
+                "{}:{}:{}",
+                location
+                    .uri
+                    .path()
+                    .strip_prefix(&(absolute_dir.clone() + "/"))
+                    .unwrap_or(location.uri.path()),
+                location.range.start.line + 1,
+                location.range.start.character + 1
+            )
+        } else {
+            "".to_string()
+        };
+
+        let expected_output = fs::read_to_string(dir.clone() + "/output.txt")
+            .unwrap()
+            .trim()
+            .to_string();
+
+        if expected_output == test_output {
+            (".".to_string(), None, None)
+        } else {
+            test_diagnostics.push((dir, format!("- {}\n+ {}", expected_output, test_output)));
+            ("F".to_string(), None, None)
+        }
+    }
+}
+
+/// Finds the position marked by a `^` in a comment, which points at the line above it
+fn get_marked_position(file_contents: &str) -> Position {
+    let lines = file_contents.lines().collect::<Vec<_>>();
+
+    for (i, line) in lines.iter().enumerate().skip(1) {
+        if !line.trim_start().starts_with("//") {
+            continue;
+        }
+
+        if let Some(marker_offset) = line.find('^') {
+            let marker_column = line[..marker_offset].chars().count();
+
+            // LSP columns count UTF-16 code units
+            return Position {
+                line: i as u32 - 1,
+                character: lines[i - 1]
+                    .chars()
+                    .take(marker_column)
+                    .map(char::len_utf16)
+                    .sum::<usize>() as u32,
+            };
+        }
+    }
+
+    panic!("no position marker in test file");
 }
 
 fn copy_recursively(source: impl AsRef<Path>, destination: impl AsRef<Path>) -> io::Result<()> {
@@ -508,6 +841,54 @@ fn copy_recursively(source: impl AsRef<Path>, destination: impl AsRef<Path>) ->
     Ok(())
 }
 
+/// Gets the files that differ between two steps of a diff test, keyed by their path in the workdir
+fn get_changed_files(
+    previous_folder: &str,
+    folder: &str,
+    workdir_base: &str,
+) -> FxHashMap<String, FileStatus> {
+    let get_contents = |folder: &str| {
+        WalkDir::new(folder)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| {
+                (
+                    e.path()
+                        .strip_prefix(folder)
+                        .unwrap()
+                        .to_str()
+                        .unwrap()
+                        .to_string(),
+                    fs::read(e.path()).unwrap(),
+                )
+            })
+            .collect::<FxHashMap<_, _>>()
+    };
+
+    let previous_contents = get_contents(previous_folder);
+    let contents = get_contents(folder);
+
+    let mut changed_files = FxHashMap::default();
+
+    for (path, file_contents) in &contents {
+        if previous_contents.get(path) != Some(file_contents) {
+            changed_files.insert(
+                format!("{}/{}", workdir_base, path),
+                FileStatus::Modified(0, 0),
+            );
+        }
+    }
+
+    for path in previous_contents.keys() {
+        if !contents.contains_key(path) {
+            changed_files.insert(format!("{}/{}", workdir_base, path), FileStatus::Deleted);
+        }
+    }
+
+    changed_files
+}
+
 fn get_all_test_folders(test_or_test_dir: String) -> Vec<String> {
     let mut test_folders = vec![];
     if Path::new(&(test_or_test_dir.clone() + "/input.hack")).exists()
@@ -528,7 +909,8 @@ fn get_all_test_folders(test_or_test_dir: String) -> Vec<String> {
             if metadata.is_dir() {
                 if let Some(path) = path.to_str() {
                     if (Path::new(&(path.to_owned() + "/input.hack")).exists()
-                        && !path.contains("/diff/"))
+                        && !path.contains("/diff/")
+                        && !path.contains("/baseline/"))
                         || Path::new(&(path.to_owned() + "/output.txt")).exists()
                     {
                         test_folders.push(path.to_owned().to_string());
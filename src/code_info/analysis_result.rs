@@ -5,10 +5,14 @@ use serde::Serialize;
 
 use crate::{
     code_location::FilePath,
-    data_flow::graph::{DataFlowGraph, GraphKind},
-    issue::{Issue, IssueKind},
+    data_flow::{
+        graph::{DataFlowGraph, GraphKind},
+        taint_summary::TaintSummary,
+    },
+    issue::{Issue, IssueKind, IssueSeverity},
     symbol_references::SymbolReferences,
-    Interner,
+    t_union::TUnion,
+    Interner, StrId,
 };
 
 #[derive(Clone, Debug)]
@@ -19,6 +23,44 @@ pub enum Replacement {
     Substitute(String),
 }
 
+impl Replacement {
+    /// Resolves a replacement of the given span into the span it actually replaces, along with
+    /// the text that should replace it. Trimming preceding whitespace widens the span itself, so
+    /// any further replacements of the same span apply to the widened one.
+    pub fn get_edit(&self, span: &mut (u32, u32), file_contents: &str) -> (u32, u32, String) {
+        let (start, end) = *span;
+
+        match self {
+            Replacement::Remove => (start, end, "".to_string()),
+            Replacement::TrimPrecedingWhitespace(beg_of_line) => {
+                let potential_whitespace = &file_contents[(*beg_of_line as usize)..start as usize];
+
+                if potential_whitespace.trim() == "" {
+                    span.0 = *beg_of_line;
+
+                    if *beg_of_line > 0
+                        && &file_contents[((*beg_of_line as usize) - 1)..span.0 as usize] == "\n"
+                    {
+                        span.0 -= 1;
+                    }
+                }
+
+                if file_contents.get(end as usize..end as usize + 1) == Some(",") {
+                    span.1 += 1;
+                }
+
+                (span.0, span.1, "".to_string())
+            }
+            Replacement::TrimTrailingWhitespace(end_of_line) => {
+                let potential_whitespace = &file_contents[end as usize..(*end_of_line as usize)];
+
+                (start, *end_of_line, potential_whitespace.trim().to_string())
+            }
+            Replacement::Substitute(string) => (start, end, string.clone()),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct AnalysisResult {
     pub emitted_issues: FxHashMap<FilePath, Vec<Issue>>,
@@ -30,6 +72,11 @@ pub struct AnalysisResult {
     pub symbol_references: SymbolReferences,
     pub issue_counts: FxHashMap<IssueKind, usize>,
     pub time_in_analysis: Duration,
+    pub expr_types: FxHashMap<FilePath, FxHashMap<(u32, u32), TUnion>>,
+    pub symbol_positions: FxHashMap<FilePath, FxHashMap<(u32, u32), (StrId, StrId)>>,
+    pub taint_summaries: FxHashMap<FilePath, FxHashMap<(StrId, StrId), TaintSummary>>,
+    /// Files that were analyzed in this run, as opposed to having their results loaded from cache
+    pub analyzed_files: FxHashSet<FilePath>,
 }
 
 impl AnalysisResult {
@@ -47,6 +94,10 @@ impl AnalysisResult {
             issue_counts: FxHashMap::default(),
             symbol_references,
             time_in_analysis: Duration::default(),
+            expr_types: FxHashMap::default(),
+            symbol_positions: FxHashMap::default(),
+            taint_summaries: FxHashMap::default(),
+            analyzed_files: FxHashSet::default(),
         }
     }
 
@@ -68,6 +119,25 @@ impl AnalysisResult {
         for (kind, count) in other.issue_counts {
             *self.issue_counts.entry(kind).or_insert(0) += count;
         }
+        for (file_path, expr_types) in other.expr_types {
+            self.expr_types
+                .entry(file_path)
+                .or_default()
+                .extend(expr_types);
+        }
+        for (file_path, symbol_positions) in other.symbol_positions {
+            self.symbol_positions
+                .entry(file_path)
+                .or_default()
+                .extend(symbol_positions);
+        }
+        for (file_path, taint_summaries) in other.taint_summaries {
+            self.taint_summaries
+                .entry(file_path)
+                .or_default()
+                .extend(taint_summaries);
+        }
+        self.analyzed_files.extend(other.analyzed_files);
     }
 
     pub fn get_all_issues(
@@ -127,10 +197,15 @@ pub struct CheckPointEntry {
 }
 
 impl CheckPointEntry {
-    pub fn from_issue(issue: &Issue, path: &str) -> Self {
+    pub fn from_issue(issue: &Issue, path: &str, severity: IssueSeverity) -> Self {
         Self {
             output: issue.description.clone(),
-            level: "failure".to_string(),
+            level: match severity {
+                IssueSeverity::Error => "failure",
+                IssueSeverity::Warning => "warning",
+                IssueSeverity::Info => "notice",
+            }
+            .to_string(),
             filename: path.to_string(),
             line: issue.pos.start_line,
             case: issue.kind.to_string(),
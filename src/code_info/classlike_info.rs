@@ -27,6 +27,15 @@ pub enum ClassConstantType {
     Concrete(TUnion),
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UninitializedPropertyRead {
+    pub property_name: StrId,
+    pub pos: HPos,
+
+    /// Whether `parent::__construct()` has been called on every path to the read
+    pub after_parent_constructor: bool,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ClassLikeInfo {
     pub constants: IndexMap<StrId, ConstantInfo>,
@@ -40,7 +49,9 @@ pub struct ClassLikeInfo {
 
     pub is_stubbed: bool,
 
-    pub is_deprecated: bool,
+    pub deprecated: Option<String>,
+
+    pub is_internal: bool,
 
     pub internal_to: Option<String>,
 
@@ -177,8 +188,22 @@ pub struct ClassLikeInfo {
 
     pub template_type_uses_count: FxHashMap<String, u32>,
 
+    /**
+     * Properties that this class's own constructor assigns on every path
+     */
     pub initialized_properties: FxHashSet<StrId>,
 
+    /**
+     * Whether this class's own constructor calls parent::__construct() on every path
+     */
+    pub constructor_calls_parent: bool,
+
+    /**
+     * Reads of `$this->prop` in this class's own constructor that may happen before
+     * the property has been assigned
+     */
+    pub uninitialized_property_reads: Vec<UninitializedPropertyRead>,
+
     pub invalid_dependencies: Vec<StrId>,
 
     /**
@@ -225,7 +250,7 @@ impl ClassLikeInfo {
             constants: IndexMap::default(),
             is_populated: false,
             is_stubbed: false,
-            is_deprecated: false,
+            deprecated: None,
             is_abstract: false,
             is_final: false,
             kind: SymbolKind::Class,
@@ -255,6 +280,9 @@ impl ClassLikeInfo {
             hash: None,
             inheritable_property_ids: FxHashMap::default(),
             initialized_properties: FxHashSet::default(),
+            constructor_calls_parent: false,
+            uninitialized_property_reads: vec![],
+            is_internal: false,
             internal_to: None,
             invalid_dependencies: Vec::new(),
             def_location,
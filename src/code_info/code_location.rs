@@ -60,4 +60,18 @@ impl HPos {
             insertion_start: stmt_start,
         }
     }
+
+    /// The start of a file, for issues that don't point at any particular code in it
+    pub fn file_start(file_path: FilePath) -> HPos {
+        HPos {
+            file_path,
+            start_offset: 0,
+            end_offset: 0,
+            start_line: 1,
+            end_line: 1,
+            start_column: 1,
+            end_column: 1,
+            insertion_start: None,
+        }
+    }
 }
@@ -11,7 +11,10 @@ use crate::t_atomic::TAtomic;
 use crate::t_union::TUnion;
 use crate::type_definition_info::TypeDefinitionInfo;
 use crate::StrId;
-use crate::{class_constant_info::ConstantInfo, code_location::FilePath};
+use crate::{
+    class_constant_info::ConstantInfo,
+    code_location::{FilePath, HPos},
+};
 use rustc_hash::{FxHashMap, FxHashSet};
 use serde::{Deserialize, Serialize};
 
@@ -410,6 +413,44 @@ impl CodebaseInfo {
         self.get_method(&self.get_declaring_method_id(method_id))
     }
 
+    /// Gets the location of the name of a symbol or class member, falling back to
+    /// its definition location when the name location is unknown
+    pub fn get_symbol_pos(&self, symbol: &(StrId, StrId)) -> Option<HPos> {
+        if let Some(functionlike_info) = self.functionlike_infos.get(symbol) {
+            return Some(
+                functionlike_info
+                    .name_location
+                    .unwrap_or(functionlike_info.def_location),
+            );
+        }
+
+        if symbol.1.is_empty() {
+            if let Some(classlike_info) = self.classlike_infos.get(&symbol.0) {
+                return Some(classlike_info.name_location);
+            }
+
+            if let Some(type_definition_info) = self.type_definitions.get(&symbol.0) {
+                return Some(type_definition_info.location);
+            }
+
+            return self
+                .constant_infos
+                .get(&symbol.0)
+                .map(|constant_info| constant_info.pos);
+        }
+
+        let classlike_info = self.classlike_infos.get(&symbol.0)?;
+
+        if let Some(property_info) = classlike_info.properties.get(&symbol.1) {
+            return property_info.pos;
+        }
+
+        classlike_info
+            .constants
+            .get(&symbol.1)
+            .map(|constant_info| constant_info.pos)
+    }
+
     pub fn extend(&mut self, other: CodebaseInfo) {
         self.classlike_infos.extend(other.classlike_infos);
         self.functionlike_infos.extend(other.functionlike_infos);
@@ -423,4 +464,41 @@ impl CodebaseInfo {
         self.const_files.extend(other.const_files);
         self.files.extend(other.files);
     }
+
+    /// Removes everything that a file has added to the codebase, even if it wasn't fully scanned
+    pub fn remove_file_storage(&mut self, file_path: FilePath) {
+        let type_names = self
+            .symbols
+            .classlike_files
+            .iter()
+            .filter(|(_, classlike_file)| **classlike_file == file_path)
+            .map(|(name, _)| *name)
+            .chain(
+                self.classlike_infos
+                    .iter()
+                    .filter(|(_, storage)| storage.def_location.file_path == file_path)
+                    .map(|(name, _)| *name),
+            )
+            .chain(
+                self.type_definitions
+                    .iter()
+                    .filter(|(_, storage)| storage.location.file_path == file_path)
+                    .map(|(name, _)| *name),
+            )
+            .collect::<FxHashSet<_>>();
+
+        for type_name in type_names {
+            self.symbols.all.remove(&type_name);
+            self.symbols.classlike_files.remove(&type_name);
+            self.classlike_infos.remove(&type_name);
+            self.type_definitions.remove(&type_name);
+        }
+
+        self.functionlike_infos
+            .retain(|_, storage| storage.def_location.file_path != file_path);
+        self.constant_infos
+            .retain(|_, storage| storage.pos.file_path != file_path);
+        self.closures_in_files.remove(&file_path);
+        self.files.remove(&file_path);
+    }
 }
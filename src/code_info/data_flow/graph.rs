@@ -1,10 +1,12 @@
 use super::{
     node::{DataFlowNode, DataFlowNodeKind},
     path::{ArrayDataKind, DataFlowPath, PathKind},
+    taint_summary::TaintSummary,
 };
-use crate::taint::SinkType;
+use crate::{code_location::HPos, taint::SinkType};
 use oxidized::ast_defs::Pos;
 use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WholeProgramKind {
@@ -18,6 +20,38 @@ pub enum GraphKind {
     WholeProgram(WholeProgramKind),
 }
 
+/// Why taint analysis stopped data from flowing out of a node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TaintCutoffReason {
+    /// The value has a type (like `int`) that can't carry taints
+    NonTaintableType(String),
+    /// The value is passed to a function with an `IgnorePath` attribute
+    IgnorePath,
+    /// The code only runs when a function with an `IgnorePathIfTrue` attribute returns true
+    IgnorePathIfTrue,
+    /// The value is a shape with per-field `ShapeSource` taints
+    ShapeSource,
+}
+
+impl std::fmt::Display for TaintCutoffReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TaintCutoffReason::NonTaintableType(type_id) => {
+                write!(f, "values of type {} can't carry taints", type_id)
+            }
+            TaintCutoffReason::IgnorePath => write!(f, "IgnorePath attribute"),
+            TaintCutoffReason::IgnorePathIfTrue => write!(f, "IgnorePathIfTrue attribute"),
+            TaintCutoffReason::ShapeSource => write!(f, "ShapeSource attribute"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaintCutoff {
+    pub reason: TaintCutoffReason,
+    pub pos: HPos,
+}
+
 #[derive(Debug, Clone)]
 pub struct DataFlowGraph {
     pub kind: GraphKind,
@@ -28,6 +62,9 @@ pub struct DataFlowGraph {
     pub sinks: FxHashMap<String, DataFlowNode>,
     pub mixed_source_counts: FxHashMap<String, FxHashSet<String>>,
     pub specializations: FxHashMap<String, FxHashSet<String>>,
+    /// Places where taints were prevented from flowing out of a node, recorded
+    /// when explaining taint paths
+    pub cutoffs: FxHashMap<String, Vec<TaintCutoff>>,
     specialized_calls: FxHashMap<String, FxHashSet<String>>,
 }
 
@@ -42,6 +79,7 @@ impl DataFlowGraph {
             sinks: FxHashMap::default(),
             mixed_source_counts: FxHashMap::default(),
             specializations: FxHashMap::default(),
+            cutoffs: FxHashMap::default(),
             specialized_calls: FxHashMap::default(),
         }
     }
@@ -125,6 +163,15 @@ impl DataFlowGraph {
             );
     }
 
+    pub fn add_cutoff(&mut self, node_id: &str, reason: TaintCutoffReason, pos: HPos) {
+        let cutoffs = self.cutoffs.entry(node_id.to_string()).or_default();
+        let cutoff = TaintCutoff { reason, pos };
+
+        if !cutoffs.contains(&cutoff) {
+            cutoffs.push(cutoff);
+        }
+    }
+
     pub fn add_graph(&mut self, graph: DataFlowGraph) {
         if self.kind != graph.kind {
             panic!("Graph kinds are different");
@@ -152,6 +199,9 @@ impl DataFlowGraph {
                     .or_default()
                     .extend(specializations);
             }
+            for (key, cutoffs) in graph.cutoffs {
+                self.cutoffs.entry(key).or_default().extend(cutoffs);
+            }
         }
 
         self.vertices.extend(graph.vertices);
@@ -159,6 +209,31 @@ impl DataFlowGraph {
         self.sinks.extend(graph.sinks);
     }
 
+    pub fn add_taint_summary(&mut self, summary: &TaintSummary) {
+        for node in summary
+            .vertices
+            .values()
+            .chain(summary.sources.values())
+            .chain(summary.sinks.values())
+        {
+            self.add_node(node.clone());
+        }
+
+        for (from_id, to) in &summary.forward_edges {
+            self.forward_edges
+                .entry(from_id.clone())
+                .or_default()
+                .extend(to.iter().map(|(to_id, path)| (to_id.clone(), path.clone())));
+        }
+
+        for (node_id, cutoffs) in &summary.cutoffs {
+            self.cutoffs
+                .entry(node_id.clone())
+                .or_default()
+                .extend(cutoffs.iter().cloned());
+        }
+    }
+
     /// Returns a set of nodes that are origin nodes for the given assignment
     pub fn get_origin_nodes(
         &self,
@@ -1,4 +1,5 @@
 pub mod graph;
 pub mod node;
 pub mod path;
+pub mod taint_summary;
 pub mod tainted_node;
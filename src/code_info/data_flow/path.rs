@@ -112,7 +112,7 @@ impl std::fmt::Display for PathKind {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DataFlowPath {
     pub kind: PathKind,
     pub added_taints: Option<FxHashSet<SinkType>>,
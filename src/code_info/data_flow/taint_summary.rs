@@ -0,0 +1,232 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    graph::{DataFlowGraph, TaintCutoff},
+    node::{DataFlowNode, DataFlowNodeKind},
+    path::{DataFlowPath, PathKind},
+};
+use crate::taint::SinkType;
+
+/// The taint flows through a single function or method body.
+///
+/// Before a summary is cached, local variables are collapsed away wherever that doesn't lose
+/// path information, so what's left are the flows from the function's params (and the values it
+/// fetches or receives from calls) to its return value, its sinks and the functions it calls,
+/// along with the taints those flows add or remove.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaintSummary {
+    pub vertices: FxHashMap<String, DataFlowNode>,
+    pub sources: FxHashMap<String, DataFlowNode>,
+    pub sinks: FxHashMap<String, DataFlowNode>,
+    pub forward_edges: FxHashMap<String, FxHashMap<String, DataFlowPath>>,
+    pub cutoffs: FxHashMap<String, Vec<TaintCutoff>>,
+}
+
+impl TaintSummary {
+    pub fn new(graph: DataFlowGraph) -> Self {
+        Self {
+            vertices: graph.vertices,
+            sources: graph.sources,
+            sinks: graph.sinks,
+            forward_edges: graph.forward_edges,
+            cutoffs: graph.cutoffs,
+        }
+    }
+
+    pub fn extend(&mut self, other: Self) {
+        self.vertices.extend(other.vertices);
+        self.sources.extend(other.sources);
+        self.sinks.extend(other.sinks);
+
+        for (from_id, to) in other.forward_edges {
+            self.forward_edges.entry(from_id).or_default().extend(to);
+        }
+
+        for (node_id, cutoffs) in other.cutoffs {
+            self.cutoffs.entry(node_id).or_default().extend(cutoffs);
+        }
+    }
+
+    /// Gets a smaller copy of the summary for caching, without the local variables that
+    /// taint traces would otherwise pass through
+    pub fn get_collapsed(&self) -> Self {
+        let mut summary = self.clone();
+        summary.collapse_local_vertices();
+        summary
+    }
+
+    fn collapse_local_vertices(&mut self) {
+        let mut backward_edges: FxHashMap<String, FxHashSet<String>> = FxHashMap::default();
+
+        for (from_id, to) in &self.forward_edges {
+            for to_id in to.keys() {
+                backward_edges
+                    .entry(to_id.clone())
+                    .or_default()
+                    .insert(from_id.clone());
+            }
+        }
+
+        let mut local_vertex_ids = self
+            .vertices
+            .values()
+            .filter(|vertex| {
+                is_local_vertex(vertex)
+                    && !self.sources.contains_key(&vertex.id)
+                    && !self.sinks.contains_key(&vertex.id)
+                    && !self.cutoffs.contains_key(&vertex.id)
+            })
+            .map(|vertex| vertex.id.clone())
+            .collect::<Vec<_>>();
+
+        local_vertex_ids.sort();
+
+        for vertex_id in local_vertex_ids {
+            let parent_ids = backward_edges.remove(&vertex_id).unwrap_or_default();
+            let child_paths = self
+                .forward_edges
+                .get(&vertex_id)
+                .cloned()
+                .unwrap_or_default();
+
+            // a local vertex that's never reached, or that doesn't lead anywhere, can't be
+            // part of a path from a source to a sink
+            if !parent_ids.is_empty() && !child_paths.is_empty() {
+                let new_paths = if let Some(new_paths) =
+                    self.get_bypassing_paths(&vertex_id, &parent_ids, &child_paths)
+                {
+                    new_paths
+                } else {
+                    backward_edges.insert(vertex_id, parent_ids);
+                    continue;
+                };
+
+                for (parent_id, child_id, new_path) in new_paths {
+                    backward_edges
+                        .entry(child_id.clone())
+                        .or_default()
+                        .insert(parent_id.clone());
+
+                    self.forward_edges
+                        .get_mut(&parent_id)
+                        .unwrap()
+                        .insert(child_id, new_path);
+                }
+            }
+
+            for parent_id in &parent_ids {
+                if let Some(parent_paths) = self.forward_edges.get_mut(parent_id) {
+                    parent_paths.remove(&vertex_id);
+
+                    if parent_paths.is_empty() {
+                        self.forward_edges.remove(parent_id);
+                    }
+                }
+            }
+
+            for child_id in child_paths.keys() {
+                if let Some(child_parent_ids) = backward_edges.get_mut(child_id) {
+                    child_parent_ids.remove(&vertex_id);
+                }
+            }
+
+            self.forward_edges.remove(&vertex_id);
+            self.vertices.remove(&vertex_id);
+        }
+    }
+
+    /// Gets the paths that replace all paths through the given vertex, or None if
+    /// the vertex can't be removed without changing what the search for sinks sees
+    fn get_bypassing_paths(
+        &self,
+        vertex_id: &String,
+        parent_ids: &FxHashSet<String>,
+        child_paths: &FxHashMap<String, DataFlowPath>,
+    ) -> Option<Vec<(String, String, DataFlowPath)>> {
+        let mut new_paths = vec![];
+
+        for parent_id in parent_ids {
+            let parent_paths = &self.forward_edges[parent_id];
+            let parent_path = &parent_paths[vertex_id];
+
+            for (child_id, child_path) in child_paths {
+                if parent_id == child_id {
+                    continue;
+                }
+
+                let new_path = combine_paths(parent_path, child_path)?;
+
+                if let Some(existing_path) = parent_paths.get(child_id) {
+                    if existing_path != &new_path {
+                        return None;
+                    }
+                }
+
+                new_paths.push((parent_id.clone(), child_id.clone(), new_path));
+            }
+        }
+
+        Some(new_paths)
+    }
+}
+
+/// Vertices for assignments to local variables have ids that are qualified by their position,
+/// so nothing outside the function body can refer to them
+fn is_local_vertex(node: &DataFlowNode) -> bool {
+    if let DataFlowNodeKind::Vertex {
+        pos: Some(pos),
+        unspecialized_id: None,
+        ..
+    } = &node.kind
+    {
+        node.id.ends_with(&format!(
+            "-{}:{}-{}",
+            pos.file_path.0 .0, pos.start_offset, pos.end_offset
+        ))
+    } else {
+        false
+    }
+}
+
+/// Combines two consecutive paths into one, as long as one of them is a default path
+/// (so that the path kinds seen when searching for sinks stay the same)
+fn combine_paths(first: &DataFlowPath, second: &DataFlowPath) -> Option<DataFlowPath> {
+    let kind = match (&first.kind, &second.kind) {
+        (PathKind::Default, kind) | (kind, PathKind::Default) => kind.clone(),
+        _ => return None,
+    };
+
+    let first_added_taints = first.added_taints.clone().unwrap_or_default();
+    let first_removed_taints = first.removed_taints.clone().unwrap_or_default();
+    let second_added_taints = second.added_taints.clone().unwrap_or_default();
+    let second_removed_taints = second.removed_taints.clone().unwrap_or_default();
+
+    // taints added by the first path survive unless the first path also removes them, and taints
+    // removed by the first path stay removed unless the second path adds them back
+    let added_taints = first_added_taints
+        .difference(&first_removed_taints)
+        .chain(second_added_taints.iter())
+        .cloned()
+        .collect::<FxHashSet<SinkType>>();
+
+    let removed_taints = first_removed_taints
+        .difference(&second_added_taints)
+        .chain(second_removed_taints.iter())
+        .cloned()
+        .collect::<FxHashSet<SinkType>>();
+
+    Some(DataFlowPath {
+        kind,
+        added_taints: if added_taints.is_empty() {
+            None
+        } else {
+            Some(added_taints)
+        },
+        removed_taints: if removed_taints.is_empty() {
+            None
+        } else {
+            Some(removed_taints)
+        },
+    })
+}
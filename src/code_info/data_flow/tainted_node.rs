@@ -11,10 +11,17 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     code_location::HPos,
-    taint::{self, SinkType, SourceType},
+    taint::{self, SinkType, SourceSinkPairs, SourceType},
     Interner,
 };
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TaintTraceStep {
+    pub label: String,
+    pub pos: Option<HPos>,
+    pub path: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TaintedNode {
     pub id: String,
@@ -63,6 +70,25 @@ impl TaintedNode {
         source_descriptor
     }
 
+    pub fn get_trace_steps(&self) -> Vec<TaintTraceStep> {
+        let mut steps = if let Some(previous_source) = &self.previous {
+            previous_source.get_trace_steps()
+        } else {
+            vec![]
+        };
+
+        steps.push(TaintTraceStep {
+            label: self.label.clone(),
+            pos: self.pos.as_ref().map(|p| **p),
+            path: match self.path_types.iter().last() {
+                Some(PathKind::Default) | None => None,
+                Some(path) => Some(path.to_string()),
+            },
+        });
+
+        steps
+    }
+
     pub fn get_taint_sources(&self) -> &FxHashSet<SourceType> {
         if let Some(previous_source) = &self.previous {
             return previous_source.get_taint_sources();
@@ -71,7 +97,7 @@ impl TaintedNode {
         &self.taint_sources
     }
 
-    pub fn from(node: &DataFlowNode) -> Self {
+    pub fn from(node: &DataFlowNode, source_sink_pairs: &SourceSinkPairs) -> Self {
         match &node.kind {
             DataFlowNodeKind::Vertex {
                 pos,
@@ -94,7 +120,7 @@ impl TaintedNode {
                 let mut sinks = FxHashSet::default();
 
                 for source_type in types {
-                    sinks.extend(taint::get_sinks_for_sources(source_type));
+                    sinks.extend(taint::get_sinks_for_sources(source_type, source_sink_pairs));
                 }
 
                 TaintedNode {
@@ -7,4 +7,6 @@ use crate::{ast_signature::DefSignatureNode, functionlike_info::FunctionLikeInfo
 pub struct FileInfo {
     pub ast_nodes: Vec<DefSignatureNode>,
     pub closure_infos: FxHashMap<usize, FunctionLikeInfo>,
+    /// The module set by a `module` declaration at the top of the file
+    pub module: Option<String>,
 }
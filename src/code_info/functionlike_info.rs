@@ -76,8 +76,14 @@ pub struct FunctionLikeInfo {
 
     pub suppressed_issues: Option<FxHashMap<IssueKind, HPos>>,
 
-    pub deprecated: bool,
+    /// The message from the `__Deprecated` attribute, if present
+    pub deprecated: Option<String>,
 
+    /// Whether the function or method is marked `internal`, so it can only be used
+    /// from inside its module
+    pub is_internal: bool,
+
+    /// The module declared in the file that contains an internal function or method
     pub internal_to: Option<String>,
 
     /**
@@ -111,6 +117,11 @@ pub struct FunctionLikeInfo {
 
     pub pure_can_throw: bool,
 
+    /**
+     * Whether the function has a <<Hakana\Pure>> attribute, so its body must have no side effects
+     */
+    pub has_pure_attribute: bool,
+
     /**
      * Whether or not the function output is dependent solely on input - a function can be
      * impure but still have this property (e.g. var_export). Useful for taint analysis.
@@ -177,7 +188,8 @@ impl FunctionLikeInfo {
             is_populated: false,
             user_defined: false,
             suppressed_issues: None,
-            deprecated: false,
+            deprecated: None,
+            is_internal: false,
             internal_to: None,
             template_types: IndexMap::new(),
             assertions: None,
@@ -205,6 +217,7 @@ impl FunctionLikeInfo {
             async_version: None,
             is_production_code: true,
             pure_can_throw: false,
+            has_pure_attribute: false,
         }
     }
 
@@ -5,13 +5,23 @@ use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumString};
 
 use crate::{
-    code_location::HPos, function_context::FunctionLikeIdentifier, taint::SinkType, StrId,
+    code_location::{HPos, StmtStart},
+    data_flow::tainted_node::TaintTraceStep,
+    function_context::FunctionLikeIdentifier,
+    taint::SinkType,
+    StrId,
 };
 
 #[derive(Clone, PartialEq, Eq, Hash, Display, Debug, Serialize, Deserialize, EnumString)]
 pub enum IssueKind {
+    ArchitectureViolation,
+    BannedFunction,
     CannotInferGenericParam,
     CustomIssue(String),
+    DeprecatedClass,
+    DeprecatedFunction,
+    DeprecatedMethod,
+    DeprecatedProperty,
     EmptyBlock,
     FalsableReturnStatement,
     FalseArgument,
@@ -23,8 +33,14 @@ pub enum IssueKind {
     ImpossibleNullTypeComparison,
     ImpossibleTruthinessCheck,
     ImpossibleTypeComparison,
+    ImpureFunctionCall,
+    ImpureGlobalRead,
+    ImpurePropertyAssignment,
     IncompatibleTypeParameters,
+    InternalClassUsedOutsideModule,
     InternalError,
+    InternalFunctionUsedOutsideModule,
+    InternalMethodUsedOutsideModule,
     InvalidArgument,
     InvalidArrayOffset,
     InvalidContainsCheck,
@@ -35,6 +51,7 @@ pub enum IssueKind {
     InvalidReturnStatement,
     InvalidReturnType,
     InvalidReturnValue,
+    InvalidTemplateVariance,
     LessSpecificArgument,
     LessSpecificNestedAnyArgumentType,
     LessSpecificNestedAnyReturnStatement,
@@ -43,6 +60,7 @@ pub enum IssueKind {
     LessSpecificReturnStatement,
     MethodCallOnNull,
     MissingRequiredXhpAttribute,
+    MissingReturnType,
     MixedAnyArgument,
     MixedAnyArrayAccess,
     MixedAnyArrayAssignment,
@@ -62,6 +80,7 @@ pub enum IssueKind {
     MixedPropertyTypeCoercion,
     MixedReturnStatement,
     NoValue,
+    NonExhaustiveSwitch,
     NonExistentClass,
     NonExistentClassConstant,
     NonExistentClasslike,
@@ -93,6 +112,7 @@ pub enum IssueKind {
     PossiblyNullPropertyFetch,
     PossiblyUndefinedIntArrayOffset,
     PossiblyUndefinedStringArrayOffset,
+    PropertyNotSetInConstructor,
     PropertyTypeCoercion,
     RedundantIssetCheck,
     RedundantKeyCheck,
@@ -105,7 +125,9 @@ pub enum IssueKind {
     UndefinedStringArrayOffset,
     UndefinedVariable,
     UnevaluatedCode,
+    UninitializedPropertyRead,
     UnnecessaryShapesIdx,
+    UnreachableDefault,
     UnrecognizedBinaryOp,
     UnrecognizedExpression,
     UnrecognizedStatement,
@@ -211,6 +233,29 @@ impl IssueKind {
     }
 }
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    #[default]
+    Error,
+    Warning,
+    Info,
+}
+
+impl std::fmt::Display for IssueSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                IssueSeverity::Error => "ERROR",
+                IssueSeverity::Warning => "WARNING",
+                IssueSeverity::Info => "INFO",
+            }
+        )
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Issue {
     pub kind: IssueKind,
@@ -219,6 +264,7 @@ pub struct Issue {
     pub can_fix: bool,
     pub fixme_added: bool,
     pub symbol: (StrId, StrId),
+    pub taint_trace: Option<Vec<TaintTraceStep>>,
 }
 
 impl Issue {
@@ -239,12 +285,39 @@ impl Issue {
             pos,
             can_fix: false,
             fixme_added: false,
+            taint_trace: None,
         }
     }
 
-    pub fn format(&self, path: &String) -> String {
+    /// The HAKANA_FIXME comment that suppresses this issue when inserted at the given statement start
+    pub fn get_fixme_comment(&self, insertion_start: &StmtStart) -> String {
+        format!(
+            "/* HAKANA_FIXME[{}]{} */{}",
+            self.kind.to_string(),
+            if let IssueKind::UnusedParameter
+            | IssueKind::UnusedAssignment
+            | IssueKind::UnusedAssignmentInClosure
+            | IssueKind::UnusedAssignmentStatement
+            | IssueKind::UnusedStatement
+            | IssueKind::UnusedFunction
+            | IssueKind::UnusedPrivateMethod = self.kind
+            {
+                "".to_string()
+            } else {
+                " ".to_string() + &self.description
+            },
+            if insertion_start.add_newline {
+                "\n".to_string() + &"\t".repeat(insertion_start.column as usize)
+            } else {
+                " ".to_string()
+            }
+        )
+    }
+
+    pub fn format(&self, path: &String, severity: IssueSeverity) -> String {
         format!(
-            "ERROR: {} - {}:{}:{} - {}\n",
+            "{}: {} - {}:{}:{} - {}\n",
+            severity,
             self.kind.to_string(),
             path,
             self.pos.start_line,
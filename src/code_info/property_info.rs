@@ -35,7 +35,12 @@ pub struct PropertyInfo {
 
     pub is_promoted: bool,
 
+    // has the <<__LateInit>> attribute, so doesn't need to be set in the constructor
+    pub is_late_init: bool,
+
     pub is_internal: bool,
 
+    pub deprecated: Option<String>,
+
     pub suppressed_issues: Option<FxHashMap<IssueKind, HPos>>,
 }
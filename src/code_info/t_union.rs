@@ -328,6 +328,32 @@ impl TUnion {
         })
     }
 
+    /// Gets the classes, enums and type aliases named anywhere in the type, whether or not
+    /// it has been populated yet
+    pub fn get_referenced_type_names(&self) -> Vec<StrId> {
+        let mut type_names = vec![];
+
+        for child_node in self.get_all_child_nodes() {
+            if let TypeNode::Atomic(
+                TAtomic::TReference { name, .. }
+                | TAtomic::TNamedObject {
+                    name,
+                    is_this: false,
+                    ..
+                }
+                | TAtomic::TEnum { name, .. }
+                | TAtomic::TTypeAlias { name, .. },
+            ) = child_node
+            {
+                if !type_names.contains(name) {
+                    type_names.push(*name);
+                }
+            }
+        }
+
+        type_names
+    }
+
     pub fn get_template_types(&self) -> Vec<&TAtomic> {
         let all_child_nodes = self.get_all_child_nodes();
 
@@ -1,9 +1,9 @@
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use serde::{Deserialize, Serialize};
-use std::{hash::Hash, str::FromStr};
+use std::{fmt, hash::Hash, str::FromStr};
 use strum_macros::{Display, EnumString};
 
-#[derive(Clone, PartialEq, Eq, Hash, Display, Debug, Serialize, Deserialize, EnumString)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, EnumString)]
 pub enum SourceType {
     UriRequestHeader,
     NonUriRequestHeader,
@@ -11,11 +11,23 @@ pub enum SourceType {
     UserPII,
     UserPassword,
     SystemSecret,
+    Custom(String),
+}
+
+// custom sources are shown by their own name rather than as "Custom"
+impl fmt::Display for SourceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceType::Custom(name) => write!(f, "{}", name),
+            _ => write!(f, "{:?}", self),
+        }
+    }
 }
 
 impl SourceType {
     pub fn get_error_message(&self) -> &str {
         match self {
+            SourceType::Custom(str) => str,
             SourceType::UriRequestHeader => "a URL query string",
             SourceType::NonUriRequestHeader => "a server request",
             SourceType::RawUserData => "raw user-controllable data",
@@ -90,12 +102,23 @@ const PAIRS: [(SourceType, SinkType); 31] = [
     (SourceType::SystemSecret, SinkType::Output),
 ];
 
-pub fn get_sinks_for_sources(source: &SourceType) -> FxHashSet<SinkType> {
-    PAIRS
-        .into_iter()
-        .filter(|p| &p.0 == source)
-        .map(|p| p.1)
-        .collect()
+pub type SourceSinkPairs = FxHashMap<SourceType, FxHashSet<SinkType>>;
+
+pub fn get_default_source_sink_pairs() -> SourceSinkPairs {
+    let mut pairs = SourceSinkPairs::default();
+
+    for (source_type, sink_type) in PAIRS {
+        pairs.entry(source_type).or_default().insert(sink_type);
+    }
+
+    pairs
+}
+
+pub fn get_sinks_for_sources(
+    source: &SourceType,
+    source_sink_pairs: &SourceSinkPairs,
+) -> FxHashSet<SinkType> {
+    source_sink_pairs.get(source).cloned().unwrap_or_default()
 }
 
 impl SinkType {
@@ -138,7 +161,12 @@ impl SinkType {
 }
 
 pub fn string_to_source_types(str: String) -> Option<SourceType> {
-    SourceType::from_str(&str).ok()
+    if let Ok(source_type) = SourceType::from_str(&str) {
+        Some(source_type)
+    } else {
+        str.strip_prefix("Custom:")
+            .map(|custom_type| SourceType::Custom(custom_type.to_string()))
+    }
 }
 
 pub fn string_to_sink_types(str: String) -> FxHashSet<SinkType> {
@@ -8,7 +8,7 @@ use hakana_reflection_info::{
     ast_signature::DefSignatureNode,
     attribute_info::AttributeInfo,
     class_constant_info::ConstantInfo,
-    classlike_info::{ClassConstantType, ClassLikeInfo, Variance},
+    classlike_info::{ClassConstantType, ClassLikeInfo, UninitializedPropertyRead, Variance},
     code_location::HPos,
     codebase_info::{symbols::SymbolKind, CodebaseInfo},
     functionlike_info::MetaStart,
@@ -25,7 +25,9 @@ use oxidized::{
     ast_defs::{self, ClassishKind},
 };
 
-use crate::{functionlike_scanner::adjust_location_from_comments, simple_type_inferer};
+use crate::functionlike_scanner::{adjust_location_from_comments, get_deprecation_message};
+use crate::property_initialization;
+use crate::simple_type_inferer;
 use crate::{get_uses_hash, typehint_resolver::get_type_from_hint};
 
 pub(crate) fn scan(
@@ -542,6 +544,7 @@ pub(crate) fn scan(
 
     let codegen_id = interner.intern_str("Codegen");
     let sealed_id = interner.intern_str("__Sealed");
+    let deprecated_id = interner.intern_str("__Deprecated");
 
     for user_attribute in &classlike_node.user_attributes {
         let name = *resolved_names
@@ -554,6 +557,10 @@ pub(crate) fn scan(
 
         storage.attributes.push(AttributeInfo { name });
 
+        if name == deprecated_id {
+            storage.deprecated = Some(get_deprecation_message(user_attribute));
+        }
+
         if name == sealed_id {
             let mut child_classlikes = FxHashSet::default();
 
@@ -580,6 +587,26 @@ pub(crate) fn scan(
 
     // todo iterate over enum cases
 
+    if let Some(constructor_initialization) =
+        property_initialization::get_constructor_initialization(classlike_node)
+    {
+        storage.initialized_properties = constructor_initialization
+            .initialized_properties
+            .into_iter()
+            .map(|property_name| interner.intern(property_name))
+            .collect();
+        storage.constructor_calls_parent = constructor_initialization.calls_parent_constructor;
+        storage.uninitialized_property_reads = constructor_initialization
+            .possibly_uninitialized_reads
+            .into_iter()
+            .map(|property_read| UninitializedPropertyRead {
+                property_name: interner.intern(property_read.property_name),
+                pos: HPos::new(&property_read.pos, file_source.file_path, None),
+                after_parent_constructor: property_read.after_parent_constructor,
+            })
+            .collect();
+    }
+
     for class_property_node in &classlike_node.vars {
         visit_property_declaration(
             class_property_node,
@@ -756,7 +783,9 @@ fn visit_xhp_attribute(
         has_default: xhp_attribute.1.expr.is_some(),
         soft_readonly: false,
         is_promoted: false,
+        is_late_init: false,
         is_internal: false,
+        deprecated: None,
         suppressed_issues: None,
     };
 
@@ -1012,7 +1041,21 @@ fn visit_property_declaration(
         has_default: property_node.expr.is_some(),
         soft_readonly: false,
         is_promoted: false,
+        is_late_init: property_node.user_attributes.iter().any(|user_attribute| {
+            resolved_names
+                .get(&user_attribute.name.0.start_offset())
+                .map_or(false, |name| interner.lookup(*name) == "__LateInit")
+        }),
         is_internal: matches!(property_node.visibility, ast_defs::Visibility::Internal),
+        deprecated: property_node
+            .user_attributes
+            .iter()
+            .find(|user_attribute| {
+                resolved_names
+                    .get(&user_attribute.name.0.start_offset())
+                    .map_or(false, |name| interner.lookup(*name) == "__Deprecated")
+            })
+            .map(get_deprecation_message),
         suppressed_issues: None,
     };
 
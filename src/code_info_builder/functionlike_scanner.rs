@@ -382,6 +382,9 @@ pub(crate) fn get_functionlike(
             "Hakana\\MustUse" => {
                 functionlike_info.must_use = true;
             }
+            "Hakana\\Pure" => {
+                functionlike_info.has_pure_attribute = true;
+            }
             "__EntryPoint" => {
                 functionlike_info.dynamically_callable = true;
                 functionlike_info.ignore_taint_path = true;
@@ -392,6 +395,9 @@ pub(crate) fn get_functionlike(
             "Codegen" => {
                 functionlike_info.generated = true;
             }
+            "__Deprecated" => {
+                functionlike_info.deprecated = Some(get_deprecation_message(user_attribute));
+            }
             _ => {}
         }
     }
@@ -418,6 +424,13 @@ pub(crate) fn get_functionlike(
         FnEffect::Some(EFFECT_IMPURE)
     };
 
+    // the attribute is enforced by the analyzer, so callers can treat the function as pure
+    if functionlike_info.has_pure_attribute
+        && !matches!(functionlike_info.effects, FnEffect::Arg(_))
+    {
+        functionlike_info.effects = FnEffect::Pure;
+    }
+
     if matches!(functionlike_info.effects, FnEffect::Pure) || this_name.is_none() {
         functionlike_info.specialize_call = true;
     }
@@ -541,6 +554,15 @@ fn is_async_call_is_same_as_sync(
     true
 }
 
+/// The first argument to `__Deprecated` is the message shown when the deprecated symbol is used
+pub(crate) fn get_deprecation_message(user_attribute: &aast::UserAttribute<(), ()>) -> String {
+    if let Some(aast::Expr(_, _, aast::Expr_::String(message))) = user_attribute.params.first() {
+        message.to_string()
+    } else {
+        "".to_string()
+    }
+}
+
 pub(crate) fn adjust_location_from_comments(
     comments: &Vec<(Pos, Comment)>,
     meta_start: &mut MetaStart,
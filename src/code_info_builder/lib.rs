@@ -28,6 +28,7 @@ use rustc_hash::{FxHashMap, FxHashSet};
 
 mod classlike_scanner;
 mod functionlike_scanner;
+pub mod property_initialization;
 pub mod simple_type_inferer;
 pub mod typehint_resolver;
 
@@ -51,6 +52,7 @@ struct Scanner<'a> {
     closures: FxHashMap<usize, FunctionLikeInfo>,
     ast_nodes: Vec<DefSignatureNode>,
     uses: Uses,
+    module: Option<String>,
 }
 
 impl<'ast> Visitor<'ast> for Scanner<'_> {
@@ -80,6 +82,9 @@ impl<'ast> Visitor<'ast> for Scanner<'_> {
                     }
                 }
             }
+            aast::Def::SetModule(module_name) => {
+                self.module = Some(module_name.1.clone());
+            }
             _ => {}
         }
 
@@ -115,6 +120,13 @@ impl<'ast> Visitor<'ast> for Scanner<'_> {
             &self.uses,
         );
 
+        if class.internal {
+            if let Some(storage) = self.codebase.classlike_infos.get_mut(&class_name) {
+                storage.is_internal = true;
+                storage.internal_to = self.module.clone();
+            }
+        }
+
         class.recurse(
             &mut Context {
                 classlike_name: Some(class_name),
@@ -450,7 +462,7 @@ impl<'ast> Visitor<'ast> for Scanner<'_> {
     }
 
     fn visit_method_(&mut self, c: &mut Context, m: &aast::Method_<(), ()>) -> Result<(), ()> {
-        let (method_name, functionlike_storage) = functionlike_scanner::scan_method(
+        let (method_name, mut functionlike_storage) = functionlike_scanner::scan_method(
             self.codebase,
             self.interner,
             self.all_custom_issues,
@@ -462,10 +474,15 @@ impl<'ast> Visitor<'ast> for Scanner<'_> {
             self.user_defined,
         );
 
+        if matches!(m.visibility, ast_defs::Visibility::Internal) {
+            functionlike_storage.is_internal = true;
+            functionlike_storage.internal_to = self.module.clone();
+        }
+
         c.member_name = Some(method_name);
 
         if let Some(last_current_node) = self.ast_nodes.last_mut() {
-            let (signature_hash, body_hash) = get_function_hashes(
+            let (mut signature_hash, body_hash) = get_function_hashes(
                 &self.file_source.file_contents,
                 &functionlike_storage.def_location,
                 &m.name,
@@ -477,6 +494,27 @@ impl<'ast> Visitor<'ast> for Scanner<'_> {
                     .get(&(c.classlike_name.unwrap(), c.member_name.unwrap()))
                     .unwrap_or(&vec![]),
             );
+
+            // subclasses rely on which properties the constructor initializes, so a change
+            // to those is treated as a change to the constructor's signature
+            if method_name == StrId::CONSTRUCT {
+                if let Some(classlike_storage) = self
+                    .codebase
+                    .classlike_infos
+                    .get(&c.classlike_name.unwrap())
+                {
+                    for property_name in &classlike_storage.initialized_properties {
+                        let mut hasher = rustc_hash::FxHasher::default();
+                        property_name.0.hash(&mut hasher);
+                        signature_hash = signature_hash.wrapping_add(hasher.finish());
+                    }
+
+                    if classlike_storage.constructor_calls_parent {
+                        signature_hash = signature_hash.wrapping_add(1);
+                    }
+                }
+            }
+
             last_current_node.children.push(DefSignatureNode {
                 name: functionlike_storage.name,
                 start_offset: functionlike_storage.def_location.start_offset,
@@ -539,7 +577,7 @@ impl<'ast> Visitor<'ast> for Scanner<'_> {
     fn visit_fun_def(&mut self, c: &mut Context, f: &aast::FunDef<(), ()>) -> Result<(), ()> {
         let name = *self.resolved_names.get(&f.name.0.start_offset()).unwrap();
 
-        let functionlike_storage = self.visit_function(
+        let mut functionlike_storage = self.visit_function(
             false,
             c,
             name,
@@ -549,6 +587,11 @@ impl<'ast> Visitor<'ast> for Scanner<'_> {
             Some(&f.name.0),
         );
 
+        if f.internal {
+            functionlike_storage.is_internal = true;
+            functionlike_storage.internal_to = self.module.clone();
+        }
+
         let (signature_hash, body_hash) = get_function_hashes(
             &self.file_source.file_contents,
             &functionlike_storage.def_location,
@@ -832,6 +875,7 @@ pub fn collect_info_for_aast(
         closures: FxHashMap::default(),
         ast_nodes: Vec::new(),
         uses,
+        module: None,
     };
 
     let mut context = Context {
@@ -850,6 +894,7 @@ pub fn collect_info_for_aast(
             FileInfo {
                 closure_infos: checker.closures,
                 ast_nodes: checker.ast_nodes,
+                module: checker.module,
             },
         );
     }
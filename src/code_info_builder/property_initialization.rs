@@ -0,0 +1,576 @@
+use oxidized::{
+    aast,
+    aast_visitor::{AstParams, Node, Visitor},
+    ast_defs::{self, Pos},
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// What a class's own constructor does to the properties declared on `$this`.
+#[derive(Debug, Default)]
+pub struct ConstructorInitialization {
+    /// Properties assigned on every path through the constructor
+    pub initialized_properties: FxHashSet<String>,
+
+    /// Whether `parent::__construct()` is called on every path through the constructor
+    pub calls_parent_constructor: bool,
+
+    /// Reads of `$this->prop` that may happen before the property has been assigned
+    pub possibly_uninitialized_reads: Vec<PropertyRead>,
+}
+
+#[derive(Debug)]
+pub struct PropertyRead {
+    pub property_name: String,
+    pub pos: Pos,
+
+    /// Whether `parent::__construct()` has been called on every path to the read
+    pub after_parent_constructor: bool,
+}
+
+#[derive(Clone)]
+struct InitializationState {
+    assigned: FxHashSet<String>,
+    parent_called: bool,
+}
+
+impl InitializationState {
+    fn merge(states: Vec<InitializationState>) -> Option<InitializationState> {
+        let mut states = states.into_iter();
+        let mut merged = states.next()?;
+
+        for state in states {
+            merged.assigned.retain(|name| state.assigned.contains(name));
+            merged.parent_called = merged.parent_called && state.parent_called;
+        }
+
+        Some(merged)
+    }
+}
+
+struct BlockOutcome {
+    // None when every path through the block returns or throws
+    next: Option<InitializationState>,
+
+    // states at any `break` or `continue` inside the block
+    breaks: Vec<InitializationState>,
+}
+
+impl BlockOutcome {
+    fn next(state: InitializationState) -> Self {
+        Self {
+            next: Some(state),
+            breaks: vec![],
+        }
+    }
+}
+
+/// Walks the constructor of the given class (if it declares one), following calls to
+/// private and final methods on `$this`, to work out which properties the constructor
+/// is guaranteed to initialize.
+///
+/// This is a purely syntactic analysis, so it runs during scanning.
+pub fn get_constructor_initialization(
+    classlike_node: &aast::Class_<(), ()>,
+) -> Option<ConstructorInitialization> {
+    let constructor = classlike_node
+        .methods
+        .iter()
+        .find(|m| m.name.1.eq_ignore_ascii_case("__construct") && !m.abstract_)?;
+
+    let mut walker = ConstructorWalker {
+        inlinable_methods: classlike_node
+            .methods
+            .iter()
+            .filter(|m| {
+                !m.static_
+                    && !m.abstract_
+                    && (m.final_ || matches!(m.visibility, ast_defs::Visibility::Private))
+            })
+            .map(|m| (m.name.1.as_str(), m))
+            .collect(),
+        visiting_methods: FxHashSet::default(),
+        return_states: vec![],
+        possibly_uninitialized_reads: vec![],
+    };
+
+    let initial_state = InitializationState {
+        assigned: constructor
+            .params
+            .iter()
+            .filter(|param| param.visibility.is_some())
+            .map(|param| param.name[1..].to_string())
+            .collect(),
+        parent_called: false,
+    };
+
+    let final_state = walker.walk_method_body(constructor, initial_state);
+
+    let mut possibly_uninitialized_reads = walker.possibly_uninitialized_reads;
+    possibly_uninitialized_reads.sort_by_key(|read| read.pos.start_offset());
+    possibly_uninitialized_reads.dedup_by_key(|read| read.pos.start_offset());
+
+    Some(if let Some(final_state) = final_state {
+        ConstructorInitialization {
+            initialized_properties: final_state.assigned,
+            calls_parent_constructor: final_state.parent_called,
+            possibly_uninitialized_reads,
+        }
+    } else {
+        // a constructor that always throws never produces an object
+        ConstructorInitialization {
+            initialized_properties: classlike_node
+                .vars
+                .iter()
+                .map(|var| var.id.1.clone())
+                .collect(),
+            calls_parent_constructor: true,
+            possibly_uninitialized_reads,
+        }
+    })
+}
+
+struct ConstructorWalker<'a> {
+    inlinable_methods: FxHashMap<&'a str, &'a aast::Method_<(), ()>>,
+    visiting_methods: FxHashSet<&'a str>,
+    return_states: Vec<InitializationState>,
+    possibly_uninitialized_reads: Vec<PropertyRead>,
+}
+
+impl<'a> ConstructorWalker<'a> {
+    fn walk_method_body(
+        &mut self,
+        method: &'a aast::Method_<(), ()>,
+        state: InitializationState,
+    ) -> Option<InitializationState> {
+        let outer_return_states = std::mem::take(&mut self.return_states);
+
+        self.visiting_methods.insert(method.name.1.as_str());
+        let outcome = self.walk_block(&method.body.fb_ast.0, state);
+        self.visiting_methods.remove(method.name.1.as_str());
+
+        let mut exit_states = std::mem::replace(&mut self.return_states, outer_return_states);
+        exit_states.extend(outcome.next);
+
+        InitializationState::merge(exit_states)
+    }
+
+    fn walk_block(
+        &mut self,
+        stmts: &'a [aast::Stmt<(), ()>],
+        state: InitializationState,
+    ) -> BlockOutcome {
+        let mut state = state;
+        let mut breaks = vec![];
+
+        for stmt in stmts {
+            let outcome = self.walk_stmt(stmt, state);
+            breaks.extend(outcome.breaks);
+
+            if let Some(next_state) = outcome.next {
+                state = next_state;
+            } else {
+                return BlockOutcome { next: None, breaks };
+            }
+        }
+
+        BlockOutcome {
+            next: Some(state),
+            breaks,
+        }
+    }
+
+    fn walk_stmt(
+        &mut self,
+        stmt: &'a aast::Stmt<(), ()>,
+        state: InitializationState,
+    ) -> BlockOutcome {
+        match &stmt.1 {
+            aast::Stmt_::Expr(boxed) => BlockOutcome::next(self.walk_expr(boxed, state)),
+            aast::Stmt_::Return(boxed) => {
+                let state = if let Some(return_expr) = &**boxed {
+                    self.walk_expr(return_expr, state)
+                } else {
+                    state
+                };
+
+                self.return_states.push(state);
+
+                BlockOutcome {
+                    next: None,
+                    breaks: vec![],
+                }
+            }
+            aast::Stmt_::Throw(boxed) => {
+                self.walk_expr(boxed, state);
+
+                BlockOutcome {
+                    next: None,
+                    breaks: vec![],
+                }
+            }
+            aast::Stmt_::Break | aast::Stmt_::Continue => BlockOutcome {
+                next: None,
+                breaks: vec![state],
+            },
+            aast::Stmt_::If(boxed) => {
+                let state = self.walk_expr(&boxed.0, state);
+                let if_outcome = self.walk_block(&boxed.1 .0, state.clone());
+                let else_outcome = self.walk_block(&boxed.2 .0, state);
+
+                let mut breaks = if_outcome.breaks;
+                breaks.extend(else_outcome.breaks);
+
+                BlockOutcome {
+                    next: InitializationState::merge(
+                        if_outcome
+                            .next
+                            .into_iter()
+                            .chain(else_outcome.next)
+                            .collect(),
+                    ),
+                    breaks,
+                }
+            }
+            aast::Stmt_::Do(boxed) => {
+                let body_outcome = self.walk_block(&boxed.0 .0, state);
+
+                // a continue inside the loop body jumps to the condition
+                let mut exit_states = body_outcome.breaks;
+                exit_states.extend(body_outcome.next);
+
+                BlockOutcome {
+                    next: InitializationState::merge(exit_states)
+                        .map(|state| self.walk_expr(&boxed.1, state)),
+                    breaks: vec![],
+                }
+            }
+            aast::Stmt_::While(boxed) => {
+                let state = self.walk_expr(&boxed.0, state);
+
+                // the loop body may never run, so we only care about the reads inside it
+                self.walk_block(&boxed.1 .0, state.clone());
+
+                BlockOutcome::next(state)
+            }
+            aast::Stmt_::For(boxed) => {
+                let mut state = state;
+
+                for init_expr in &boxed.0 {
+                    state = self.walk_expr(init_expr, state);
+                }
+
+                if let Some(cond_expr) = &boxed.1 {
+                    state = self.walk_expr(cond_expr, state);
+                }
+
+                if let Some(mut loop_state) = self.walk_block(&boxed.3 .0, state.clone()).next {
+                    for increment_expr in &boxed.2 {
+                        loop_state = self.walk_expr(increment_expr, loop_state);
+                    }
+                }
+
+                BlockOutcome::next(state)
+            }
+            aast::Stmt_::Foreach(boxed) => {
+                let state = self.walk_expr(&boxed.0, state);
+
+                self.walk_block(&boxed.2 .0, state.clone());
+
+                BlockOutcome::next(state)
+            }
+            aast::Stmt_::Switch(boxed) => {
+                let mut state = self.walk_expr(&boxed.0, state);
+
+                for case in boxed.1.iter() {
+                    state = self.walk_expr(&case.0, state);
+                }
+
+                let mut exit_states = vec![];
+
+                let case_blocks = boxed
+                    .1
+                    .iter()
+                    .map(|case| &case.1 .0)
+                    .chain(boxed.2.iter().map(|default_case| &default_case.1 .0))
+                    .collect::<Vec<_>>();
+
+                for (i, case_block) in case_blocks.iter().enumerate() {
+                    let case_outcome = self.walk_block(case_block, state.clone());
+                    exit_states.extend(case_outcome.breaks);
+
+                    // cases that fall through are handled by walking the next case
+                    if i == case_blocks.len() - 1 {
+                        exit_states.extend(case_outcome.next);
+                    }
+                }
+
+                if boxed.2.is_none() {
+                    exit_states.push(state);
+                }
+
+                BlockOutcome {
+                    next: InitializationState::merge(exit_states),
+                    breaks: vec![],
+                }
+            }
+            aast::Stmt_::Try(boxed) => {
+                let try_outcome = self.walk_block(&boxed.0 .0, state.clone());
+
+                let mut exit_states = try_outcome.next.into_iter().collect::<Vec<_>>();
+                let mut breaks = try_outcome.breaks;
+
+                for catch in boxed.1.iter() {
+                    let catch_outcome = self.walk_block(&catch.2 .0, state.clone());
+                    exit_states.extend(catch_outcome.next);
+                    breaks.extend(catch_outcome.breaks);
+                }
+
+                if let Some(merged_state) = InitializationState::merge(exit_states) {
+                    let finally_outcome = self.walk_block(&boxed.2 .0, merged_state);
+                    breaks.extend(finally_outcome.breaks);
+
+                    BlockOutcome {
+                        next: finally_outcome.next,
+                        breaks,
+                    }
+                } else {
+                    self.walk_block(&boxed.2 .0, state);
+
+                    BlockOutcome { next: None, breaks }
+                }
+            }
+            aast::Stmt_::Block(boxed) => self.walk_block(&boxed.1 .0, state),
+            aast::Stmt_::Using(boxed) => {
+                let mut state = state;
+
+                for using_expr in &boxed.exprs.1 {
+                    state = self.walk_expr(using_expr, state);
+                }
+
+                self.walk_block(&boxed.block.0, state)
+            }
+            aast::Stmt_::Awaitall(boxed) => {
+                let mut state = state;
+
+                for (_, await_expr) in &boxed.0 {
+                    state = self.walk_expr(await_expr, state);
+                }
+
+                self.walk_block(&boxed.1 .0, state)
+            }
+            aast::Stmt_::Concurrent(boxed) => self.walk_block(&boxed.0, state),
+            _ => BlockOutcome::next(state),
+        }
+    }
+
+    fn walk_expr(
+        &mut self,
+        expr: &'a aast::Expr<(), ()>,
+        state: InitializationState,
+    ) -> InitializationState {
+        let mut expr_walker = ExprWalker {
+            constructor_walker: self,
+            state,
+        };
+
+        expr.visit(&mut (), &mut expr_walker).ok();
+
+        expr_walker.state
+    }
+
+    fn add_read(&mut self, property_name: &str, pos: &Pos, state: &InitializationState) {
+        if !state.assigned.contains(property_name) {
+            self.possibly_uninitialized_reads.push(PropertyRead {
+                property_name: property_name.to_string(),
+                pos: pos.clone(),
+                after_parent_constructor: state.parent_called,
+            });
+        }
+    }
+}
+
+struct ExprWalker<'w, 'a> {
+    constructor_walker: &'w mut ConstructorWalker<'a>,
+    state: InitializationState,
+}
+
+impl<'w, 'a> ExprWalker<'w, 'a> {
+    fn walk_branch(&mut self, expr: &'a aast::Expr<(), ()>) -> InitializationState {
+        self.constructor_walker.walk_expr(expr, self.state.clone())
+    }
+}
+
+impl<'ast, 'w> Visitor<'ast> for ExprWalker<'w, 'ast> {
+    type Params = AstParams<(), ()>;
+
+    fn object(&mut self) -> &mut dyn Visitor<'ast, Params = Self::Params> {
+        self
+    }
+
+    fn visit_expr(&mut self, c: &mut (), expr: &'ast aast::Expr<(), ()>) -> Result<(), ()> {
+        match &expr.2 {
+            aast::Expr_::Binop(boxed) => match &boxed.bop {
+                ast_defs::Bop::Eq(compound_op) => {
+                    if let Some(property_name) = get_this_property_name(&boxed.lhs) {
+                        if compound_op.is_some() {
+                            self.constructor_walker.add_read(
+                                property_name,
+                                &boxed.lhs.1,
+                                &self.state,
+                            );
+                        }
+
+                        boxed.rhs.visit(c, self)?;
+                        self.state.assigned.insert(property_name.to_string());
+
+                        return Ok(());
+                    }
+
+                    if let aast::Expr_::List(list_exprs) = &boxed.lhs.2 {
+                        boxed.rhs.visit(c, self)?;
+
+                        for list_expr in list_exprs {
+                            if let Some(property_name) = get_this_property_name(list_expr) {
+                                self.state.assigned.insert(property_name.to_string());
+                            } else {
+                                list_expr.visit(c, self)?;
+                            }
+                        }
+
+                        return Ok(());
+                    }
+
+                    boxed.rhs.visit(c, self)?;
+                    return boxed.lhs.visit(c, self);
+                }
+                ast_defs::Bop::Ampamp | ast_defs::Bop::Barbar | ast_defs::Bop::QuestionQuestion => {
+                    boxed.lhs.visit(c, self)?;
+
+                    // the right-hand side may not be evaluated
+                    self.walk_branch(&boxed.rhs);
+
+                    return Ok(());
+                }
+                _ => {}
+            },
+            aast::Expr_::Eif(boxed) => {
+                boxed.0.visit(c, self)?;
+
+                let if_state = if let Some(if_expr) = &boxed.1 {
+                    self.walk_branch(if_expr)
+                } else {
+                    self.state.clone()
+                };
+
+                let else_state = self.walk_branch(&boxed.2);
+
+                if let Some(merged_state) = InitializationState::merge(vec![if_state, else_state]) {
+                    self.state = merged_state;
+                }
+
+                return Ok(());
+            }
+            aast::Expr_::ObjGet(_) => {
+                if let Some(property_name) = get_this_property_name(expr) {
+                    self.constructor_walker
+                        .add_read(property_name, &expr.1, &self.state);
+
+                    return Ok(());
+                }
+            }
+            aast::Expr_::Call(boxed) => {
+                if let Some(method_name) = get_this_method_name(&boxed.func) {
+                    for (_, arg_expr) in &boxed.args {
+                        arg_expr.visit(c, self)?;
+                    }
+
+                    if let Some(unpacked_arg) = &boxed.unpacked_arg {
+                        unpacked_arg.visit(c, self)?;
+                    }
+
+                    let method = self
+                        .constructor_walker
+                        .inlinable_methods
+                        .get(method_name)
+                        .copied();
+
+                    if let Some(method) = method {
+                        if !self
+                            .constructor_walker
+                            .visiting_methods
+                            .contains(method_name)
+                        {
+                            if let Some(method_state) = self
+                                .constructor_walker
+                                .walk_method_body(method, self.state.clone())
+                            {
+                                self.state = method_state;
+                            }
+                        }
+                    }
+
+                    return Ok(());
+                }
+
+                if is_parent_constructor_call(&boxed.func) {
+                    for (_, arg_expr) in &boxed.args {
+                        arg_expr.visit(c, self)?;
+                    }
+
+                    if let Some(unpacked_arg) = &boxed.unpacked_arg {
+                        unpacked_arg.visit(c, self)?;
+                    }
+
+                    self.state.parent_called = true;
+
+                    return Ok(());
+                }
+            }
+            aast::Expr_::Lfun(_) | aast::Expr_::Efun(_) => {
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        expr.recurse(c, self)
+    }
+}
+
+fn get_this_property_name(expr: &aast::Expr<(), ()>) -> Option<&str> {
+    if let aast::Expr_::ObjGet(boxed) = &expr.2 {
+        if let (aast::Expr_::Lvar(lhs_var), aast::Expr_::Id(property_id)) =
+            (&boxed.0 .2, &boxed.1 .2)
+        {
+            if lhs_var.1 .1 == "$this" && matches!(boxed.3, ast_defs::PropOrMethod::IsProp) {
+                return Some(property_id.1.as_str());
+            }
+        }
+    }
+
+    None
+}
+
+fn get_this_method_name(expr: &aast::Expr<(), ()>) -> Option<&str> {
+    if let aast::Expr_::ObjGet(boxed) = &expr.2 {
+        if let (aast::Expr_::Lvar(lhs_var), aast::Expr_::Id(method_id)) = (&boxed.0 .2, &boxed.1 .2)
+        {
+            if lhs_var.1 .1 == "$this" && matches!(boxed.3, ast_defs::PropOrMethod::IsMethod) {
+                return Some(method_id.1.as_str());
+            }
+        }
+    }
+
+    None
+}
+
+fn is_parent_constructor_call(expr: &aast::Expr<(), ()>) -> bool {
+    if let aast::Expr_::ClassConst(boxed) = &expr.2 {
+        if let aast::ClassId_::CIexpr(lhs_expr) = &boxed.0 .2 {
+            if let aast::Expr_::Id(id) = &lhs_expr.2 {
+                return id.1 == "parent" && boxed.1 .1.eq_ignore_ascii_case("__construct");
+            }
+        }
+    }
+
+    false
+}
@@ -1,3 +1,4 @@
+use crate::file::VirtualFileSystem;
 use crate::{get_aast_for_path, update_progressbar, SuccessfulScanData};
 use hakana_aast_helper::ParserError;
 use hakana_analyzer::config::Config;
@@ -14,6 +15,7 @@ use oxidized::aast;
 use oxidized::scoured_comments::ScouredComments;
 use rustc_hash::{FxHashMap, FxHashSet};
 
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::{Arc, Mutex};
 use std::{fs, io};
 
@@ -85,6 +87,7 @@ pub fn analyze_files(
                     str_path,
                     codebase,
                     interner,
+                    &scan_data.file_system,
                     &config,
                     &mut new_analysis_result,
                     resolved_names,
@@ -97,16 +100,7 @@ pub fn analyze_files(
                     vec![Issue::new(
                         IssueKind::InvalidHackFile,
                         "Invalid Hack file".to_string(),
-                        HPos {
-                            file_path,
-                            start_offset: 1,
-                            end_offset: 1,
-                            start_line: 1,
-                            end_line: 1,
-                            start_column: 1,
-                            end_column: 1,
-                            insertion_start: None,
-                        },
+                        HPos::file_start(file_path),
                         &None,
                     )],
                 );
@@ -156,6 +150,7 @@ pub fn analyze_files(
                             str_path,
                             codebase,
                             interner,
+                            &scan_data.file_system,
                             &analysis_config,
                             &mut new_analysis_result,
                             resolved_names,
@@ -188,11 +183,39 @@ pub fn analyze_files(
     Ok(())
 }
 
+/// Reanalyzes a single, already-scanned file on its own, using its unsaved contents if there are any
+pub fn analyze_single_file(
+    file_path: FilePath,
+    str_path: &String,
+    scan_data: &SuccessfulScanData,
+    config: &Arc<Config>,
+) -> Option<AnalysisResult> {
+    let resolved_names = scan_data.resolved_names.get(&file_path)?;
+
+    let mut analysis_result = AnalysisResult::new(config.graph_kind, SymbolReferences::new());
+
+    analyze_file(
+        file_path,
+        str_path,
+        &scan_data.codebase,
+        &scan_data.interner,
+        &scan_data.file_system,
+        config,
+        &mut analysis_result,
+        resolved_names,
+        &Logger::DevNull,
+        &Arc::new(FxHashMap::default()),
+    );
+
+    Some(analysis_result)
+}
+
 fn analyze_file(
     file_path: FilePath,
     str_path: &String,
     codebase: &CodebaseInfo,
     interner: &Interner,
+    file_system: &VirtualFileSystem,
     config: &Arc<Config>,
     analysis_result: &mut AnalysisResult,
     resolved_names: &FxHashMap<usize, StrId>,
@@ -201,6 +224,49 @@ fn analyze_file(
 ) {
     logger.log_debug_sync(&format!("Analyzing {}", &str_path));
 
+    // a panic in one file shouldn't stop the other files in its group from being analyzed
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        parse_and_analyze_file(
+            file_path,
+            str_path,
+            codebase,
+            interner,
+            file_system,
+            config,
+            analysis_result,
+            resolved_names,
+            asts,
+        )
+    }));
+
+    if let Err(panic_payload) = result {
+        analysis_result
+            .emitted_issues
+            .entry(file_path)
+            .or_default()
+            .push(Issue::new(
+                IssueKind::InternalError,
+                format!(
+                    "Panic while analyzing file: {}",
+                    file_analyzer::get_panic_message(&*panic_payload)
+                ),
+                HPos::file_start(file_path),
+                &None,
+            ));
+    }
+}
+
+fn parse_and_analyze_file(
+    file_path: FilePath,
+    str_path: &String,
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+    file_system: &VirtualFileSystem,
+    config: &Arc<Config>,
+    analysis_result: &mut AnalysisResult,
+    resolved_names: &FxHashMap<usize, StrId>,
+    asts: &Arc<FxHashMap<FilePath, (aast::Program<(), ()>, ScouredComments)>>,
+) {
     if let Some(aast) = asts.get(&file_path) {
         analyze_loaded_ast(
             str_path,
@@ -213,7 +279,11 @@ fn analyze_file(
             analysis_result,
         );
     } else {
-        let aast = match get_aast_for_path(file_path, str_path) {
+        let aast = match get_aast_for_path(
+            file_path,
+            str_path,
+            file_system.file_contents_overlays.get(str_path),
+        ) {
             Ok(aast) => (aast.0, aast.1),
             Err(err) => {
                 analysis_result.emitted_issues.insert(
@@ -222,16 +292,7 @@ fn analyze_file(
                         ParserError::NotAHackFile => Issue::new(
                             IssueKind::InvalidHackFile,
                             "Invalid Hack file".to_string(),
-                            HPos {
-                                file_path,
-                                start_offset: 0,
-                                end_offset: 0,
-                                start_line: 0,
-                                end_line: 0,
-                                start_column: 0,
-                                end_column: 0,
-                                insertion_start: None,
-                            },
+                            HPos::file_start(file_path),
                             &None,
                         ),
                         ParserError::SyntaxError { message, pos } => {
@@ -285,13 +346,5 @@ fn analyze_loaded_ast(
     let mut file_analyzer =
         file_analyzer::FileAnalyzer::new(file_source, resolved_names, codebase, interner, config);
 
-    match file_analyzer.analyze(&aast.0, analysis_result) {
-        Ok(()) => {}
-        Err(err) => {
-            analysis_result.emitted_issues.insert(
-                file_path,
-                vec![Issue::new(IssueKind::InternalError, err.0, err.1, &None)],
-            );
-        }
-    };
+    file_analyzer.analyze(&aast.0, analysis_result);
 }
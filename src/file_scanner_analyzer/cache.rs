@@ -1,6 +1,7 @@
 use hakana_logger::Logger;
 use hakana_reflection_info::code_location::FilePath;
 use hakana_reflection_info::codebase_info::CodebaseInfo;
+use hakana_reflection_info::data_flow::taint_summary::TaintSummary;
 use hakana_reflection_info::issue::Issue;
 use hakana_reflection_info::symbol_references::SymbolReferences;
 use hakana_reflection_info::Interner;
@@ -98,6 +99,26 @@ pub(crate) fn load_cached_existing_issues(
     None
 }
 
+pub(crate) fn load_cached_taint_summaries(
+    taint_summaries_path: &String,
+    use_codebase_cache: bool,
+    logger: &Logger,
+) -> Option<FxHashMap<FilePath, FxHashMap<(StrId, StrId), TaintSummary>>> {
+    if Path::new(taint_summaries_path).exists() && use_codebase_cache {
+        logger.log_sync("Deserializing taint summaries cache");
+        let serialized = fs::read(taint_summaries_path)
+            .unwrap_or_else(|_| panic!("Could not read file {}", &taint_summaries_path));
+        if let Ok(d) = bincode::deserialize::<
+            FxHashMap<FilePath, FxHashMap<(StrId, StrId), TaintSummary>>,
+        >(&serialized)
+        {
+            return Some(d);
+        }
+    }
+
+    None
+}
+
 pub(crate) fn get_file_manifest(cache_dir: &String) -> Option<VirtualFileSystem> {
     let aast_manifest_path = format!("{}/manifest", cache_dir);
 
@@ -0,0 +1,79 @@
+use hakana_analyzer::config::Config;
+use hakana_analyzer::custom_hook::{AfterCodebaseAnalysisData, CodebaseAnalysisData};
+use hakana_reflection_info::analysis_result::AnalysisResult;
+use hakana_reflection_info::codebase_info::CodebaseInfo;
+use hakana_reflection_info::Interner;
+use rustc_hash::FxHashSet;
+
+use std::sync::Arc;
+
+/// Runs the `after_codebase_analysis` hooks, and adds whatever issues and replacements
+/// they produce to the analysis result
+pub(crate) fn run_after_codebase_analysis_hooks(
+    analysis_result: &mut AnalysisResult,
+    config: &Arc<Config>,
+    codebase: &CodebaseInfo,
+    interner: &Interner,
+    ignored_paths: &Option<FxHashSet<String>>,
+) {
+    let mut codebase_analysis_data = CodebaseAnalysisData::default();
+
+    for hook in &config.hooks {
+        hook.after_codebase_analysis(
+            &mut codebase_analysis_data,
+            AfterCodebaseAnalysisData {
+                analysis_result,
+                codebase,
+                config,
+                interner,
+            },
+        );
+    }
+
+    let is_ignored_path = |file_path: &str| {
+        if let Some(ignored_paths) = ignored_paths {
+            ignored_paths
+                .iter()
+                .any(|ignored_path| file_path.matches(ignored_path.as_str()).count() > 0)
+        } else {
+            false
+        }
+    };
+
+    for (file_path, replacements) in codebase_analysis_data.replacements {
+        if is_ignored_path(interner.lookup(&file_path.0)) {
+            continue;
+        }
+
+        analysis_result
+            .replacements
+            .entry(file_path)
+            .or_default()
+            .extend(replacements);
+    }
+
+    for issue in codebase_analysis_data.issues {
+        let file_path = interner.lookup(&issue.pos.file_path.0);
+
+        if !config.allow_issues_in_file(file_path)
+            || !config.allow_issue_kind_in_file(&issue.kind, file_path)
+            || !config.can_add_issue(&issue)
+        {
+            continue;
+        }
+
+        if is_ignored_path(file_path) {
+            continue;
+        }
+
+        *analysis_result
+            .issue_counts
+            .entry(issue.kind.clone())
+            .or_insert(0) += 1;
+        analysis_result
+            .emitted_definition_issues
+            .entry(issue.pos.file_path)
+            .or_default()
+            .push(issue);
+    }
+}
@@ -2,9 +2,11 @@ use hakana_logger::Logger;
 use hakana_reflection_info::analysis_result::AnalysisResult;
 use hakana_reflection_info::code_location::FilePath;
 use hakana_reflection_info::codebase_info::CodebaseInfo;
+use hakana_reflection_info::data_flow::taint_summary::TaintSummary;
 use hakana_reflection_info::diff::CodebaseDiff;
 use hakana_reflection_info::issue::Issue;
 use hakana_reflection_info::symbol_references::SymbolReferences;
+use hakana_reflection_info::t_union::TUnion;
 use hakana_reflection_info::Interner;
 use hakana_reflection_info::StrId;
 use rustc_hash::FxHashMap;
@@ -12,12 +14,16 @@ use rustc_hash::FxHashSet;
 
 use crate::cache::load_cached_existing_issues;
 use crate::cache::load_cached_existing_references;
+use crate::cache::load_cached_taint_summaries;
 
 #[derive(Default)]
 pub(crate) struct CachedAnalysis {
     pub safe_symbols: FxHashSet<StrId>,
     pub safe_symbol_members: FxHashSet<(StrId, StrId)>,
     pub existing_issues: FxHashMap<FilePath, Vec<Issue>>,
+    pub existing_expr_types: FxHashMap<FilePath, FxHashMap<(u32, u32), TUnion>>,
+    pub existing_symbol_positions: FxHashMap<FilePath, FxHashMap<(u32, u32), (StrId, StrId)>>,
+    pub existing_taint_summaries: FxHashMap<FilePath, FxHashMap<(StrId, StrId), TaintSummary>>,
     pub symbol_references: SymbolReferences,
 }
 
@@ -29,11 +35,23 @@ pub(crate) fn mark_safe_symbols_from_diff(
     files_to_analyze: &mut Vec<String>,
     issues_path: &Option<String>,
     references_path: &Option<String>,
+    taint_summaries_path: &Option<String>,
     previous_analysis_result: Option<AnalysisResult>,
 ) -> CachedAnalysis {
+    let mut existing_expr_types = FxHashMap::default();
+    let mut existing_symbol_positions = FxHashMap::default();
+    let mut existing_taint_summaries = None;
+
     let (existing_references, mut existing_issues) = if let Some(previous_analysis_result) =
         previous_analysis_result
     {
+        existing_expr_types = previous_analysis_result.expr_types;
+        existing_symbol_positions = previous_analysis_result.symbol_positions;
+
+        if !previous_analysis_result.taint_summaries.is_empty() {
+            existing_taint_summaries = Some(previous_analysis_result.taint_summaries);
+        }
+
         (
             previous_analysis_result.symbol_references,
             previous_analysis_result.emitted_issues,
@@ -54,6 +72,11 @@ pub(crate) fn mark_safe_symbols_from_diff(
                 return CachedAnalysis::default();
             };
 
+        if let Some(taint_summaries_path) = taint_summaries_path {
+            existing_taint_summaries =
+                load_cached_taint_summaries(taint_summaries_path, true, logger);
+        }
+
         (existing_references, existing_issues)
     } else {
         return CachedAnalysis::default();
@@ -102,7 +125,34 @@ pub(crate) fn mark_safe_symbols_from_diff(
         .map(|(file_id, _)| interner.lookup(&file_id.0))
         .collect::<FxHashSet<_>>();
 
-    files_to_analyze.retain(|full_path| invalid_files.contains(&full_path.as_str()));
+    let changed_files = if let Some(existing_taint_summaries) = existing_taint_summaries {
+        let changed_files = get_changed_files(&codebase_diff, codebase);
+
+        cached_analysis.existing_taint_summaries = get_valid_taint_summaries(
+            existing_taint_summaries,
+            &changed_files,
+            codebase,
+            &mut cached_analysis.safe_symbols,
+            &mut cached_analysis.safe_symbol_members,
+        );
+
+        changed_files
+            .into_iter()
+            .map(|file_path| interner.lookup(&file_path.0))
+            .collect::<FxHashSet<_>>()
+    } else {
+        FxHashSet::default()
+    };
+
+    files_to_analyze.retain(|full_path| {
+        invalid_files.contains(&full_path.as_str()) || changed_files.contains(&full_path.as_str())
+    });
+
+    update_positions_from_diff(&mut existing_expr_types, &codebase_diff);
+    cached_analysis.existing_expr_types = existing_expr_types;
+
+    update_positions_from_diff(&mut existing_symbol_positions, &codebase_diff);
+    cached_analysis.existing_symbol_positions = existing_symbol_positions;
 
     update_issues_from_diff(
         &mut existing_issues,
@@ -114,6 +164,71 @@ pub(crate) fn mark_safe_symbols_from_diff(
     cached_analysis
 }
 
+fn get_changed_files(codebase_diff: &CodebaseDiff, codebase: &CodebaseInfo) -> FxHashSet<FilePath> {
+    let changed_symbols = codebase_diff
+        .keep_signature
+        .iter()
+        .chain(codebase_diff.add_or_delete.iter())
+        .map(|symbol| symbol.0)
+        .collect::<FxHashSet<_>>();
+
+    codebase
+        .files
+        .iter()
+        .filter(|(file_path, file_info)| {
+            codebase_diff.diff_map.contains_key(file_path)
+                || codebase_diff.deletion_ranges_map.contains_key(file_path)
+                || file_info
+                    .ast_nodes
+                    .iter()
+                    .any(|node| changed_symbols.contains(&node.name))
+        })
+        .map(|(file_path, _)| *file_path)
+        .collect()
+}
+
+/// Taint summaries identify nodes by their positions, so unlike issues they can't be moved around.
+/// Instead every symbol in a changed file is reanalyzed, and summaries are only kept for safe
+/// symbols in files that haven't changed.
+fn get_valid_taint_summaries(
+    mut existing_taint_summaries: FxHashMap<FilePath, FxHashMap<(StrId, StrId), TaintSummary>>,
+    changed_files: &FxHashSet<FilePath>,
+    codebase: &CodebaseInfo,
+    safe_symbols: &mut FxHashSet<StrId>,
+    safe_symbol_members: &mut FxHashSet<(StrId, StrId)>,
+) -> FxHashMap<FilePath, FxHashMap<(StrId, StrId), TaintSummary>> {
+    for file_path in changed_files {
+        if let Some(file_info) = codebase.files.get(file_path) {
+            for node in &file_info.ast_nodes {
+                safe_symbols.remove(&node.name);
+
+                for child in &node.children {
+                    safe_symbol_members.remove(&(node.name, child.name));
+                }
+            }
+        }
+    }
+
+    existing_taint_summaries.retain(|file_path, file_taint_summaries| {
+        if !codebase.files.contains_key(file_path) || changed_files.contains(file_path) {
+            return false;
+        }
+
+        // summaries of reanalyzed symbols get replaced
+        file_taint_summaries.retain(|symbol, _| {
+            if symbol.1 != StrId::EMPTY {
+                safe_symbol_members.contains(symbol)
+            } else {
+                symbol.0 == file_path.0 || safe_symbols.contains(&symbol.0)
+            }
+        });
+
+        !file_taint_summaries.is_empty()
+    });
+
+    existing_taint_summaries
+}
+
 fn update_issues_from_diff(
     existing_issues: &mut FxHashMap<FilePath, Vec<Issue>>,
     codebase_diff: CodebaseDiff,
@@ -171,3 +286,39 @@ fn update_issues_from_diff(
         }
     }
 }
+
+fn update_positions_from_diff<T>(
+    existing_positions: &mut FxHashMap<FilePath, FxHashMap<(u32, u32), T>>,
+    codebase_diff: &CodebaseDiff,
+) {
+    for (existing_file, file_positions) in existing_positions.iter_mut() {
+        if let Some(deletion_ranges) = codebase_diff.deletion_ranges_map.get(existing_file) {
+            file_positions.retain(|(start_offset, _), _| {
+                !deletion_ranges
+                    .iter()
+                    .any(|(from, to)| start_offset >= from && start_offset <= to)
+            });
+        }
+
+        if let Some(diff_map) = codebase_diff.diff_map.get(existing_file) {
+            *file_positions = std::mem::take(file_positions)
+                .into_iter()
+                .map(|((start_offset, end_offset), value)| {
+                    for (from, to, file_offset, _) in diff_map {
+                        if &start_offset >= from && &start_offset <= to {
+                            return (
+                                (
+                                    ((start_offset as isize) + file_offset) as u32,
+                                    ((end_offset as isize) + file_offset) as u32,
+                                ),
+                                value,
+                            );
+                        }
+                    }
+
+                    ((start_offset, end_offset), value)
+                })
+                .collect();
+        }
+    }
+}
@@ -17,10 +17,19 @@ pub enum FileStatus {
 #[derive(Clone, Default, Serialize, Deserialize, Debug)]
 pub struct VirtualFileSystem {
     pub file_hashes_and_times: FxHashMap<FilePath, (u64, u64)>,
+
+    // the contents of files with unsaved changes in the language server,
+    // which take precedence over whatever's on disk
+    #[serde(skip)]
+    pub file_contents_overlays: FxHashMap<String, String>,
 }
 
 impl VirtualFileSystem {
     pub fn get_contents_hash(&self, file_path: &String) -> Result<u64, std::io::Error> {
+        if let Some(file_contents) = self.file_contents_overlays.get(file_path) {
+            return Ok(xxhash_rust::xxh3::xxh3_64(file_contents.as_bytes()));
+        }
+
         match fs::read_to_string(file_path) {
             Ok(file_contents) => Ok(xxhash_rust::xxh3::xxh3_64(file_contents.as_bytes())),
             Err(error) => Err(error),
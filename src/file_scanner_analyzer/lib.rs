@@ -1,10 +1,11 @@
 pub(crate) mod populator;
 
 use analyzer::analyze_files;
+use codebase_hooks::run_after_codebase_analysis_hooks;
 use diff::{mark_safe_symbols_from_diff, CachedAnalysis};
 use file::{FileStatus, VirtualFileSystem};
 use hakana_aast_helper::{get_aast_for_path_and_contents, ParserError};
-use hakana_analyzer::config::Config;
+use hakana_analyzer::config::{Config, SecurityConfig};
 use hakana_analyzer::dataflow::program_analyzer::{find_connections, find_tainted_data};
 use hakana_logger::Logger;
 use hakana_reflection_info::analysis_result::AnalysisResult;
@@ -28,12 +29,15 @@ use unused_symbols::find_unused_definitions;
 mod analyzer;
 mod ast_differ;
 mod cache;
+mod codebase_hooks;
 mod diff;
 pub mod file;
 pub mod scanner;
 mod unused_symbols;
 pub mod wasm;
 
+pub use analyzer::analyze_single_file;
+
 #[derive(RustEmbed)]
 #[folder = "$CARGO_MANIFEST_DIR/../../third-party/hhvm/hphp/hack/hhi"]
 #[prefix = "hhi_embedded_"]
@@ -94,6 +98,7 @@ pub async fn scan_and_analyze_async(
         file_system,
         asts,
         mut files_to_analyze,
+        scan_issues,
     } = scan_files(
         &all_scanned_dirs,
         None,
@@ -114,6 +119,7 @@ pub async fn scan_and_analyze_async(
             &mut files_to_analyze,
             &None,
             &None,
+            &None,
             previous_analysis_result,
         )
     } else {
@@ -130,10 +136,22 @@ pub async fn scan_and_analyze_async(
         cached_analysis.safe_symbol_members,
     );
 
+    if let GraphKind::WholeProgram(_) = config.graph_kind {
+        apply_security_config(&mut codebase, &interner, &config.security_config);
+    }
+
     let mut analysis_result =
         AnalysisResult::new(config.graph_kind, cached_analysis.symbol_references);
 
     analysis_result.emitted_issues = cached_analysis.existing_issues;
+    analysis_result.expr_types = cached_analysis.existing_expr_types;
+    analysis_result.symbol_positions = cached_analysis.existing_symbol_positions;
+    analysis_result.taint_summaries = cached_analysis.existing_taint_summaries;
+    analysis_result.analyzed_files = files_to_analyze
+        .iter()
+        .filter_map(|file_path| interner.get(file_path))
+        .map(FilePath)
+        .collect();
 
     let analysis_result = Arc::new(Mutex::new(analysis_result));
 
@@ -164,6 +182,9 @@ pub async fn scan_and_analyze_async(
 
     let mut analysis_result = (*analysis_result.lock().unwrap()).clone();
 
+    // files whose scan panicked only get the issue that says so
+    analysis_result.emitted_issues.extend(scan_issues);
+
     let scan_data = Arc::try_unwrap(arc_scan_data).unwrap();
 
     if config.find_unused_definitions {
@@ -176,6 +197,16 @@ pub async fn scan_and_analyze_async(
         );
     }
 
+    if !config.hooks.is_empty() {
+        run_after_codebase_analysis_hooks(
+            &mut analysis_result,
+            &config,
+            &scan_data.codebase,
+            &scan_data.interner,
+            &ignored_paths,
+        );
+    }
+
     Ok((analysis_result, scan_data))
 }
 
@@ -190,7 +221,7 @@ pub fn scan_and_analyze(
     header: &str,
     previous_scan_data: Option<SuccessfulScanData>,
     previous_analysis_result: Option<AnalysisResult>,
-    language_server_changes: Option<FxHashMap<String, FileStatus>>,
+    file_changes: Option<FxHashMap<String, FileStatus>>,
 ) -> io::Result<(AnalysisResult, SuccessfulScanData)> {
     let mut all_scanned_dirs = stubs_dirs.clone();
     all_scanned_dirs.push(config.root_dir.clone());
@@ -207,6 +238,7 @@ pub fn scan_and_analyze(
         file_system,
         asts,
         mut files_to_analyze,
+        scan_issues,
     } = scan_files(
         &all_scanned_dirs,
         cache_dir,
@@ -215,7 +247,7 @@ pub fn scan_and_analyze(
         logger.clone(),
         header,
         previous_scan_data,
-        language_server_changes,
+        file_changes,
     )?;
 
     let file_discovery_and_scanning_elapsed = file_discovery_and_scanning_now.elapsed();
@@ -239,6 +271,13 @@ pub fn scan_and_analyze(
             .unwrap_or_else(|_| panic!("Could not write aast manifest {}", &aast_manifest_path));
     }
 
+    let taint_summaries_path =
+        if let GraphKind::WholeProgram(WholeProgramKind::Taint) = config.graph_kind {
+            get_taint_summaries_path(cache_dir)
+        } else {
+            None
+        };
+
     let mut cached_analysis = if config.ast_diff {
         mark_safe_symbols_from_diff(
             &logger,
@@ -248,6 +287,7 @@ pub fn scan_and_analyze(
             &mut files_to_analyze,
             &get_issues_path(cache_dir),
             &get_references_path(cache_dir),
+            &taint_summaries_path,
             previous_analysis_result,
         )
     } else {
@@ -275,10 +315,22 @@ pub fn scan_and_analyze(
         ));
     }
 
+    if let GraphKind::WholeProgram(_) = config.graph_kind {
+        apply_security_config(&mut codebase, &interner, &config.security_config);
+    }
+
     let mut analysis_result =
         AnalysisResult::new(config.graph_kind, cached_analysis.symbol_references);
 
     analysis_result.emitted_issues = cached_analysis.existing_issues;
+    analysis_result.expr_types = cached_analysis.existing_expr_types;
+    analysis_result.symbol_positions = cached_analysis.existing_symbol_positions;
+    analysis_result.taint_summaries = cached_analysis.existing_taint_summaries;
+    analysis_result.analyzed_files = files_to_analyze
+        .iter()
+        .filter_map(|file_path| interner.get(file_path))
+        .map(FilePath)
+        .collect();
 
     let analysis_result = Arc::new(Mutex::new(analysis_result));
 
@@ -318,6 +370,9 @@ pub fn scan_and_analyze(
 
     let mut analysis_result = (*analysis_result.lock().unwrap()).clone();
 
+    // files whose scan panicked only get the issue that says so
+    analysis_result.emitted_issues.extend(scan_issues);
+
     analysis_result.time_in_analysis = analyzed_files_elapsed;
 
     cache_analysis_data(cache_dir, &analysis_result)?;
@@ -334,14 +389,38 @@ pub fn scan_and_analyze(
         );
     }
 
+    if !config.hooks.is_empty() {
+        run_after_codebase_analysis_hooks(
+            &mut analysis_result,
+            &config,
+            &scan_data.codebase,
+            &scan_data.interner,
+            &ignored_paths,
+        );
+    }
+
     if let GraphKind::WholeProgram(whole_program_kind) = config.graph_kind {
         let issues = match whole_program_kind {
-            WholeProgramKind::Taint => find_tainted_data(
-                &analysis_result.program_dataflow_graph,
-                &config,
-                &logger,
-                &scan_data.interner,
-            ),
+            WholeProgramKind::Taint => {
+                if let Some(taint_summaries_path) = &taint_summaries_path {
+                    cache_taint_summaries(taint_summaries_path, &analysis_result)?;
+                }
+
+                for file_taint_summaries in analysis_result.taint_summaries.values() {
+                    for taint_summary in file_taint_summaries.values() {
+                        analysis_result
+                            .program_dataflow_graph
+                            .add_taint_summary(taint_summary);
+                    }
+                }
+
+                find_tainted_data(
+                    &analysis_result.program_dataflow_graph,
+                    &config,
+                    &logger,
+                    &scan_data.interner,
+                )
+            }
             WholeProgramKind::Query => find_connections(
                 &analysis_result.program_dataflow_graph,
                 &config,
@@ -362,6 +441,58 @@ pub fn scan_and_analyze(
     Ok((analysis_result, scan_data))
 }
 
+fn apply_security_config(
+    codebase: &mut CodebaseInfo,
+    interner: &Interner,
+    security_config: &SecurityConfig,
+) {
+    let get_functionlike_key = |functionlike_name: &String| {
+        let functionlike_name = functionlike_name.trim_start_matches('\\');
+
+        if let Some((class_name, method_name)) = functionlike_name.split_once("::") {
+            Some((interner.get(class_name)?, interner.get(method_name)?))
+        } else {
+            Some((interner.get(functionlike_name)?, StrId::EMPTY))
+        }
+    };
+
+    for (functionlike_name, source_types) in &security_config.functionlike_sources {
+        if let Some(functionlike_info) = get_functionlike_key(functionlike_name)
+            .and_then(|key| codebase.functionlike_infos.get_mut(&key))
+        {
+            functionlike_info
+                .taint_source_types
+                .extend(source_types.iter().cloned());
+        }
+    }
+
+    for (functionlike_name, param_sinks) in &security_config.functionlike_sinks {
+        if let Some(functionlike_info) = get_functionlike_key(functionlike_name)
+            .and_then(|key| codebase.functionlike_infos.get_mut(&key))
+        {
+            for param in functionlike_info.params.iter_mut() {
+                if let Some(sink_types) = param_sinks.get(&param.name) {
+                    param
+                        .taint_sinks
+                        .get_or_insert_with(FxHashSet::default)
+                        .extend(sink_types.iter().cloned());
+                }
+            }
+        }
+    }
+
+    for (functionlike_name, removed_taints) in &security_config.functionlike_sanitizers {
+        if let Some(functionlike_info) = get_functionlike_key(functionlike_name)
+            .and_then(|key| codebase.functionlike_infos.get_mut(&key))
+        {
+            functionlike_info
+                .removed_taints
+                .get_or_insert_with(FxHashSet::default)
+                .extend(removed_taints.iter().cloned());
+        }
+    }
+}
+
 fn cache_analysis_data(
     cache_dir: Option<&String>,
     analysis_result: &AnalysisResult,
@@ -380,6 +511,31 @@ fn cache_analysis_data(
     Ok(())
 }
 
+fn cache_taint_summaries(
+    taint_summaries_path: &String,
+    analysis_result: &AnalysisResult,
+) -> Result<(), io::Error> {
+    // the current run searches the full graph, but cached summaries only need to keep the
+    // paths that other functions can see
+    let collapsed_taint_summaries = analysis_result
+        .taint_summaries
+        .iter()
+        .map(|(file_path, file_taint_summaries)| {
+            (
+                *file_path,
+                file_taint_summaries
+                    .iter()
+                    .map(|(symbol, taint_summary)| (*symbol, taint_summary.get_collapsed()))
+                    .collect::<FxHashMap<_, _>>(),
+            )
+        })
+        .collect::<FxHashMap<_, _>>();
+
+    let mut taint_summaries_file = fs::File::create(taint_summaries_path).unwrap();
+    let serialized_taint_summaries = bincode::serialize(&collapsed_taint_summaries).unwrap();
+    taint_summaries_file.write_all(&serialized_taint_summaries)
+}
+
 fn get_issues_path(cache_dir: Option<&String>) -> Option<String> {
     cache_dir.map(|cache_dir| format!("{}/issues", cache_dir))
 }
@@ -388,11 +544,18 @@ fn get_references_path(cache_dir: Option<&String>) -> Option<String> {
     cache_dir.map(|cache_dir| format!("{}/references", cache_dir))
 }
 
+fn get_taint_summaries_path(cache_dir: Option<&String>) -> Option<String> {
+    cache_dir.map(|cache_dir| format!("{}/taint_summaries", cache_dir))
+}
+
 pub fn get_aast_for_path(
     file_path: FilePath,
     file_path_str: &str,
+    file_contents_overlay: Option<&String>,
 ) -> Result<(aast::Program<(), ()>, ScouredComments, String), ParserError> {
-    let file_contents = if file_path_str.starts_with("hsl_embedded_") {
+    let file_contents = if let Some(file_contents_overlay) = file_contents_overlay {
+        file_contents_overlay.clone()
+    } else if file_path_str.starts_with("hsl_embedded_") {
         std::str::from_utf8(
             &HslAsset::get(file_path_str)
                 .unwrap_or_else(|| panic!("Could not read HSL file {}", file_path_str))
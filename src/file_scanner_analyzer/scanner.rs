@@ -1,6 +1,7 @@
 use std::fs;
 use std::io;
 use std::io::Write;
+use std::panic::{self, AssertUnwindSafe};
 use std::path::Path;
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -22,11 +23,13 @@ use ast_differ::get_diff;
 use hakana_aast_helper::name_context::NameContext;
 use hakana_aast_helper::ParserError;
 use hakana_analyzer::config::Config;
+use hakana_analyzer::file_analyzer;
 use hakana_logger::Logger;
-use hakana_reflection_info::code_location::FilePath;
+use hakana_reflection_info::code_location::{FilePath, HPos};
 use hakana_reflection_info::codebase_info::symbols::SymbolKind;
 use hakana_reflection_info::codebase_info::CodebaseInfo;
 use hakana_reflection_info::diff::CodebaseDiff;
+use hakana_reflection_info::issue::{Issue, IssueKind};
 use hakana_reflection_info::FileSource;
 use hakana_reflection_info::Interner;
 use hakana_reflection_info::StrId;
@@ -47,6 +50,14 @@ pub struct ScanFilesResult {
     pub codebase_diff: CodebaseDiff,
     pub asts: FxHashMap<FilePath, (aast::Program<(), ()>, ScouredComments)>,
     pub files_to_analyze: Vec<String>,
+    pub scan_issues: FxHashMap<FilePath, Vec<Issue>>,
+}
+
+#[derive(Debug)]
+pub(crate) enum ScanError {
+    Parser(ParserError),
+    // the scan panicked, and the issue reports it in place of the file's discarded storage
+    Panic(Issue),
 }
 
 pub fn scan_files(
@@ -130,8 +141,11 @@ pub fn scan_files(
         }
     }
 
-    let file_system = if let Some(language_server_changes) = language_server_changes {
-        let mut file_system = existing_file_system.clone().unwrap();
+    // changes can only be applied on top of a known file system, otherwise every file is scanned
+    let file_system = if let (Some(language_server_changes), Some(existing_file_system)) =
+        (language_server_changes, &existing_file_system)
+    {
+        let mut file_system = existing_file_system.clone();
 
         file_system.apply_language_server_changes(
             language_server_changes,
@@ -251,6 +265,7 @@ pub fn scan_files(
     let interner = Arc::new(Mutex::new(interner));
     let resolved_names = Arc::new(Mutex::new(resolved_names));
     let asts = Arc::new(Mutex::new(FxHashMap::default()));
+    let scan_issues = Arc::new(Mutex::new(FxHashMap::default()));
 
     let has_new_files = !files_to_scan.is_empty() || !changed_files.is_empty();
 
@@ -306,9 +321,10 @@ pub fn scan_files(
                     .lookup(&file_path.0)
                     .to_string();
 
-                if let Ok(scanner_result) = scan_file(
+                match scan_file(
                     &str_path,
                     **file_path,
+                    file_system.file_contents_overlays.get(&str_path),
                     &config.all_custom_issues,
                     &mut new_codebase,
                     &mut new_interner,
@@ -317,18 +333,25 @@ pub fn scan_files(
                     !test_patterns.iter().any(|p| p.matches(&str_path)),
                     &logger,
                 ) {
-                    if !config.ast_diff && analyze_map.contains(&str_path) {
-                        asts.lock().unwrap().insert(**file_path, scanner_result.1);
-                    }
+                    Ok(scanner_result) => {
+                        if !config.ast_diff && analyze_map.contains(&str_path) {
+                            asts.lock().unwrap().insert(**file_path, scanner_result.1);
+                        }
 
-                    resolved_names
-                        .lock()
-                        .unwrap()
-                        .insert(**file_path, scanner_result.0);
-                } else {
-                    asts.lock().unwrap().remove(*file_path);
-                    resolved_names.lock().unwrap().remove(*file_path);
-                    new_codebase.files.remove(*file_path);
+                        resolved_names
+                            .lock()
+                            .unwrap()
+                            .insert(**file_path, scanner_result.0);
+                    }
+                    Err(error) => {
+                        if let ScanError::Panic(issue) = error {
+                            scan_issues.lock().unwrap().insert(**file_path, vec![issue]);
+                        }
+
+                        asts.lock().unwrap().remove(*file_path);
+                        resolved_names.lock().unwrap().remove(*file_path);
+                        new_codebase.files.remove(*file_path);
+                    }
                 }
 
                 update_progressbar(i as u64, bar.clone());
@@ -365,6 +388,8 @@ pub fn scan_files(
                 let test_patterns = test_patterns.clone();
                 let asts = asts.clone();
                 let logger = logger.clone();
+                let file_contents_overlays = file_system.file_contents_overlays.clone();
+                let scan_issues = scan_issues.clone();
 
                 let handle = std::thread::spawn(move || {
                     let mut new_codebase = CodebaseInfo::new();
@@ -381,9 +406,10 @@ pub fn scan_files(
                             .lookup(&file_path.0)
                             .to_string();
 
-                        if let Ok(scanner_result) = scan_file(
+                        match scan_file(
                             &str_path,
                             *file_path,
+                            file_contents_overlays.get(&str_path),
                             &config.all_custom_issues,
                             &mut new_codebase,
                             &mut new_interner,
@@ -392,15 +418,22 @@ pub fn scan_files(
                             !test_patterns.iter().any(|p| p.matches(&str_path)),
                             &logger.clone(),
                         ) {
-                            if !config.ast_diff && analyze_map.contains(&str_path) {
-                                local_asts.insert(*file_path, scanner_result.1);
-                            }
+                            Ok(scanner_result) => {
+                                if !config.ast_diff && analyze_map.contains(&str_path) {
+                                    local_asts.insert(*file_path, scanner_result.1);
+                                }
 
-                            local_resolved_names.insert(*file_path, scanner_result.0);
-                        } else {
-                            local_asts.remove(file_path);
-                            local_resolved_names.remove(file_path);
-                            new_codebase.files.remove(file_path);
+                                local_resolved_names.insert(*file_path, scanner_result.0);
+                            }
+                            Err(error) => {
+                                if let ScanError::Panic(issue) = error {
+                                    scan_issues.lock().unwrap().insert(*file_path, vec![issue]);
+                                }
+
+                                local_asts.remove(file_path);
+                                local_resolved_names.remove(file_path);
+                                new_codebase.files.remove(file_path);
+                            }
                         };
 
                         let mut tally = files_processed.lock().unwrap();
@@ -450,13 +483,21 @@ pub fn scan_files(
 
     let interner = Arc::try_unwrap(interner).unwrap().into_inner().unwrap();
 
-    let resolved_names = Arc::try_unwrap(resolved_names)
+    let mut resolved_names = Arc::try_unwrap(resolved_names)
         .unwrap()
         .into_inner()
         .unwrap();
 
     let asts = Arc::try_unwrap(asts).unwrap().into_inner().unwrap();
 
+    let scan_issues: FxHashMap<FilePath, Vec<Issue>> =
+        Arc::try_unwrap(scan_issues).unwrap().into_inner().unwrap();
+
+    // files whose scan panicked aren't analyzed, even if they were scanned on an earlier run
+    for file_path in scan_issues.keys() {
+        resolved_names.remove(file_path);
+    }
+
     if has_new_files {
         if let Some(codebase_path) = codebase_path {
             let mut codebase_file = fs::File::create(codebase_path).unwrap();
@@ -485,6 +526,7 @@ pub fn scan_files(
         asts,
         files_to_analyze,
         file_system,
+        scan_issues,
     })
 }
 
@@ -547,6 +589,7 @@ pub fn add_builtins_to_scan(
 pub(crate) fn scan_file(
     str_path: &str,
     file_path: FilePath,
+    file_contents_overlay: Option<&String>,
     all_custom_issues: &FxHashSet<String>,
     codebase: &mut CodebaseInfo,
     interner: &mut ThreadedInterner,
@@ -559,11 +602,63 @@ pub(crate) fn scan_file(
         FxHashMap<usize, StrId>,
         (aast::Program<(), ()>, ScouredComments),
     ),
-    ParserError,
+    ScanError,
 > {
     logger.log_debug_sync(&format!("scanning {}", str_path));
 
-    let aast = get_aast_for_path(file_path, str_path);
+    // a panic in one file shouldn't stop the other files in its group from being scanned
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        scan_file_contents(
+            str_path,
+            file_path,
+            file_contents_overlay,
+            all_custom_issues,
+            codebase,
+            interner,
+            empty_name_context,
+            user_defined,
+            is_production_code,
+        )
+        .map_err(ScanError::Parser)
+    }))
+    .unwrap_or_else(|panic_payload| {
+        let panic_message = file_analyzer::get_panic_message(&*panic_payload);
+
+        logger.log_sync(&format!(
+            "Panic while scanning {}: {}",
+            str_path, panic_message
+        ));
+
+        // whatever the file had added to the codebase before the panic can't be trusted
+        codebase.remove_file_storage(file_path);
+
+        Err(ScanError::Panic(Issue::new(
+            IssueKind::InternalError,
+            format!("Panic while scanning file: {}", panic_message),
+            HPos::file_start(file_path),
+            &None,
+        )))
+    })
+}
+
+fn scan_file_contents(
+    str_path: &str,
+    file_path: FilePath,
+    file_contents_overlay: Option<&String>,
+    all_custom_issues: &FxHashSet<String>,
+    codebase: &mut CodebaseInfo,
+    interner: &mut ThreadedInterner,
+    empty_name_context: NameContext<'_>,
+    user_defined: bool,
+    is_production_code: bool,
+) -> Result<
+    (
+        FxHashMap<usize, StrId>,
+        (aast::Program<(), ()>, ScouredComments),
+    ),
+    ParserError,
+> {
+    let aast = get_aast_for_path(file_path, str_path, file_contents_overlay);
 
     let aast = match aast {
         Ok(aast) => aast,
@@ -5,7 +5,7 @@ use hakana_analyzer::dataflow::program_analyzer::find_tainted_data;
 use hakana_analyzer::file_analyzer;
 use hakana_logger::Logger;
 use hakana_reflection_info::analysis_result::AnalysisResult;
-use hakana_reflection_info::code_location::{FilePath, HPos};
+use hakana_reflection_info::code_location::FilePath;
 use hakana_reflection_info::codebase_info::CodebaseInfo;
 use hakana_reflection_info::data_flow::graph::{GraphKind, WholeProgramKind};
 use hakana_reflection_info::issue::{Issue, IssueKind};
@@ -74,6 +74,14 @@ pub fn scan_and_analyze_single_file(
     )?;
 
     if matches!(analysis_config.graph_kind, GraphKind::WholeProgram(_)) {
+        for file_taint_summaries in analysis_result.taint_summaries.values() {
+            for taint_summary in file_taint_summaries.values() {
+                analysis_result
+                    .program_dataflow_graph
+                    .add_taint_summary(taint_summary);
+            }
+        }
+
         let issues = find_tainted_data(
             &analysis_result.program_dataflow_graph,
             &analysis_config,
@@ -116,6 +124,7 @@ pub fn get_single_file_codebase(
         scan_file(
             file.as_ref(),
             interned_file_path,
+            None,
             &FxHashSet::default(),
             &mut codebase,
             &mut threaded_interner,
@@ -137,6 +146,7 @@ pub fn get_single_file_codebase(
         scan_file(
             file.as_ref(),
             interned_file_path,
+            None,
             &FxHashSet::default(),
             &mut codebase,
             &mut threaded_interner,
@@ -157,6 +167,7 @@ pub fn get_single_file_codebase(
         scan_file(
             str_path,
             interned_file_path,
+            None,
             &FxHashSet::default(),
             &mut codebase,
             &mut threaded_interner,
@@ -270,29 +281,7 @@ pub fn analyze_single_file(
         analysis_config,
     );
 
-    match file_analyzer.analyze(&aast.0, &mut analysis_result) {
-        Ok(()) => {}
-        Err(err) => {
-            analysis_result.emitted_issues.insert(
-                file_path,
-                vec![Issue::new(
-                    IssueKind::InternalError,
-                    err.0,
-                    HPos {
-                        file_path,
-                        start_offset: 1,
-                        end_offset: 1,
-                        start_line: 1,
-                        end_line: 1,
-                        start_column: 1,
-                        end_column: 1,
-                        insertion_start: None,
-                    },
-                    &None,
-                )],
-            );
-        }
-    }
+    file_analyzer.analyze(&aast.0, &mut analysis_result);
 
     Ok(analysis_result)
 }
@@ -1,4 +1,5 @@
 use hakana_reflection_info::codebase_info::CodebaseInfo;
+use hakana_reflection_info::issue::IssueSeverity;
 use hakana_reflection_info::Interner;
 use hakana_workhorse::wasm::{get_single_file_codebase, scan_and_analyze_single_file};
 use serde_json::json;
@@ -39,7 +40,8 @@ impl ScannerAndAnalyzer {
                 {
                     for issue in issues {
                         issue_json_objects.push(json!({
-                            "severity": "ERROR",
+                            // there's no hakana.json here, so every issue has the default severity
+                            "severity": IssueSeverity::default().to_string(),
                             "line_from": issue.pos.start_line,
                             "line_to": issue.pos.end_line,
                             "type": format!("{}", issue.kind),
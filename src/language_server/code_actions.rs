@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use hakana_analyzer::config::Config;
+use hakana_reflection_info::analysis_result::AnalysisResult;
+use hakana_reflection_info::code_location::FilePath;
+use hakana_reflection_info::issue::{Issue, IssueKind};
+use hakana_workhorse::{analyze_single_file, SuccessfulScanData};
+use rustc_hash::FxHashMap;
+use tower_lsp::lsp_types::*;
+
+use crate::get_range;
+
+pub(crate) fn get_code_actions(
+    scan_data: &SuccessfulScanData,
+    analysis_result: &AnalysisResult,
+    analysis_config: &Config,
+    uri: &Url,
+    file_path: FilePath,
+    file_contents: &str,
+    diagnostics: Vec<Diagnostic>,
+    fix_results: &mut FxHashMap<IssueKind, Option<AnalysisResult>>,
+) -> Vec<CodeActionOrCommand> {
+    let file_issues = analysis_result
+        .emitted_issues
+        .get(&file_path)
+        .into_iter()
+        .chain(analysis_result.emitted_definition_issues.get(&file_path))
+        .flatten()
+        .collect::<Vec<_>>();
+
+    let mut code_actions = vec![];
+
+    for diagnostic in diagnostics {
+        if diagnostic.source.as_deref() != Some("Hakana") {
+            continue;
+        }
+
+        let issue_kind = if let Some(NumberOrString::String(issue_kind)) = &diagnostic.code {
+            if let Ok(issue_kind) =
+                IssueKind::from_str_custom(issue_kind, &analysis_config.all_custom_issues)
+            {
+                issue_kind
+            } else {
+                continue;
+            }
+        } else {
+            continue;
+        };
+
+        let issue = if let Some(issue) = file_issues.iter().find(|issue| {
+            issue.kind == issue_kind
+                && issue.pos.start_line - 1 == diagnostic.range.start.line
+                && issue.pos.start_column as u32 - 1 == diagnostic.range.start.character
+        }) {
+            issue
+        } else {
+            continue;
+        };
+
+        // fixes are computed by reanalyzing the file once per issue kind, and reused until the
+        // next analysis
+        let fix_result = fix_results.entry(issue_kind.clone()).or_insert_with(|| {
+            get_fix_analysis_result(scan_data, analysis_config, file_path, &issue_kind)
+        });
+
+        if let Some(fix_result) = fix_result {
+            let kind_issue_count = file_issues
+                .iter()
+                .filter(|file_issue| file_issue.kind == issue_kind)
+                .count();
+
+            let text_edits = get_fix_edits(
+                fix_result,
+                issue,
+                file_path,
+                file_contents,
+                kind_issue_count == 1,
+            );
+
+            if !text_edits.is_empty() {
+                code_actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                    title: get_fix_title(&issue_kind),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![diagnostic.clone()]),
+                    edit: Some(get_workspace_edit(uri, text_edits)),
+                    is_preferred: Some(true),
+                    ..CodeAction::default()
+                }));
+            }
+        }
+
+        if let Some(insertion_start) = &issue.pos.insertion_start {
+            if let Some(range) = get_range(
+                file_contents,
+                insertion_start.offset,
+                insertion_start.offset,
+            ) {
+                code_actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                    title: format!("Suppress {} here", issue_kind.to_string()),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![diagnostic]),
+                    edit: Some(get_workspace_edit(
+                        uri,
+                        vec![TextEdit {
+                            range,
+                            new_text: issue.get_fixme_comment(insertion_start),
+                        }],
+                    )),
+                    ..CodeAction::default()
+                }));
+            }
+        }
+    }
+
+    code_actions
+}
+
+fn get_fix_analysis_result(
+    scan_data: &SuccessfulScanData,
+    analysis_config: &Config,
+    file_path: FilePath,
+    issue_kind: &IssueKind,
+) -> Option<AnalysisResult> {
+    // keeps the loaded plugins and hakana.json settings, only changing what gets fixed
+    let mut config = analysis_config.clone();
+
+    config.issues_to_fix.insert(issue_kind.clone());
+    config.find_unused_expressions = issue_kind.is_unused_expression();
+    config.find_unused_definitions = issue_kind.is_unused_definition();
+    config.allowed_issues = None;
+
+    let str_path = scan_data.interner.lookup(&file_path.0).to_string();
+
+    analyze_single_file(file_path, &str_path, scan_data, &Arc::new(config))
+}
+
+/// Gets the edits for a given issue from a fix analysis, which can contain fixes for every issue
+/// of the same kind in the file
+fn get_fix_edits(
+    fix_result: &AnalysisResult,
+    issue: &Issue,
+    file_path: FilePath,
+    file_contents: &str,
+    is_only_issue_of_kind: bool,
+) -> Vec<TextEdit> {
+    let overlaps_issue = |start: u32, end: u32| {
+        is_only_issue_of_kind || (start <= issue.pos.end_offset && end >= issue.pos.start_offset)
+    };
+
+    let mut text_edits = vec![];
+
+    if let Some(replacements) = fix_result.replacements.get(&file_path) {
+        for (span, replacement) in replacements {
+            if !overlaps_issue(span.0, span.1) {
+                continue;
+            }
+
+            let (start, end, new_text) = replacement.get_edit(&mut span.clone(), file_contents);
+
+            if let Some(range) = get_range(file_contents, start, end) {
+                text_edits.push(TextEdit { range, new_text });
+            }
+        }
+    }
+
+    if let Some(insertions) = fix_result.insertions.get(&file_path) {
+        for (offset, insertion) in insertions {
+            if !overlaps_issue(*offset, *offset) {
+                continue;
+            }
+
+            if let Some(range) = get_range(file_contents, *offset, *offset) {
+                text_edits.push(TextEdit {
+                    range,
+                    new_text: insertion.join(""),
+                });
+            }
+        }
+    }
+
+    text_edits
+}
+
+fn get_fix_title(issue_kind: &IssueKind) -> String {
+    match issue_kind {
+        IssueKind::UnusedAssignment | IssueKind::UnusedAssignmentStatement => {
+            "Remove unused assignment".to_string()
+        }
+        IssueKind::UnusedClosureParameter => "Rename unused closure parameter".to_string(),
+        IssueKind::NoJoinInAsyncFunction => "Replace HH\\Asio\\join with await".to_string(),
+        IssueKind::UnnecessaryShapesIdx => "Replace Shapes::idx with array access".to_string(),
+        IssueKind::EmptyBlock => "Remove empty block".to_string(),
+        _ => format!("Fix {}", issue_kind.to_string()),
+    }
+}
+
+fn get_workspace_edit(uri: &Url, text_edits: Vec<TextEdit>) -> WorkspaceEdit {
+    WorkspaceEdit {
+        changes: Some(HashMap::from_iter([(uri.clone(), text_edits)])),
+        ..WorkspaceEdit::default()
+    }
+}
@@ -0,0 +1,216 @@
+use std::fs;
+
+use hakana_reflection_info::analysis_result::AnalysisResult;
+use hakana_reflection_info::code_location::{FilePath, HPos};
+use hakana_reflection_info::codebase_info::CodebaseInfo;
+use hakana_reflection_info::StrId;
+use hakana_workhorse::SuccessfulScanData;
+use rustc_hash::FxHashSet;
+use tower_lsp::lsp_types::*;
+
+use crate::{get_range, position_to_offset};
+
+pub fn get_definition(
+    scan_data: &SuccessfulScanData,
+    analysis_result: &AnalysisResult,
+    file_path: FilePath,
+    file_contents: &str,
+    position: Position,
+) -> Option<GotoDefinitionResponse> {
+    let offset = position_to_offset(file_contents, position)?;
+
+    let symbol = get_symbol_at_offset(scan_data, analysis_result, file_path, offset)?;
+
+    let symbol_pos = scan_data.codebase.get_symbol_pos(&symbol)?;
+
+    Some(GotoDefinitionResponse::Scalar(get_location(
+        &symbol_pos,
+        scan_data,
+        file_path,
+        file_contents,
+    )?))
+}
+
+pub(crate) fn get_references(
+    scan_data: &SuccessfulScanData,
+    analysis_result: &AnalysisResult,
+    file_path: FilePath,
+    file_contents: &str,
+    position: Position,
+    include_declaration: bool,
+) -> Option<Vec<Location>> {
+    let offset = position_to_offset(file_contents, position)?;
+
+    let symbol = get_symbol_at_offset(scan_data, analysis_result, file_path, offset)?;
+
+    let mut locations = vec![];
+
+    if include_declaration {
+        if let Some(symbol_pos) = scan_data.codebase.get_symbol_pos(&symbol) {
+            locations.extend(get_location(
+                &symbol_pos,
+                scan_data,
+                file_path,
+                file_contents,
+            ));
+        }
+    }
+
+    // we only need to look at the files containing symbols that reference the given symbol
+    let referencing_files = analysis_result
+        .symbol_references
+        .get_references_to_symbol(symbol)
+        .into_iter()
+        .filter_map(|referencing_symbol| {
+            scan_data
+                .codebase
+                .get_symbol_pos(&(referencing_symbol.0, StrId::EMPTY))
+                .map(|pos| pos.file_path)
+        })
+        .collect::<FxHashSet<_>>();
+
+    for referencing_file in referencing_files {
+        let file_symbol_positions = if let Some(file_symbol_positions) =
+            analysis_result.symbol_positions.get(&referencing_file)
+        {
+            file_symbol_positions
+        } else {
+            continue;
+        };
+
+        let file_path_str = scan_data.interner.lookup(&referencing_file.0);
+
+        let (url, referencing_file_contents) = if let (Ok(url), Some(contents)) = (
+            Url::from_file_path(file_path_str),
+            if referencing_file == file_path {
+                Some(file_contents.to_string())
+            } else {
+                get_file_contents(scan_data, file_path_str)
+            },
+        ) {
+            (url, contents)
+        } else {
+            continue;
+        };
+
+        let mut file_locations = file_symbol_positions
+            .iter()
+            .filter(|(_, referenced_symbol)| **referenced_symbol == symbol)
+            .filter_map(|((start_offset, end_offset), _)| {
+                Some(Location {
+                    uri: url.clone(),
+                    range: get_range(&referencing_file_contents, *start_offset, *end_offset)?,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        file_locations
+            .sort_by_key(|location| (location.range.start.line, location.range.start.character));
+
+        locations.extend(file_locations);
+    }
+
+    Some(locations)
+}
+
+fn get_symbol_at_offset(
+    scan_data: &SuccessfulScanData,
+    analysis_result: &AnalysisResult,
+    file_path: FilePath,
+    offset: u32,
+) -> Option<(StrId, StrId)> {
+    if let Some((_, symbol)) =
+        analysis_result
+            .symbol_positions
+            .get(&file_path)
+            .and_then(|file_symbol_positions| {
+                file_symbol_positions
+                    .iter()
+                    .filter(|((start_offset, end_offset), _)| {
+                        *start_offset <= offset && offset < *end_offset
+                    })
+                    .min_by_key(|((start_offset, end_offset), _)| end_offset - start_offset)
+            })
+    {
+        return Some(*symbol);
+    }
+
+    // otherwise the cursor may be on the name of a declaration
+    get_declaration_at_offset(&scan_data.codebase, file_path, offset)
+}
+
+/// Finds the symbol or class member whose declared name contains the given offset,
+/// only looking at the definitions in that file
+pub(crate) fn get_declaration_at_offset(
+    codebase: &CodebaseInfo,
+    file_path: FilePath,
+    offset: u32,
+) -> Option<(StrId, StrId)> {
+    let contains_offset = |symbol: &(StrId, StrId)| {
+        if let Some(pos) = codebase.get_symbol_pos(symbol) {
+            pos.file_path == file_path && pos.start_offset <= offset && offset < pos.end_offset
+        } else {
+            false
+        }
+    };
+
+    for node in &codebase.files.get(&file_path)?.ast_nodes {
+        if offset < node.start_offset || offset > node.end_offset {
+            continue;
+        }
+
+        let symbol = (node.name, StrId::EMPTY);
+
+        if contains_offset(&symbol) {
+            return Some(symbol);
+        }
+
+        for child in &node.children {
+            let member = (node.name, child.name);
+
+            if contains_offset(&member) {
+                return Some(member);
+            }
+        }
+    }
+
+    None
+}
+
+fn get_location(
+    pos: &HPos,
+    scan_data: &SuccessfulScanData,
+    file_path: FilePath,
+    file_contents: &str,
+) -> Option<Location> {
+    let pos_file_path_str = scan_data.interner.lookup(&pos.file_path.0);
+
+    // positions hold byte columns, but LSP columns are UTF-16 code units
+    let range = if pos.file_path == file_path {
+        get_range(file_contents, pos.start_offset, pos.end_offset)?
+    } else {
+        get_range(
+            &get_file_contents(scan_data, pos_file_path_str)?,
+            pos.start_offset,
+            pos.end_offset,
+        )?
+    };
+
+    Some(Location {
+        uri: Url::from_file_path(pos_file_path_str).ok()?,
+        range,
+    })
+}
+
+// files open in the editor are analyzed using their unsaved contents, so positions refer to those
+fn get_file_contents(scan_data: &SuccessfulScanData, file_path_str: &str) -> Option<String> {
+    if let Some(file_contents) = scan_data
+        .file_system
+        .file_contents_overlays
+        .get(file_path_str)
+    {
+        return Some(file_contents.clone());
+    }
+
+    fs::read_to_string(file_path_str).ok()
+}
@@ -0,0 +1,116 @@
+use hakana_reflection_info::analysis_result::AnalysisResult;
+use hakana_reflection_info::code_location::FilePath;
+use hakana_reflection_info::functionlike_info::FunctionLikeInfo;
+use hakana_reflection_info::{Interner, StrId};
+use hakana_workhorse::SuccessfulScanData;
+use tower_lsp::lsp_types::*;
+
+use crate::definition::get_declaration_at_offset;
+use crate::{get_range, position_to_offset};
+
+pub fn get_hover(
+    scan_data: &SuccessfulScanData,
+    analysis_result: &AnalysisResult,
+    file_path: FilePath,
+    file_contents: &str,
+    position: Position,
+) -> Option<Hover> {
+    let offset = position_to_offset(file_contents, position)?;
+
+    // prefer the innermost expression that contains the cursor
+    if let Some(((start_offset, end_offset), expr_type)) = analysis_result
+        .expr_types
+        .get(&file_path)
+        .and_then(|file_expr_types| {
+            file_expr_types
+                .iter()
+                .filter(|((start_offset, end_offset), _)| {
+                    *start_offset <= offset && offset < *end_offset
+                })
+                .min_by_key(|((start_offset, end_offset), _)| end_offset - start_offset)
+        })
+    {
+        return Some(get_hack_hover(
+            expr_type.get_id(Some(&scan_data.interner)),
+            get_range(file_contents, *start_offset, *end_offset),
+        ));
+    }
+
+    let (classlike_name, method_name) =
+        get_declaration_at_offset(&scan_data.codebase, file_path, offset)?;
+
+    let functionlike_info = scan_data
+        .codebase
+        .functionlike_infos
+        .get(&(classlike_name, method_name))?;
+
+    let name_location = functionlike_info.name_location?;
+
+    Some(get_hack_hover(
+        get_functionlike_signature(
+            functionlike_info,
+            &classlike_name,
+            &method_name,
+            &scan_data.interner,
+        ),
+        get_range(
+            file_contents,
+            name_location.start_offset,
+            name_location.end_offset,
+        ),
+    ))
+}
+
+pub(crate) fn get_functionlike_signature(
+    functionlike_info: &FunctionLikeInfo,
+    classlike_name: &StrId,
+    method_name: &StrId,
+    interner: &Interner,
+) -> String {
+    let params = functionlike_info
+        .params
+        .iter()
+        .map(|param| {
+            format!(
+                "{}{}{}",
+                if param.is_inout { "inout " } else { "" },
+                if let Some(signature_type) = &param.signature_type {
+                    signature_type.get_id(Some(interner)) + " "
+                } else {
+                    "".to_string()
+                },
+                param.name
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        "function {}({}): {}",
+        if method_name.is_empty() {
+            interner.lookup(classlike_name).to_string()
+        } else {
+            format!(
+                "{}::{}",
+                interner.lookup(classlike_name),
+                interner.lookup(method_name)
+            )
+        },
+        params,
+        if let Some(return_type) = &functionlike_info.return_type {
+            return_type.get_id(Some(interner))
+        } else {
+            "mixed".to_string()
+        }
+    )
+}
+
+fn get_hack_hover(contents: String, range: Option<Range>) -> Hover {
+    Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: format!("```hack\n{}\n```", contents),
+        }),
+        range,
+    }
+}
@@ -1,20 +1,38 @@
 use std::error::Error;
+use std::fs;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use hakana_analyzer::config::{self, Config};
 use hakana_analyzer::custom_hook::CustomHook;
 use hakana_logger::{Logger, Verbosity};
 use hakana_reflection_info::analysis_result::AnalysisResult;
+use hakana_reflection_info::code_location::FilePath;
+use hakana_reflection_info::issue::{IssueKind, IssueSeverity};
 use hakana_workhorse::file::FileStatus;
 use hakana_workhorse::{scan_and_analyze_async, SuccessfulScanData};
 use rustc_hash::{FxHashMap, FxHashSet};
+use tokio::sync::Mutex;
 use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer};
 
+mod code_actions;
+pub mod definition;
+pub mod hover;
+
+// how long the unsaved contents must stay unchanged before they're reanalyzed
+const UNSAVED_CHANGES_DEBOUNCE: Duration = Duration::from_millis(300);
+
 #[derive(Debug)]
 pub struct Backend {
+    // shared with the tasks that reanalyze unsaved changes once typing has stopped
+    state: Arc<Mutex<BackendState>>,
+}
+
+#[derive(Debug)]
+struct BackendState {
     client: Client,
     analysis_config: Arc<Config>,
     previous_scan_data: Arc<Option<SuccessfulScanData>>,
@@ -22,18 +40,33 @@ pub struct Backend {
     all_diagnostics: Option<FxHashMap<Url, Vec<Diagnostic>>>,
     file_changes: Option<FxHashMap<String, FileStatus>>,
     files_with_errors: FxHashSet<Url>,
+    file_contents_overlays: FxHashMap<String, String>,
+    unsaved_changes_since: Option<Instant>,
+    // incremented on every unsaved change, so a pending reanalysis knows it's been superseded
+    unsaved_changes_generation: u64,
+    // incremented whenever a new analysis result replaces the previous one
+    analysis_generation: u64,
+    // fix analyses used for code actions, along with the analysis generation they were computed in
+    fix_results: FxHashMap<FilePath, (u64, FxHashMap<IssueKind, Option<AnalysisResult>>)>,
 }
 
 impl Backend {
     pub fn new(client: Client, analysis_config: Arc<Config>) -> Self {
         Self {
-            client,
-            analysis_config,
-            previous_scan_data: Arc::new(None),
-            previous_analysis_result: Arc::new(None),
-            all_diagnostics: None,
-            file_changes: None,
-            files_with_errors: FxHashSet::default(),
+            state: Arc::new(Mutex::new(BackendState {
+                client,
+                analysis_config,
+                previous_scan_data: Arc::new(None),
+                previous_analysis_result: Arc::new(None),
+                all_diagnostics: None,
+                file_changes: None,
+                files_with_errors: FxHashSet::default(),
+                file_contents_overlays: FxHashMap::default(),
+                unsaved_changes_since: None,
+                unsaved_changes_generation: 0,
+                analysis_generation: 0,
+                fix_results: FxHashMap::default(),
+            })),
         }
     }
 }
@@ -41,19 +74,27 @@ impl Backend {
 #[tower_lsp::async_trait(?Send)]
 impl LanguageServer for Backend {
     async fn initialize(&mut self, _: InitializeParams) -> Result<InitializeResult> {
-        self.do_analysis().await;
+        let mut state = self.state.lock().await;
+
+        state.do_analysis().await;
 
         Ok(InitializeResult {
             capabilities: ServerCapabilities {
                 text_document_sync: Some(TextDocumentSyncCapability::Options(
                     TextDocumentSyncOptions {
                         open_close: Some(true),
-                        change: Some(TextDocumentSyncKind::NONE),
+                        change: Some(TextDocumentSyncKind::INCREMENTAL),
                         will_save: Some(false),
                         will_save_wait_until: Some(false),
-                        save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+                        save: Some(TextDocumentSyncSaveOptions::SaveOptions(SaveOptions {
+                            include_text: Some(true),
+                        })),
                     },
                 )),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                definition_provider: Some(OneOf::Left(true)),
+                references_provider: Some(OneOf::Left(true)),
+                code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
                 ..ServerCapabilities::default()
             },
             ..InitializeResult::default()
@@ -61,6 +102,8 @@ impl LanguageServer for Backend {
     }
 
     async fn initialized(&mut self, _: InitializedParams) {
+        let mut state = self.state.lock().await;
+
         let registration = Registration {
             id: "watch-hack-files".to_string(),
             method: "workspace/didChangeWatchedFiles".to_string(),
@@ -83,21 +126,25 @@ impl LanguageServer for Backend {
 
         let registrations = vec![registration];
 
-        self.client
+        state
+            .client
             .register_capability(registrations)
             .await
             .unwrap();
 
-        self.emit_issues().await;
+        state.emit_issues().await;
 
-        self.all_diagnostics = None;
+        state.all_diagnostics = None;
 
-        self.client
+        state
+            .client
             .log_message(MessageType::INFO, "server initialized!")
             .await;
     }
 
     async fn did_change_watched_files(&mut self, params: DidChangeWatchedFilesParams) {
+        let mut state = self.state.lock().await;
+
         let mut new_file_statuses = FxHashMap::default();
 
         for file_event in params.changes {
@@ -120,34 +167,293 @@ impl LanguageServer for Backend {
             }
         }
 
-        // self.client
+        // state.client
         //     .log_message(
         //         MessageType::INFO,
         //         format!("adding changes {:?}", new_file_statuses),
         //     )
         //     .await;
 
-        if let Some(ref mut existing_file_changes) = self.file_changes {
-            existing_file_changes.extend(new_file_statuses);
-        } else {
-            self.file_changes = Some(new_file_statuses);
-        }
+        state.add_file_changes(new_file_statuses);
 
         if Path::new(".git/index.lock").exists() {
-            self.client
+            state
+                .client
                 .log_message(MessageType::INFO, "Waiting a sec while git is doing stuff")
                 .await;
         } else {
-            // self.client
+            // state.client
             //     .log_message(
             //         MessageType::INFO,
-            //         format!("analyzing changes {:?}", self.file_changes),
+            //         format!("analyzing changes {:?}", state.file_changes),
             //     )
             //     .await;
-            self.do_analysis().await;
-            self.file_changes = None;
-            self.emit_issues().await;
+            state.do_analysis().await;
+            state.file_changes = None;
+            state.emit_issues().await;
+        }
+    }
+
+    async fn did_open(&mut self, params: DidOpenTextDocumentParams) {
+        let mut state = self.state.lock().await;
+
+        if let Some(file_path) = get_file_path(&params.text_document.uri) {
+            state
+                .file_contents_overlays
+                .insert(file_path, params.text_document.text);
+        }
+    }
+
+    async fn did_change(&mut self, params: DidChangeTextDocumentParams) {
+        let mut state_guard = self.state.lock().await;
+        let state = &mut *state_guard;
+
+        let file_path = if let Some(file_path) = get_file_path(&params.text_document.uri) {
+            file_path
+        } else {
+            return;
+        };
+
+        let file_contents =
+            if let Some(file_contents) = state.file_contents_overlays.get_mut(&file_path) {
+                file_contents
+            } else {
+                return;
+            };
+
+        for content_change in params.content_changes {
+            if !apply_content_change(file_contents, content_change) {
+                // we've lost track of the editor's contents, so we fall back to the file on
+                // disk until the next save sends us the full text again
+                state.file_contents_overlays.remove(&file_path);
+
+                state
+                    .client
+                    .log_message(
+                        MessageType::ERROR,
+                        format!(
+                            "Could not apply change to {}, ignoring unsaved changes until it's saved",
+                            file_path
+                        ),
+                    )
+                    .await;
+                break;
+            }
+        }
+
+        state.add_file_changes(FxHashMap::from_iter([(
+            file_path,
+            FileStatus::Modified(0, 0),
+        )]));
+
+        state.unsaved_changes_since.get_or_insert_with(Instant::now);
+        state.unsaved_changes_generation += 1;
+
+        let generation = state.unsaved_changes_generation;
+
+        drop(state_guard);
+
+        // reanalyze once there have been no further changes for the debounce interval
+        let state = self.state.clone();
+
+        tokio::task::spawn_local(async move {
+            tokio::time::sleep(UNSAVED_CHANGES_DEBOUNCE).await;
+
+            let mut state = state.lock().await;
+
+            if state.unsaved_changes_generation == generation {
+                state.analyze_unsaved_changes().await;
+            }
+        });
+    }
+
+    async fn did_save(&mut self, params: DidSaveTextDocumentParams) {
+        let mut state = self.state.lock().await;
+
+        // resync the overlay with the saved contents, in case an earlier change couldn't be applied
+        if let (Some(file_path), Some(text)) =
+            (get_file_path(&params.text_document.uri), params.text)
+        {
+            if state
+                .file_contents_overlays
+                .insert(file_path.clone(), text)
+                .is_none()
+            {
+                state.add_file_changes(FxHashMap::from_iter([(
+                    file_path,
+                    FileStatus::Modified(0, 0),
+                )]));
+
+                state.unsaved_changes_since.get_or_insert_with(Instant::now);
+            }
         }
+
+        state.analyze_unsaved_changes().await;
+    }
+
+    async fn did_close(&mut self, params: DidCloseTextDocumentParams) {
+        let mut state = self.state.lock().await;
+
+        let file_path = if let Some(file_path) = get_file_path(&params.text_document.uri) {
+            file_path
+        } else {
+            return;
+        };
+
+        // any unsaved changes are discarded, so we go back to the contents on disk
+        if state.file_contents_overlays.remove(&file_path).is_some() {
+            state.add_file_changes(FxHashMap::from_iter([(
+                file_path,
+                FileStatus::Modified(0, 0),
+            )]));
+
+            state.unsaved_changes_since.get_or_insert_with(Instant::now);
+            state.analyze_unsaved_changes().await;
+        }
+    }
+
+    async fn hover(&mut self, params: HoverParams) -> Result<Option<Hover>> {
+        let mut state = self.state.lock().await;
+
+        state.analyze_unsaved_changes().await;
+
+        let text_document_position = params.text_document_position_params;
+
+        let (scan_data, analysis_result) = if let Some(analysis) = state.get_analysis() {
+            analysis
+        } else {
+            return Ok(None);
+        };
+
+        let (file_path, file_contents) = if let Some(file) =
+            state.get_file(scan_data, &text_document_position.text_document.uri)
+        {
+            file
+        } else {
+            return Ok(None);
+        };
+
+        Ok(hover::get_hover(
+            scan_data,
+            analysis_result,
+            file_path,
+            &file_contents,
+            text_document_position.position,
+        ))
+    }
+
+    async fn goto_definition(
+        &mut self,
+        params: GotoDefinitionParams,
+    ) -> Result<Option<GotoDefinitionResponse>> {
+        let mut state = self.state.lock().await;
+
+        state.analyze_unsaved_changes().await;
+
+        let text_document_position = params.text_document_position_params;
+
+        let (scan_data, analysis_result) = if let Some(analysis) = state.get_analysis() {
+            analysis
+        } else {
+            return Ok(None);
+        };
+
+        let (file_path, file_contents) = if let Some(file) =
+            state.get_file(scan_data, &text_document_position.text_document.uri)
+        {
+            file
+        } else {
+            return Ok(None);
+        };
+
+        Ok(definition::get_definition(
+            scan_data,
+            analysis_result,
+            file_path,
+            &file_contents,
+            text_document_position.position,
+        ))
+    }
+
+    async fn references(&mut self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
+        let mut state = self.state.lock().await;
+
+        state.analyze_unsaved_changes().await;
+
+        let text_document_position = params.text_document_position;
+
+        let (scan_data, analysis_result) = if let Some(analysis) = state.get_analysis() {
+            analysis
+        } else {
+            return Ok(None);
+        };
+
+        let (file_path, file_contents) = if let Some(file) =
+            state.get_file(scan_data, &text_document_position.text_document.uri)
+        {
+            file
+        } else {
+            return Ok(None);
+        };
+
+        Ok(definition::get_references(
+            scan_data,
+            analysis_result,
+            file_path,
+            &file_contents,
+            text_document_position.position,
+            params.context.include_declaration,
+        ))
+    }
+
+    async fn code_action(
+        &mut self,
+        params: CodeActionParams,
+    ) -> Result<Option<CodeActionResponse>> {
+        let mut state = self.state.lock().await;
+
+        state.analyze_unsaved_changes().await;
+
+        let state = &mut *state;
+
+        // borrowed field by field so the fix result cache can be updated alongside
+        let (scan_data, analysis_result) = if let (Some(scan_data), Some(analysis_result)) =
+            (&*state.previous_scan_data, &*state.previous_analysis_result)
+        {
+            (scan_data, analysis_result)
+        } else {
+            return Ok(None);
+        };
+
+        let (file_path, file_contents) =
+            if let Some(file) = state.get_file(scan_data, &params.text_document.uri) {
+                file
+            } else {
+                return Ok(None);
+            };
+
+        let analysis_generation = state.analysis_generation;
+
+        let (fix_results_generation, fix_results) = state
+            .fix_results
+            .entry(file_path)
+            .or_insert_with(|| (analysis_generation, FxHashMap::default()));
+
+        if *fix_results_generation != analysis_generation {
+            *fix_results_generation = analysis_generation;
+            fix_results.clear();
+        }
+
+        Ok(Some(code_actions::get_code_actions(
+            scan_data,
+            analysis_result,
+            &state.analysis_config,
+            &params.text_document.uri,
+            file_path,
+            &file_contents,
+            params.context.diagnostics,
+            fix_results,
+        )))
     }
 
     async fn shutdown(&mut self) -> Result<()> {
@@ -155,7 +461,49 @@ impl LanguageServer for Backend {
     }
 }
 
-impl Backend {
+impl BackendState {
+    fn get_analysis(&self) -> Option<(&SuccessfulScanData, &AnalysisResult)> {
+        if let (Some(scan_data), Some(analysis_result)) =
+            (&*self.previous_scan_data, &*self.previous_analysis_result)
+        {
+            Some((scan_data, analysis_result))
+        } else {
+            None
+        }
+    }
+
+    fn get_file(&self, scan_data: &SuccessfulScanData, uri: &Url) -> Option<(FilePath, String)> {
+        let file_path_str = get_file_path(uri)?;
+        let file_path = FilePath(scan_data.interner.get(&file_path_str)?);
+
+        let file_contents =
+            if let Some(file_contents) = self.file_contents_overlays.get(&file_path_str) {
+                file_contents.clone()
+            } else {
+                fs::read_to_string(&file_path_str).ok()?
+            };
+
+        Some((file_path, file_contents))
+    }
+
+    fn add_file_changes(&mut self, new_file_statuses: FxHashMap<String, FileStatus>) {
+        if let Some(ref mut existing_file_changes) = self.file_changes {
+            existing_file_changes.extend(new_file_statuses);
+        } else {
+            self.file_changes = Some(new_file_statuses);
+        }
+    }
+
+    async fn analyze_unsaved_changes(&mut self) {
+        if self.unsaved_changes_since.is_none() || self.file_changes.is_none() {
+            return;
+        }
+
+        self.do_analysis().await;
+        self.file_changes = None;
+        self.emit_issues().await;
+    }
+
     async fn do_analysis(&mut self) {
         let previous_scan_data = self.previous_scan_data.clone();
         let previous_analysis_result = self.previous_analysis_result.clone();
@@ -163,7 +511,13 @@ impl Backend {
         self.previous_scan_data = Arc::new(None);
         self.previous_analysis_result = Arc::new(None);
 
-        let successful_scan_data = Arc::try_unwrap(previous_scan_data).unwrap();
+        let mut successful_scan_data = Arc::try_unwrap(previous_scan_data).unwrap();
+
+        // files open in the editor are analyzed using their unsaved contents
+        if let Some(ref mut successful_scan_data) = successful_scan_data {
+            successful_scan_data.file_system.file_contents_overlays =
+                self.file_contents_overlays.clone();
+        }
 
         let analysis_result = Arc::try_unwrap(previous_analysis_result).unwrap();
 
@@ -192,6 +546,7 @@ impl Backend {
         .await;
 
         self.file_changes = None;
+        self.unsaved_changes_since = None;
 
         match result {
             Ok((analysis_result, successful_scan_data)) => {
@@ -222,7 +577,16 @@ impl Backend {
                                     character: emitted_issue.pos.end_column as u32 - 1,
                                 },
                             },
-                            Some(DiagnosticSeverity::ERROR),
+                            Some(
+                                match self.analysis_config.get_issue_severity(
+                                    emitted_issue,
+                                    &successful_scan_data.interner,
+                                ) {
+                                    IssueSeverity::Error => DiagnosticSeverity::ERROR,
+                                    IssueSeverity::Warning => DiagnosticSeverity::WARNING,
+                                    IssueSeverity::Info => DiagnosticSeverity::INFORMATION,
+                                },
+                            ),
                             Some(NumberOrString::String(emitted_issue.kind.to_string())),
                             Some("Hakana".to_string()),
                             emitted_issue.description.clone(),
@@ -253,6 +617,7 @@ impl Backend {
                 }
 
                 self.all_diagnostics = Some(all_diagnostics);
+                self.analysis_generation += 1;
                 self.previous_scan_data = Arc::new(Some(successful_scan_data));
                 self.previous_analysis_result = Arc::new(Some(analysis_result));
             }
@@ -323,8 +688,9 @@ pub fn get_config(
     config.find_unused_definitions = true;
     config.ignore_mixed_issues = true;
     config.ast_diff = true;
+    config.collect_expr_types = true;
 
-    config.hooks = plugins;
+    config.hooks = plugins.into_iter().map(Arc::from).collect();
 
     let config_path_str = format!("{}/hakana.json", cwd);
 
@@ -336,3 +702,107 @@ pub fn get_config(
 
     Ok(config)
 }
+
+/// Converts an LSP position, whose character is counted in UTF-16 code units, into a byte offset
+pub(crate) fn position_to_offset(file_contents: &str, position: Position) -> Option<u32> {
+    let mut offset = 0;
+    let mut line_count = 0;
+
+    for (i, line) in file_contents.split_inclusive('\n').enumerate() {
+        if i as u32 == position.line {
+            return Some(offset + utf16_column_to_byte_column(line, position.character)?);
+        }
+
+        offset += line.len() as u32;
+        line_count += 1;
+    }
+
+    // the position can also be at the start of the empty line after a trailing newline
+    if position.line == line_count
+        && position.character == 0
+        && (file_contents.is_empty() || file_contents.ends_with('\n'))
+    {
+        return Some(offset);
+    }
+
+    None
+}
+
+fn utf16_column_to_byte_column(line: &str, character: u32) -> Option<u32> {
+    let mut utf16_column = 0;
+
+    for (byte_column, c) in line.char_indices() {
+        if utf16_column == character {
+            return Some(byte_column as u32);
+        }
+
+        // positions can't point into the middle of a character
+        if utf16_column > character {
+            return None;
+        }
+
+        utf16_column += c.len_utf16() as u32;
+    }
+
+    if utf16_column == character {
+        Some(line.len() as u32)
+    } else {
+        None
+    }
+}
+
+/// Converts a byte offset into an LSP position, whose character is counted in UTF-16 code units
+pub(crate) fn offset_to_position(file_contents: &str, offset: u32) -> Option<Position> {
+    let preceding = file_contents.get(..offset as usize)?;
+
+    let line = preceding.matches('\n').count() as u32;
+    let line_start = preceding.rfind('\n').map(|i| i + 1).unwrap_or(0);
+
+    Some(Position {
+        line,
+        character: preceding[line_start..].encode_utf16().count() as u32,
+    })
+}
+
+pub(crate) fn get_range(file_contents: &str, start_offset: u32, end_offset: u32) -> Option<Range> {
+    Some(Range {
+        start: offset_to_position(file_contents, start_offset)?,
+        end: offset_to_position(file_contents, end_offset)?,
+    })
+}
+
+fn get_file_path(uri: &Url) -> Option<String> {
+    Some(uri.to_file_path().ok()?.to_str()?.to_string())
+}
+
+/// Applies an incremental (or full) document change, returning false if the change
+/// does not fit the current contents
+fn apply_content_change(
+    file_contents: &mut String,
+    content_change: TextDocumentContentChangeEvent,
+) -> bool {
+    let range = if let Some(range) = content_change.range {
+        range
+    } else {
+        *file_contents = content_change.text;
+        return true;
+    };
+
+    if let (Some(start_offset), Some(end_offset)) = (
+        position_to_offset(file_contents, range.start),
+        position_to_offset(file_contents, range.end),
+    ) {
+        let (start_offset, end_offset) = (start_offset as usize, end_offset as usize);
+
+        if start_offset <= end_offset
+            && end_offset <= file_contents.len()
+            && file_contents.is_char_boundary(start_offset)
+            && file_contents.is_char_boundary(end_offset)
+        {
+            file_contents.replace_range(start_offset..end_offset, &content_change.text);
+            return true;
+        }
+    }
+
+    false
+}
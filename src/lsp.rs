@@ -48,5 +48,9 @@ async fn main() {
     };
 
     let (service, socket) = LspService::new(|client| Backend::new(client, config));
-    Server::new(stdin, stdout, socket).serve(service).await;
+
+    // the backend spawns local tasks to reanalyze unsaved changes
+    tokio::task::LocalSet::new()
+        .run_until(Server::new(stdin, stdout, socket).serve(service))
+        .await;
 }
@@ -0,0 +1,67 @@
+//! A runtime plugin loaded by the tests in `tests/inference/Plugin`, which reports calls to
+//! `debug_dump` with a custom issue.
+
+use hakana_analyzer::plugin_hook::{
+    HakanaPlugin, HakanaPluginExprData, HakanaPluginIssueSink, HakanaPluginStr,
+    HAKANA_PLUGIN_ABI_VERSION,
+};
+
+const DEBUG_DUMP_ISSUE: &str = "DebugDump";
+
+/// # Safety
+///
+/// Hakana only calls this with the ABI version it supports, and keeps the returned plugin
+/// for as long as the library is loaded
+#[no_mangle]
+pub unsafe extern "C" fn hakana_plugin_init(abi_version: u32) -> *const HakanaPlugin {
+    if abi_version != HAKANA_PLUGIN_ABI_VERSION {
+        return std::ptr::null();
+    }
+
+    // raw pointers aren't Sync, so the plugin can't be a static
+    let custom_issue_names = Box::leak(Box::new([to_plugin_str(DEBUG_DUMP_ISSUE)]));
+
+    Box::leak(Box::new(HakanaPlugin {
+        abi_version: HAKANA_PLUGIN_ABI_VERSION,
+        custom_issue_names: custom_issue_names.as_ptr(),
+        custom_issue_names_len: custom_issue_names.len(),
+        after_expr_analysis: Some(after_expr_analysis),
+        after_argument_analysis: None,
+    }))
+}
+
+extern "C" fn after_expr_analysis(
+    data: *const HakanaPluginExprData,
+    sink: *const HakanaPluginIssueSink,
+) {
+    let (data, sink) = unsafe { (&*data, &*sink) };
+
+    if unsafe { from_plugin_str(&data.callee) } != "debug_dump" {
+        return;
+    }
+
+    (sink.add_issue)(
+        sink.context,
+        to_plugin_str(DEBUG_DUMP_ISSUE),
+        to_plugin_str("debug_dump calls should not be committed"),
+        data.pos,
+    );
+}
+
+fn to_plugin_str(str: &'static str) -> HakanaPluginStr {
+    HakanaPluginStr {
+        ptr: str.as_ptr(),
+        len: str.len(),
+    }
+}
+
+/// # Safety
+///
+/// The string must point to `len` readable bytes, or be null
+unsafe fn from_plugin_str(str: &HakanaPluginStr) -> &str {
+    if str.ptr.is_null() {
+        return "";
+    }
+
+    std::str::from_utf8(std::slice::from_raw_parts(str.ptr, str.len)).unwrap_or_default()
+}